@@ -0,0 +1,191 @@
+//! Packed binary morphology against the previous per-pixel implementations,
+//! which are kept here as the baseline
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use image::{GrayImage, Luma};
+use image_proc::modifications::morphological::{
+    convex_hull::ConvexHull, dilation::Dilation, erosion::Erosion, hmt::HitOrMissTransform, Mask,
+    MorphologicalTransform, StructuringElement,
+};
+
+const SIZE: u32 = 256;
+
+fn is_foreground(pixel: &Luma<u8>) -> bool {
+    pixel[0] > 128
+}
+
+/// Blobs of different sizes, so that every operation has work to do
+fn blobs() -> GrayImage {
+    GrayImage::from_fn(SIZE, SIZE, |x, y| {
+        let (x, y) = (x as f64, y as f64);
+        let value = (x / 9.0).sin() * (y / 13.0).cos() + (x / 31.0 + y / 23.0).sin();
+        Luma([if value > 0.6 { 255 } else { 0 }])
+    })
+}
+
+/// Separate shapes for the convex hull
+fn shapes() -> GrayImage {
+    GrayImage::from_fn(SIZE, SIZE, |x, y| {
+        let ring = {
+            let (dx, dy) = (x as f64 - 70.0, y as f64 - 70.0);
+            (30.0..40.0).contains(&dx.hypot(dy)) && dx < 20.0
+        };
+        let cross = (180..190).contains(&x) && (130..230).contains(&y)
+            || (140..230).contains(&x) && (170..180).contains(&y);
+        Luma([if ring || cross { 255 } else { 0 }])
+    })
+}
+
+fn previous_dilation(mask: &Mask, image: &mut GrayImage) {
+    let mut new_image = GrayImage::new(image.width(), image.height());
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if !is_foreground(pixel) {
+            continue;
+        }
+        let mask = mask | &Mask::from_image(&new_image, x, y);
+        mask.write_to_image(&mut new_image, x, y);
+    }
+    *image = new_image;
+}
+
+fn previous_erosion(mask: &Mask, image: &mut GrayImage) {
+    let mut new_image = GrayImage::new(image.width(), image.height());
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if is_foreground(pixel) && &(mask & &Mask::from_image(image, x, y)) == mask {
+            new_image.put_pixel(x, y, Luma([255]));
+        }
+    }
+    *image = new_image;
+}
+
+/// Dilation by placing every offset of the element at every foreground pixel
+fn per_pixel_dilation(element: &StructuringElement, image: &mut GrayImage) {
+    let offsets: Vec<(i32, i32)> = element.offsets().collect();
+    let mut new_image = GrayImage::new(image.width(), image.height());
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if !is_foreground(pixel) {
+            continue;
+        }
+        for &(dx, dy) in &offsets {
+            let (x, y) = (x as i64 + dx as i64, y as i64 + dy as i64);
+            if (0..image.width() as i64).contains(&x) && (0..image.height() as i64).contains(&y) {
+                new_image.put_pixel(x as u32, y as u32, Luma([255]));
+            }
+        }
+    }
+    *image = new_image;
+}
+
+fn previous_matching_pixels(hit: &Mask, miss: &Mask, image: &GrayImage) -> Vec<(u32, u32)> {
+    image
+        .enumerate_pixels()
+        .map(|(x, y, _)| (x, y))
+        .filter(|&(x, y)| {
+            let image_mask = &Mask::from_image(image, x, y);
+            hit == &(hit & image_mask) && miss == &(miss & &(!image_mask))
+        })
+        .collect()
+}
+
+/// Convex hull rescanning the whole image until no pixel is added
+fn previous_convex_hull(image: &mut GrayImage) {
+    let elements = [0b001001001, 0b000000111, 0b100100100, 0b111000000];
+    let centre = Mask::from_raw_data(0b000010000);
+    for hit in elements.map(Mask::from_raw_data) {
+        loop {
+            let matched = previous_matching_pixels(&hit, &centre, image);
+            if matched.is_empty() {
+                break;
+            }
+            for (x, y) in matched {
+                image.put_pixel(x, y, Luma([255]));
+            }
+        }
+    }
+}
+
+/// The packed operations must give the results of the previous ones for every 3x3 mask
+fn assert_same_results(image: &GrayImage) {
+    for data in 0..512 {
+        let mask = Mask::from_raw_data(data);
+        let (mut previous, mut packed) = (image.clone(), image.clone());
+        previous_dilation(&mask, &mut previous);
+        Dilation::new(mask.clone()).apply_morph_operation(&mut packed);
+        assert!(previous == packed, "Dilation by {:09b} differs", data);
+
+        let (mut previous, mut packed) = (image.clone(), image.clone());
+        previous_erosion(&mask, &mut previous);
+        Erosion::new(mask).apply_morph_operation(&mut packed);
+        assert!(previous == packed, "Erosion by {:09b} differs", data);
+    }
+}
+
+fn small_elements(c: &mut Criterion) {
+    let image = blobs();
+    assert_same_results(&image);
+    let mask = Mask::from_raw_data(0b111111111);
+    let mut group = c.benchmark_group("3x3");
+    group.bench_function("dilation/previous", |b| {
+        b.iter(|| previous_dilation(&mask, &mut image.clone()))
+    });
+    group.bench_function("dilation/packed", |b| {
+        let dilation = Dilation::new(mask.clone());
+        b.iter(|| dilation.apply_morph_operation(&mut image.clone()))
+    });
+    group.bench_function("erosion/previous", |b| {
+        b.iter(|| previous_erosion(&mask, &mut image.clone()))
+    });
+    group.bench_function("erosion/packed", |b| {
+        let erosion = Erosion::new(mask.clone());
+        b.iter(|| erosion.apply_morph_operation(&mut image.clone()))
+    });
+    let hit = Mask::from_raw_data(0b000011010);
+    let miss = Mask::from_raw_data(0b100000001);
+    group.bench_function("hmt/previous", |b| {
+        b.iter(|| previous_matching_pixels(&hit, &miss, &image))
+    });
+    group.bench_function("hmt/packed", |b| {
+        let transform = HitOrMissTransform::new(hit.clone(), miss.clone());
+        b.iter(|| transform.apply_morph_operation(&mut image.clone()))
+    });
+    group.finish();
+}
+
+fn large_elements(c: &mut Criterion) {
+    let image = blobs();
+    let mut group = c.benchmark_group("large");
+    group.sample_size(20);
+    let elements = [
+        ("rectangle 31x31", StructuringElement::rectangle(31, 31)),
+        ("disk 10", StructuringElement::disk(10)),
+        ("line 25 at 30", StructuringElement::line(25, 30.0)),
+    ];
+    for (name, element) in elements {
+        group.bench_with_input(
+            BenchmarkId::new("per pixel", name),
+            &element,
+            |b, element| b.iter(|| per_pixel_dilation(element, &mut image.clone())),
+        );
+        group.bench_with_input(BenchmarkId::new("packed", name), &element, |b, element| {
+            let dilation = Dilation::from_element(element.clone());
+            b.iter(|| dilation.apply_morph_operation(&mut image.clone()))
+        });
+    }
+    group.finish();
+}
+
+fn convex_hull(c: &mut Criterion) {
+    let image = shapes();
+    let mut group = c.benchmark_group("convex hull");
+    group.sample_size(10);
+    group.bench_function("rescanning", |b| {
+        b.iter(|| previous_convex_hull(&mut image.clone()))
+    });
+    group.bench_function("worklist", |b| {
+        b.iter(|| ConvexHull.apply_morph_operation(&mut image.clone()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, small_elements, large_elements, convex_hull);
+criterion_main!(benches);
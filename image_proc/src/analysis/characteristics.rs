@@ -3,7 +3,12 @@ use image::RgbImage;
 use num::pow::Pow;
 
 pub trait Characteristic {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String>;
+    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
+        self.analyze_histogram(&Histogram::new(image))
+    }
+
+    /// Same as `analyze`, for an image whose histogram is already computed
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String>;
 }
 
 pub struct CompositeCharacteristic {
@@ -19,10 +24,10 @@ impl CompositeCharacteristic {
 }
 
 impl Characteristic for CompositeCharacteristic {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String> {
         let mut result = String::new();
         for characteristic in &self.characteristics {
-            result.push_str(&characteristic.analyze(image)?);
+            result.push_str(&characteristic.analyze_histogram(histogram)?);
             result.push_str("\n");
         }
         Ok(result)
@@ -32,22 +37,18 @@ impl Characteristic for CompositeCharacteristic {
 pub struct Mean;
 
 impl Mean {
-    fn analyze(image: &RgbImage) -> f64 {
-        let histogram = Histogram::new(image);
-        let mut sum: f64 = 0.0;
-        for channel in 0..3 {
-            for luma in 0..=255 {
-                sum += luma as f64 * histogram[channel][luma] as f64;
-            }
-        }
-        let mean = sum / (image.width() * image.height() * 3) as f64;
-        mean
+    pub fn analyze(image: &RgbImage) -> f64 {
+        Self::from_histogram(&Histogram::new(image))
+    }
+
+    fn from_histogram(histogram: &Histogram) -> f64 {
+        histogram.combined().mean()
     }
 }
 
 impl Characteristic for Mean {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
-        let mean = Self::analyze(image);
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String> {
+        let mean = Self::from_histogram(histogram);
         Ok(format!("{:10} {:6.3}", "Mean:", mean))
     }
 }
@@ -55,23 +56,18 @@ impl Characteristic for Mean {
 pub struct Variance;
 
 impl Variance {
-    fn analyze(image: &RgbImage) -> f64 {
-        let histogram = Histogram::new(image);
-        let mean = Mean::analyze(image);
-        let mut sum: f64 = 0.0;
-        for channel in 0..3 {
-            for luma in 0..=255 {
-                sum += f64::pow(luma as f64 - mean, 2.0) * histogram[channel][luma] as f64;
-            }
-        }
-        let variance = sum / (image.width() * image.height() * 3) as f64;
-        variance
+    pub fn analyze(image: &RgbImage) -> f64 {
+        Self::from_histogram(&Histogram::new(image))
+    }
+
+    fn from_histogram(histogram: &Histogram) -> f64 {
+        histogram.combined().central_moment(2)
     }
 }
 
 impl Characteristic for Variance {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
-        let variance = Self::analyze(image);
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String> {
+        let variance = Self::from_histogram(histogram);
         Ok(format!("{:10} {:6.3}", "Variance:", variance))
     }
 }
@@ -79,16 +75,20 @@ impl Characteristic for Variance {
 pub struct StandardDeviation;
 
 impl StandardDeviation {
-    fn analyze(image: &RgbImage) -> f64 {
-        let variance = Variance::analyze(image);
+    pub fn analyze(image: &RgbImage) -> f64 {
+        Self::from_histogram(&Histogram::new(image))
+    }
+
+    fn from_histogram(histogram: &Histogram) -> f64 {
+        let variance = Variance::from_histogram(histogram);
         let std_deviation = f64::sqrt(variance);
         std_deviation
     }
 }
 
 impl Characteristic for StandardDeviation {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
-        let std_deviation = Self::analyze(image);
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String> {
+        let std_deviation = Self::from_histogram(histogram);
         Ok(format!(
             "{:10} {:6.3}",
             "Standard Deviation:", std_deviation
@@ -99,17 +99,21 @@ impl Characteristic for StandardDeviation {
 pub struct VarianceCoefficient1;
 
 impl VarianceCoefficient1 {
-    fn analyze(image: &RgbImage) -> f64 {
-        let mean = Mean::analyze(image);
-        let std_deviation = StandardDeviation::analyze(image);
+    pub fn analyze(image: &RgbImage) -> f64 {
+        Self::from_histogram(&Histogram::new(image))
+    }
+
+    fn from_histogram(histogram: &Histogram) -> f64 {
+        let mean = Mean::from_histogram(histogram);
+        let std_deviation = StandardDeviation::from_histogram(histogram);
         let var_coe_1 = std_deviation / mean;
         var_coe_1
     }
 }
 
 impl Characteristic for VarianceCoefficient1 {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
-        let var_coe_1 = Self::analyze(image);
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String> {
+        let var_coe_1 = Self::from_histogram(histogram);
         Ok(format!(
             "{:10} {:6.3}",
             "Variance Coefficient I:", var_coe_1
@@ -120,25 +124,19 @@ impl Characteristic for VarianceCoefficient1 {
 pub struct AsymmetryCoefficient;
 
 impl AsymmetryCoefficient {
-    fn analyze(image: &RgbImage) -> f64 {
-        let histogram = Histogram::new(image);
-        let mean = Mean::analyze(image);
-        let std_deviation = StandardDeviation::analyze(image);
-        let mut sum: f64 = 0.0;
-        for channel in 0..3 {
-            for luma in 0..=255 {
-                sum += f64::pow(luma as f64 - mean, 3.0) * histogram[channel][luma] as f64;
-            }
-        }
-        let asymmetry =
-            sum / (f64::pow(std_deviation, 3) * (image.width() * image.height() * 3) as f64);
-        asymmetry
+    pub fn analyze(image: &RgbImage) -> f64 {
+        Self::from_histogram(&Histogram::new(image))
+    }
+
+    fn from_histogram(histogram: &Histogram) -> f64 {
+        let std_deviation = StandardDeviation::from_histogram(histogram);
+        histogram.combined().central_moment(3) / f64::pow(std_deviation, 3)
     }
 }
 
 impl Characteristic for AsymmetryCoefficient {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
-        let asymmetry = Self::analyze(image);
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String> {
+        let asymmetry = Self::from_histogram(histogram);
         Ok(format!("{:10} {:6.3}", "Asymmetry Coefficient:", asymmetry))
     }
 }
@@ -146,24 +144,27 @@ impl Characteristic for AsymmetryCoefficient {
 pub struct FlatteningCoefficient;
 
 impl FlatteningCoefficient {
-    fn analyze(image: &RgbImage) -> f64 {
-        let histogram = Histogram::new(image);
-        let mean = Mean::analyze(image);
-        let std_deviation = StandardDeviation::analyze(image);
+    pub fn analyze(image: &RgbImage) -> f64 {
+        Self::from_histogram(&Histogram::new(image))
+    }
+
+    fn from_histogram(histogram: &Histogram) -> f64 {
+        let mean = Mean::from_histogram(histogram);
+        let std_deviation = StandardDeviation::from_histogram(histogram);
         let mut sum: f64 = 0.0;
         for channel in 0..3 {
             for luma in 0..=255 {
                 sum += f64::pow(luma as f64 - mean, 4.0) * histogram[channel][luma] as f64 - 3.0;
             }
         }
-        let flat = sum / (f64::pow(std_deviation, 4) * (image.width() * image.height() * 3) as f64);
+        let flat = sum / (f64::pow(std_deviation, 4) * (histogram.pixel_count() * 3) as f64);
         flat
     }
 }
 
 impl Characteristic for FlatteningCoefficient {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
-        let flat = Self::analyze(image);
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String> {
+        let flat = Self::from_histogram(histogram);
         Ok(format!("{:10} {:6.3}", "Flattening Coefficient:", flat))
     }
 }
@@ -171,15 +172,18 @@ impl Characteristic for FlatteningCoefficient {
 pub struct VarianceCoefficient2;
 
 impl VarianceCoefficient2 {
-    fn analyze(image: &RgbImage) -> f64 {
-        let histogram = Histogram::new(image);
+    pub fn analyze(image: &RgbImage) -> f64 {
+        Self::from_histogram(&Histogram::new(image))
+    }
+
+    fn from_histogram(histogram: &Histogram) -> f64 {
         let mut sum: f64 = 0.0;
         for channel in 0..3 {
             for luma in 0..=255 {
                 sum += f64::pow(histogram[channel][luma] as f64, 2) as f64;
             }
         }
-        let image_size = image.width() * image.height();
+        let image_size = histogram.pixel_count();
         let n2 = f64::pow(image_size as f64, 2);
         let var2 = sum / (n2 * 3.0);
         var2
@@ -187,8 +191,8 @@ impl VarianceCoefficient2 {
 }
 
 impl Characteristic for VarianceCoefficient2 {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
-        let var2 = Self::analyze(image);
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String> {
+        let var2 = Self::from_histogram(histogram);
         Ok(format!("{:10} {:6.6}", "Flattening Coefficient:", var2))
     }
 }
@@ -196,9 +200,12 @@ impl Characteristic for VarianceCoefficient2 {
 pub struct InformationSourceEntropy;
 
 impl InformationSourceEntropy {
-    fn analyze(image: &RgbImage) -> f64 {
-        let n = (image.width() * image.height()) as f64;
-        let histogram = Histogram::new(image);
+    pub fn analyze(image: &RgbImage) -> f64 {
+        Self::from_histogram(&Histogram::new(image))
+    }
+
+    fn from_histogram(histogram: &Histogram) -> f64 {
+        let n = histogram.pixel_count() as f64;
         let mut sum: f64 = 0.0;
         for channel in 0..3 {
             for luma in 0..=255 {
@@ -215,8 +222,8 @@ impl InformationSourceEntropy {
 }
 
 impl Characteristic for InformationSourceEntropy {
-    fn analyze(&self, image: &RgbImage) -> Result<String, String> {
-        let info_src_ent = Self::analyze(image);
+    fn analyze_histogram(&self, histogram: &Histogram) -> Result<String, String> {
+        let info_src_ent = Self::from_histogram(histogram);
         Ok(format!(
             "{:10} {:6.6}",
             "Information source entropy:", info_src_ent
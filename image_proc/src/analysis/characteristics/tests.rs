@@ -40,3 +40,36 @@ fn std_dev_test() {
 
     assert_eq!(expected, result);
 }
+
+#[test]
+fn masked_histogram_test() {
+    let pixels: Vec<u8> = (0..12u8).flat_map(|x| [x, 2 * x, 3 * x]).collect();
+    let sample_image = image::RgbImage::from_raw(4, 3, pixels).unwrap();
+    let left_half = image::GrayImage::from_fn(4, 3, |x, _| image::Luma([u8::from(x < 2)]));
+    let cropped = image::imageops::crop_imm(&sample_image, 0, 0, 2, 3).to_image();
+
+    let histogram = crate::histogram::Histogram::with_mask(&sample_image, &left_half);
+
+    assert_eq!(
+        super::Mean::analyze(&cropped),
+        super::Mean::from_histogram(&histogram)
+    );
+    assert_eq!(
+        super::Variance::analyze(&cropped),
+        super::Variance::from_histogram(&histogram)
+    );
+}
+
+#[test]
+fn analyze_histogram_test() {
+    use super::Characteristic;
+
+    let pixels: Vec<u8> = (0..9u8).flat_map(|x| [x, x, x]).collect();
+    let sample_image = image::RgbImage::from_raw(3, 3, pixels).unwrap();
+    let histogram = crate::histogram::Histogram::new(&sample_image);
+
+    let result = super::Variance.analyze_histogram(&histogram);
+
+    assert_eq!(Ok(format!("{:10} {:6.3}", "Variance:", VARIANCE)), result);
+    assert_eq!(super::Variance.analyze(&sample_image), result);
+}
@@ -32,7 +32,7 @@ impl ImageComparer for CompositeComparer {
 pub struct MeanSquareError {}
 
 impl MeanSquareError {
-    fn compare(original: &RgbImage, modified: &RgbImage) -> f64 {
+    pub(crate) fn compare(original: &RgbImage, modified: &RgbImage) -> f64 {
         let Rgb(totals) = map_and_sum(original, modified, |a, b| {
             let difference = a as i128 - b as i128;
             difference * difference
@@ -119,7 +119,7 @@ impl ImageComparer for SNR {
 pub struct PSNR {}
 
 impl PSNR {
-    fn compare(original: &RgbImage, modified: &RgbImage) -> f64 {
+    pub(crate) fn compare(original: &RgbImage, modified: &RgbImage) -> f64 {
         let max_luminance = u8::MAX as f64 * u8::MAX as f64;
         let mse = MeanSquareError::compare(original, modified);
 
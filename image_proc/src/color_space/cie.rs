@@ -0,0 +1,69 @@
+use image::Rgb;
+
+// D65 reference white
+pub const WHITE_X: f64 = 0.95047;
+pub const WHITE_Y: f64 = 1.0;
+pub const WHITE_Z: f64 = 1.08883;
+
+/// CIE XYZ of an sRGB pixel, with the white point at [`WHITE_X`], [`WHITE_Y`], [`WHITE_Z`]
+pub fn rgb_to_xyz(&Rgb(pixel): &Rgb<u8>) -> [f64; 3] {
+    let [r, g, b] = pixel.map(|c| {
+        let c = c as f64 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    [
+        0.4124 * r + 0.3576 * g + 0.1805 * b,
+        0.2126 * r + 0.7152 * g + 0.0722 * b,
+        0.0193 * r + 0.1192 * g + 0.9505 * b,
+    ]
+}
+
+pub fn xyz_to_rgb([x, y, z]: [f64; 3]) -> Rgb<u8> {
+    let r = 3.2406 * x - 1.5372 * y - 0.4986 * z;
+    let g = -0.9689 * x + 1.8758 * y + 0.0415 * z;
+    let b = 0.0557 * x - 0.2040 * y + 1.0570 * z;
+    Rgb([r, g, b].map(|c| {
+        let c = if c <= 0.0031308 {
+            12.92 * c
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        (c * 255.0).round().clamp(0.0, 255.0) as u8
+    }))
+}
+
+/// CIE L*a*b* with lightness in `0..=100`
+pub fn rgb_to_lab(pixel: &Rgb<u8>) -> [f64; 3] {
+    let [x, y, z] = rgb_to_xyz(pixel);
+    let f = |t: f64| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x / WHITE_X), f(y / WHITE_Y), f(z / WHITE_Z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+pub fn lab_to_rgb([l, a, b]: [f64; 3]) -> Rgb<u8> {
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+    let f_inv = |t: f64| {
+        if t.powi(3) > 216.0 / 24389.0 {
+            t.powi(3)
+        } else {
+            (116.0 * t - 16.0) * 27.0 / 24389.0
+        }
+    };
+    xyz_to_rgb([
+        f_inv(fx) * WHITE_X,
+        f_inv(fy) * WHITE_Y,
+        f_inv(fz) * WHITE_Z,
+    ])
+}
@@ -0,0 +1,19 @@
+use image::Rgb;
+
+/// Naive CMYK without any ink profile, all components in `0..=1`
+pub fn rgb_to_cmyk(&Rgb(pixel): &Rgb<u8>) -> [f64; 4] {
+    let [r, g, b] = pixel.map(|c| c as f64 / 255.0);
+    let key = 1.0 - r.max(g).max(b);
+    if key >= 1.0 {
+        return [0.0, 0.0, 0.0, 1.0];
+    }
+    let [c, m, y] = [r, g, b].map(|component| (1.0 - component - key) / (1.0 - key));
+    [c, m, y, key]
+}
+
+pub fn cmyk_to_rgb([c, m, y, key]: [f64; 4]) -> Rgb<u8> {
+    Rgb([c, m, y].map(|component| {
+        let value = (1.0 - component) * (1.0 - key) * 255.0;
+        value.round().clamp(0.0, 255.0) as u8
+    }))
+}
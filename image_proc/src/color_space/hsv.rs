@@ -0,0 +1,79 @@
+use image::Rgb;
+
+/// Hue in degrees `0..360`, with the chroma and the largest and smallest of the normalised components
+fn hue_and_extremes(&Rgb(pixel): &Rgb<u8>) -> (f64, f64, f64) {
+    let [r, g, b] = pixel.map(|c| c as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let hue = if chroma == 0.0 {
+        // undefined for grays
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    (hue, max, min)
+}
+
+/// Pixel of the given hue and chroma, with `offset` added to all components
+fn from_hue_and_chroma(hue: f64, chroma: f64, offset: f64) -> Rgb<u8> {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let middle = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, middle, 0.0),
+        1 => (middle, chroma, 0.0),
+        2 => (0.0, chroma, middle),
+        3 => (0.0, middle, chroma),
+        4 => (middle, 0.0, chroma),
+        _ => (chroma, 0.0, middle),
+    };
+    Rgb([r, g, b].map(|c| ((c + offset) * 255.0).round().clamp(0.0, 255.0) as u8))
+}
+
+/// Hue in degrees, saturation and value in `0..=1`
+pub fn rgb_to_hsv(pixel: &Rgb<u8>) -> [f64; 3] {
+    let (hue, max, min) = hue_and_extremes(pixel);
+    let saturation = match max > 0.0 {
+        true => (max - min) / max,
+        false => 0.0,
+    };
+    [hue, saturation, max]
+}
+
+pub fn hsv_to_rgb([hue, saturation, value]: [f64; 3]) -> Rgb<u8> {
+    let chroma = value * saturation;
+    from_hue_and_chroma(hue, chroma, value - chroma)
+}
+
+/// Hue in degrees, saturation and lightness in `0..=1`
+pub fn rgb_to_hsl(pixel: &Rgb<u8>) -> [f64; 3] {
+    let (hue, max, min) = hue_and_extremes(pixel);
+    let lightness = (max + min) / 2.0;
+    let saturation = match max > min {
+        true => (max - min) / (1.0 - (2.0 * lightness - 1.0).abs()),
+        false => 0.0,
+    };
+    [hue, saturation, lightness]
+}
+
+pub fn hsl_to_rgb([hue, saturation, lightness]: [f64; 3]) -> Rgb<u8> {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    from_hue_and_chroma(hue, chroma, lightness - chroma / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primaries_have_expected_hues() {
+        assert_eq!([0.0, 1.0, 1.0], rgb_to_hsv(&Rgb([255, 0, 0])));
+        assert_eq!([120.0, 1.0, 0.5], rgb_to_hsl(&Rgb([0, 255, 0])));
+        assert_eq!(240.0, rgb_to_hsv(&Rgb([0, 0, 255]))[0]);
+        assert_eq!([0.0, 0.0, 0.4], rgb_to_hsl(&Rgb([102; 3])));
+    }
+}
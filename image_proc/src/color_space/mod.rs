@@ -0,0 +1,205 @@
+//! Conversions of 8-bit sRGB pixels to other colour spaces and back
+//!
+//! Converting a pixel to any of the spaces and back gives the same pixel.
+
+use image::{GrayImage, ImageBuffer, Luma, Rgb, RgbImage};
+
+mod cie;
+mod cmyk;
+mod hsv;
+mod ycbcr;
+
+pub use cie::*;
+pub use cmyk::*;
+pub use hsv::*;
+pub use ycbcr::*;
+
+/// A single channel of an image in a colour space, with values in their natural range
+pub type Plane = ImageBuffer<Luma<f64>, Vec<f64>>;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum ColorSpace {
+    #[default]
+    Rgb,
+    /// Hue in degrees, saturation and value in `0..=1`
+    Hsv,
+    /// Hue in degrees, saturation and lightness in `0..=1`
+    Hsl,
+    /// Full-range BT.601, all channels in `0..=255`
+    YCbCr,
+    /// CIE XYZ with D65 white
+    Xyz,
+    /// CIE L*a*b* with D65 white, lightness in `0..=100`
+    Lab,
+    /// Naive CMYK, all channels in `0..=1`
+    Cmyk,
+}
+
+impl ColorSpace {
+    pub fn channel_names(&self) -> &'static [&'static str] {
+        match self {
+            ColorSpace::Rgb => &["R", "G", "B"],
+            ColorSpace::Hsv => &["H", "S", "V"],
+            ColorSpace::Hsl => &["H", "S", "L"],
+            ColorSpace::YCbCr => &["Y", "Cb", "Cr"],
+            ColorSpace::Xyz => &["X", "Y", "Z"],
+            ColorSpace::Lab => &["L", "a", "b"],
+            ColorSpace::Cmyk => &["C", "M", "Y", "K"],
+        }
+    }
+
+    pub fn channel_count(&self) -> usize {
+        self.channel_names().len()
+    }
+
+    /// Index of the channel with the given name, ignoring case
+    pub fn channel_index(&self, name: &str) -> Option<usize> {
+        self.channel_names()
+            .iter()
+            .position(|channel| channel.eq_ignore_ascii_case(name))
+    }
+
+    /// Range of the values of a channel that is mapped to `0..=255` by [`ColorSpace::encode`]
+    pub fn channel_range(&self, channel: usize) -> (f64, f64) {
+        assert!(channel < self.channel_count(), "No channel {}", channel);
+        match (self, channel) {
+            (ColorSpace::Rgb | ColorSpace::YCbCr, _) => (0.0, 255.0),
+            (ColorSpace::Hsv | ColorSpace::Hsl, 0) => (0.0, 360.0),
+            (ColorSpace::Hsv | ColorSpace::Hsl | ColorSpace::Cmyk, _) => (0.0, 1.0),
+            (ColorSpace::Xyz, _) => (0.0, [WHITE_X, WHITE_Y, WHITE_Z][channel]),
+            (ColorSpace::Lab, 0) => (0.0, 100.0),
+            (ColorSpace::Lab, _) => (-128.0, 127.0),
+        }
+    }
+
+    /// Channels of the pixel, only the first [`ColorSpace::channel_count`] of them are used
+    pub fn from_rgb(&self, pixel: &Rgb<u8>) -> [f64; 4] {
+        let with_fourth = |[a, b, c]: [f64; 3]| [a, b, c, 0.0];
+        match self {
+            ColorSpace::Rgb => with_fourth(pixel.0.map(|c| c as f64)),
+            ColorSpace::Hsv => with_fourth(rgb_to_hsv(pixel)),
+            ColorSpace::Hsl => with_fourth(rgb_to_hsl(pixel)),
+            ColorSpace::YCbCr => with_fourth(rgb_to_ycbcr(pixel)),
+            ColorSpace::Xyz => with_fourth(rgb_to_xyz(pixel)),
+            ColorSpace::Lab => with_fourth(rgb_to_lab(pixel)),
+            ColorSpace::Cmyk => rgb_to_cmyk(pixel),
+        }
+    }
+
+    /// Pixel of the channels returned by [`ColorSpace::from_rgb`]
+    pub fn to_rgb(&self, [a, b, c, d]: [f64; 4]) -> Rgb<u8> {
+        match self {
+            ColorSpace::Rgb => Rgb([a, b, c].map(|c| c.round().clamp(0.0, 255.0) as u8)),
+            ColorSpace::Hsv => hsv_to_rgb([a, b, c]),
+            ColorSpace::Hsl => hsl_to_rgb([a, b, c]),
+            ColorSpace::YCbCr => ycbcr_to_rgb([a, b, c]),
+            ColorSpace::Xyz => xyz_to_rgb([a, b, c]),
+            ColorSpace::Lab => lab_to_rgb([a, b, c]),
+            ColorSpace::Cmyk => cmyk_to_rgb([a, b, c, d]),
+        }
+    }
+
+    /// Planes of all channels of the image
+    pub fn split(&self, image: &RgbImage) -> Vec<Plane> {
+        let (width, height) = image.dimensions();
+        let channels: Vec<[f64; 4]> = image.pixels().map(|pixel| self.from_rgb(pixel)).collect();
+        (0..self.channel_count())
+            .map(|channel| {
+                let values = channels.iter().map(|pixel| pixel[channel]).collect();
+                Plane::from_raw(width, height, values).unwrap()
+            })
+            .collect()
+    }
+
+    /// Image of the planes of all channels, as returned by [`ColorSpace::split`]
+    pub fn merge(&self, planes: &[Plane]) -> RgbImage {
+        assert_eq!(
+            self.channel_count(),
+            planes.len(),
+            "Wrong number of planes for the colour space"
+        );
+        let (width, height) = planes[0].dimensions();
+        assert!(
+            planes
+                .iter()
+                .all(|plane| plane.dimensions() == (width, height)),
+            "Planes must have the same dimensions"
+        );
+        RgbImage::from_fn(width, height, |x, y| {
+            let mut channels = [0.0; 4];
+            for (value, plane) in channels.iter_mut().zip(planes) {
+                *value = plane.get_pixel(x, y)[0];
+            }
+            self.to_rgb(channels)
+        })
+    }
+
+    /// 8-bit image of a plane, with the range of the channel stretched to `0..=255`
+    pub fn encode(&self, plane: &Plane, channel: usize) -> GrayImage {
+        let (low, high) = self.channel_range(channel);
+        GrayImage::from_fn(plane.width(), plane.height(), |x, y| {
+            let value = (plane.get_pixel(x, y)[0] - low) / (high - low) * 255.0;
+            Luma([value.round().clamp(0.0, 255.0) as u8])
+        })
+    }
+
+    /// Plane of an 8-bit image produced by [`ColorSpace::encode`]
+    pub fn decode(&self, image: &GrayImage, channel: usize) -> Plane {
+        let (low, high) = self.channel_range(channel);
+        Plane::from_fn(image.width(), image.height(), |x, y| {
+            Luma([low + image.get_pixel(x, y)[0] as f64 / 255.0 * (high - low)])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPACES: [ColorSpace; 7] = [
+        ColorSpace::Rgb,
+        ColorSpace::Hsv,
+        ColorSpace::Hsl,
+        ColorSpace::YCbCr,
+        ColorSpace::Xyz,
+        ColorSpace::Lab,
+        ColorSpace::Cmyk,
+    ];
+
+    #[test]
+    fn pixels_survive_round_trip() {
+        for space in SPACES {
+            for r in (0..=255).step_by(15) {
+                for g in (0..=255).step_by(17) {
+                    for b in (0..=255).step_by(51) {
+                        let pixel = Rgb([r as u8, g as u8, b as u8]);
+                        assert_eq!(pixel, space.to_rgb(space.from_rgb(&pixel)), "{:?}", space);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn split_and_merge_round_trip() {
+        let image = RgbImage::from_fn(16, 8, |x, y| Rgb([x as u8 * 16, y as u8 * 32, 200]));
+        for space in SPACES {
+            let planes = space.split(&image);
+
+            assert_eq!(space.channel_count(), planes.len());
+            assert_eq!(image, space.merge(&planes));
+        }
+    }
+
+    #[test]
+    fn encoding_covers_channel_range() {
+        let space = ColorSpace::Lab;
+        let plane = Plane::from_raw(3, 1, vec![-128.0, 0.0, 127.0]).unwrap();
+
+        let encoded = space.encode(&plane, 1);
+
+        assert_eq!(&[0, 128, 255], encoded.as_raw().as_slice());
+        assert_eq!(plane, space.decode(&encoded, 1));
+        assert_eq!(Some(2), space.channel_index("B"));
+    }
+}
@@ -0,0 +1,21 @@
+use image::Rgb;
+
+/// Full-range BT.601 conversion, as used by JPEG
+pub fn rgb_to_ycbcr(&Rgb([r, g, b]): &Rgb<u8>) -> [f64; 3] {
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+    [
+        0.299 * r + 0.587 * g + 0.114 * b,
+        128.0 - 0.168736 * r - 0.331264 * g + 0.5 * b,
+        128.0 + 0.5 * r - 0.418688 * g - 0.081312 * b,
+    ]
+}
+
+pub fn ycbcr_to_rgb([y, cb, cr]: [f64; 3]) -> Rgb<u8> {
+    let (cb, cr) = (cb - 128.0, cr - 128.0);
+    Rgb([
+        y + 1.402 * cr,
+        y - 0.344136 * cb - 0.714136 * cr,
+        y + 1.772 * cb,
+    ]
+    .map(|c| c.round().clamp(0.0, 255.0) as u8))
+}
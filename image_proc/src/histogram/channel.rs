@@ -0,0 +1,280 @@
+use image::{GrayImage, ImageBuffer, Pixel, Primitive};
+use std::ops::Range;
+
+/// Histogram of a single channel, with the value range split evenly into any number of bins.
+///
+/// Works for both 8-bit and 16-bit images, values are reported as the first value of their bin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelHistogram {
+    bins: Vec<u32>,
+    /// Number of possible values, 256 for 8-bit images
+    levels: u32,
+}
+
+impl ChannelHistogram {
+    /// Counts of every bin, the bins split `0..levels` evenly
+    pub fn from_bins(bins: Vec<u32>, levels: u32) -> Self {
+        assert!(
+            levels > 0 && levels <= 1 << 16,
+            "Values must fit in 16 bits"
+        );
+        assert!(
+            !bins.is_empty() && bins.len() as u32 <= levels,
+            "Number of bins must be between 1 and the number of levels"
+        );
+        Self { bins, levels }
+    }
+
+    pub fn from_values<I>(values: I, levels: u32, bins: usize) -> Self
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        let mut histogram = Self::from_bins(vec![0; bins], levels);
+        for value in values {
+            assert!((value as u32) < levels, "Value {} out of range", value);
+            let bin = histogram.bin_of(value as u32);
+            histogram.bins[bin] += 1;
+        }
+        histogram
+    }
+
+    /// Histogram of one channel of the image, counting only pixels where the mask is not zero
+    pub fn from_channel<P>(
+        image: &ImageBuffer<P, Vec<P::Subpixel>>,
+        channel: usize,
+        bins: usize,
+        mask: Option<&GrayImage>,
+    ) -> Self
+    where
+        P: Pixel,
+        P::Subpixel: Into<u16>,
+    {
+        assert!(
+            channel < P::CHANNEL_COUNT as usize,
+            "Image has no channel {}",
+            channel
+        );
+        let values = masked_pixels(image, mask).map(|pixel| pixel.channels()[channel].into());
+        Self::from_values(values, levels::<P::Subpixel>(), bins)
+    }
+
+    /// Histogram of the luma (Y of YCbCr, ITU-R BT.601) of an image with at least three channels
+    pub fn luminance<P>(
+        image: &ImageBuffer<P, Vec<P::Subpixel>>,
+        bins: usize,
+        mask: Option<&GrayImage>,
+    ) -> Self
+    where
+        P: Pixel,
+        P::Subpixel: Into<u16>,
+    {
+        assert!(P::CHANNEL_COUNT >= 3, "Luminance requires a colour image");
+        let levels = levels::<P::Subpixel>();
+        let values = masked_pixels(image, mask).map(|pixel| {
+            let [r, g, b] = [0, 1, 2].map(|channel| pixel.channels()[channel].into() as f64);
+            let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+            luma.round().min((levels - 1) as f64) as u16
+        });
+        Self::from_values(values, levels, bins)
+    }
+
+    pub fn bins(&self) -> &[u32] {
+        &self.bins
+    }
+
+    pub fn levels(&self) -> u32 {
+        self.levels
+    }
+
+    /// Number of counted values
+    pub fn count(&self) -> u64 {
+        self.bins.iter().map(|&x| x as u64).sum()
+    }
+
+    /// Values falling into the bin
+    pub fn bin_range(&self, bin: usize) -> Range<u32> {
+        let edge = |bin: usize| (bin as u64 * self.levels as u64 / self.bins.len() as u64) as u32;
+        edge(bin)..edge(bin + 1)
+    }
+
+    /// Value representing the bin in moments, the middle of its range
+    pub fn bin_value(&self, bin: usize) -> f64 {
+        let range = self.bin_range(bin);
+        (range.start + range.end - 1) as f64 / 2.0
+    }
+
+    fn bin_of(&self, value: u32) -> usize {
+        // inverse of `bin_range`, the first bin whose upper edge is above the value
+        ((value as u64 + 1) * self.bins.len() as u64).div_ceil(self.levels as u64) as usize - 1
+    }
+
+    /// Number of values in the bin and all the bins before it
+    pub fn cumulative(&self) -> Vec<u64> {
+        let mut sum = 0;
+        self.bins
+            .iter()
+            .map(|&x| {
+                sum += x as u64;
+                sum
+            })
+            .collect()
+    }
+
+    /// Cumulative distribution function, the share of values in the bin and all the bins before it
+    pub fn cdf(&self) -> Vec<f64> {
+        let count = self.count().max(1) as f64;
+        self.cumulative()
+            .into_iter()
+            .map(|x| x as f64 / count)
+            .collect()
+    }
+
+    /// The lowest value such that at least `q` of all values are in its bin or below, `None` if empty
+    pub fn quantile(&self, q: f64) -> Option<u32> {
+        assert!((0.0..=1.0).contains(&q), "Quantile must be between 0 and 1");
+        let count = self.count();
+        let bin = self
+            .cumulative()
+            .iter()
+            .position(|&x| x > 0 && x as f64 >= q * count as f64)?;
+        Some(self.bin_range(bin).start)
+    }
+
+    pub fn percentile(&self, p: f64) -> Option<u32> {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "Percentile must be between 0 and 100"
+        );
+        self.quantile(p / 100.0)
+    }
+
+    pub fn median(&self) -> Option<u32> {
+        self.quantile(0.5)
+    }
+
+    pub fn min(&self) -> Option<u32> {
+        let bin = self.bins.iter().position(|&x| x > 0)?;
+        Some(self.bin_range(bin).start)
+    }
+
+    pub fn max(&self) -> Option<u32> {
+        let bin = self.bins.iter().rposition(|&x| x > 0)?;
+        Some(self.bin_range(bin).start)
+    }
+
+    /// The most frequent value, the lowest one in case of a tie
+    pub fn mode(&self) -> Option<u32> {
+        let max = *self.bins.iter().max()?;
+        if max == 0 {
+            return None;
+        }
+        let bin = self.bins.iter().position(|&x| x == max)?;
+        Some(self.bin_range(bin).start)
+    }
+
+    pub fn mean(&self) -> f64 {
+        let sum: f64 = (0..self.bins.len())
+            .map(|bin| self.bin_value(bin) * self.bins[bin] as f64)
+            .sum();
+        sum / self.count() as f64
+    }
+
+    /// Mean of `(value - mean)^order`, the variance for order 2
+    pub fn central_moment(&self, order: i32) -> f64 {
+        let mean = self.mean();
+        let sum: f64 = (0..self.bins.len())
+            .map(|bin| (self.bin_value(bin) - mean).powi(order) * self.bins[bin] as f64)
+            .sum();
+        sum / self.count() as f64
+    }
+}
+
+fn levels<T: Primitive + Into<u16>>() -> u32 {
+    T::DEFAULT_MAX_VALUE.into() as u32 + 1
+}
+
+fn masked_pixels<'a, P: Pixel>(
+    image: &'a ImageBuffer<P, Vec<P::Subpixel>>,
+    mask: Option<&'a GrayImage>,
+) -> impl Iterator<Item = &'a P> {
+    if let Some(mask) = mask {
+        assert_eq!(
+            image.dimensions(),
+            mask.dimensions(),
+            "Mask must have the same dimensions as the image"
+        );
+    }
+    image
+        .enumerate_pixels()
+        .filter(move |(x, y, _)| mask.is_none_or(|mask| mask.get_pixel(*x, *y)[0] != 0))
+        .map(|(_, _, pixel)| pixel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Luma, Rgb, RgbImage};
+
+    #[test]
+    fn bins_split_range_evenly() {
+        let histogram = ChannelHistogram::from_values(0..=255, 256, 3);
+
+        assert_eq!(&[85, 85, 86], histogram.bins());
+        assert_eq!(0..85, histogram.bin_range(0));
+        assert_eq!(170..256, histogram.bin_range(2));
+        for value in 0..256 {
+            assert!(histogram
+                .bin_range(histogram.bin_of(value))
+                .contains(&value));
+        }
+    }
+
+    #[test]
+    fn statistics_of_known_values() {
+        let histogram = ChannelHistogram::from_values([2, 4, 4, 4, 5, 5, 7, 9], 256, 256);
+
+        assert_eq!(Some(2), histogram.min());
+        assert_eq!(Some(9), histogram.max());
+        assert_eq!(Some(4), histogram.mode());
+        assert_eq!(Some(4), histogram.median());
+        assert_eq!(Some(2), histogram.percentile(0.0));
+        assert_eq!(Some(9), histogram.percentile(100.0));
+        assert_eq!(5.0, histogram.mean());
+        assert_eq!(4.0, histogram.central_moment(2));
+        assert_eq!(1.0, histogram.cdf()[9]);
+        assert_eq!(0.5, histogram.cdf()[4]);
+    }
+
+    #[test]
+    fn empty_histogram_has_no_values() {
+        let histogram = ChannelHistogram::from_values([], 256, 16);
+
+        assert_eq!(None, histogram.min());
+        assert_eq!(None, histogram.mode());
+        assert_eq!(None, histogram.median());
+    }
+
+    #[test]
+    fn mask_restricts_counted_pixels() {
+        let image = RgbImage::from_fn(4, 4, |x, _| Rgb([x as u8 * 10, 0, 0]));
+        let mask = GrayImage::from_fn(4, 4, |x, _| Luma([if x < 2 { 255 } else { 0 }]));
+
+        let histogram = ChannelHistogram::from_channel(&image, 0, 256, Some(&mask));
+
+        assert_eq!(8, histogram.count());
+        assert_eq!(Some(10), histogram.max());
+    }
+
+    #[test]
+    fn sixteen_bit_images_use_full_range() {
+        let image: ImageBuffer<Rgb<u16>, Vec<u16>> =
+            ImageBuffer::from_fn(2, 2, |x, y| Rgb([(x * 40000 + y * 100) as u16, 0, 65535]));
+
+        let red = ChannelHistogram::from_channel(&image, 0, 1024, None);
+        let luma = ChannelHistogram::luminance(&image, 65536, None);
+
+        assert_eq!(65536, red.levels());
+        assert_eq!(Some(40100 / 64 * 64), red.max());
+        assert_eq!(Some(7471), luma.min());
+    }
+}
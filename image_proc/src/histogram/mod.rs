@@ -1,14 +1,44 @@
-use image::RgbImage;
+use image::{GrayImage, Rgb, RgbImage};
 use std::ops::{Deref, DerefMut};
 
+mod channel;
+
+pub use channel::ChannelHistogram;
+
+/// Histograms of the three channels of an 8-bit RGB image, with a bin for every value
 pub struct Histogram {
     data: [[u32; 256]; 3],
 }
 
 impl Histogram {
     pub fn new(image: &RgbImage) -> Self {
+        Self::count(image.pixels())
+    }
+
+    /// Histograms of the pixels for which the mask is not zero
+    pub fn with_mask(image: &RgbImage, mask: &GrayImage) -> Self {
+        assert_eq!(
+            image.dimensions(),
+            mask.dimensions(),
+            "Mask must have the same dimensions as the image"
+        );
+        Self::count(
+            image
+                .pixels()
+                .zip(mask.pixels())
+                .filter(|(_, mask)| mask[0] != 0)
+                .map(|(pixel, _)| pixel),
+        )
+    }
+
+    /// Histogram of the luma (Y of YCbCr) with a bin for every value
+    pub fn luminance(image: &RgbImage) -> ChannelHistogram {
+        ChannelHistogram::luminance(image, 256, None)
+    }
+
+    fn count<'a>(pixels: impl Iterator<Item = &'a Rgb<u8>>) -> Self {
         let mut data = [[0u32; 256]; 3];
-        for pixel in image.pixels() {
+        for pixel in pixels {
             for channel in 0..3 {
                 let luminosity = pixel[channel];
                 data[channel][luminosity as usize] += 1;
@@ -16,6 +46,23 @@ impl Histogram {
         }
         Self { data }
     }
+
+    /// Number of counted pixels
+    pub fn pixel_count(&self) -> u32 {
+        self.data[0].iter().sum()
+    }
+
+    pub fn channel(&self, channel: usize) -> ChannelHistogram {
+        ChannelHistogram::from_bins(self.data[channel].to_vec(), 256)
+    }
+
+    /// All three channels counted together, as one histogram of `3 * pixel_count()` values
+    pub fn combined(&self) -> ChannelHistogram {
+        let bins = (0..256)
+            .map(|value| self.data.iter().map(|channel| channel[value]).sum())
+            .collect();
+        ChannelHistogram::from_bins(bins, 256)
+    }
 }
 
 impl Deref for Histogram {
@@ -31,3 +78,25 @@ impl DerefMut for Histogram {
         &mut self.data
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    #[test]
+    fn combined_histogram_counts_every_channel() {
+        let image = RgbImage::from_fn(4, 2, |x, y| Rgb([x as u8, y as u8, 200]));
+        let mask = GrayImage::from_fn(4, 2, |_, y| Luma([y as u8]));
+
+        let histogram = Histogram::new(&image);
+        let masked = Histogram::with_mask(&image, &mask);
+
+        assert_eq!(8, histogram.pixel_count());
+        assert_eq!(24, histogram.combined().count());
+        assert_eq!(Some(200), histogram.combined().mode());
+        assert_eq!(4, masked.pixel_count());
+        assert_eq!(Some(1), masked.channel(1).min());
+        assert_eq!(histogram[0].to_vec(), histogram.channel(0).bins());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod analysis;
+pub mod color_space;
 pub(crate) mod gpu;
 pub mod histogram;
 pub mod modifications;
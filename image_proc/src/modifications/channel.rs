@@ -0,0 +1,94 @@
+use crate::color_space::ColorSpace;
+use crate::modifications::Transformation;
+use image::{DynamicImage, RgbImage};
+
+/// Applies a transformation to a single channel of a colour space, keeping the other channels,
+/// e.g. a median filter of the Lab lightness only.
+///
+/// The transformation gets the channel as a grayscale image, stretched to `0..=255` as by
+/// [`ColorSpace::encode`], and must keep the size of the image.
+pub struct ChannelTransformation<T> {
+    transformation: T,
+    space: ColorSpace,
+    channel: usize,
+}
+
+impl<T> ChannelTransformation<T> {
+    pub fn new(transformation: T, space: ColorSpace, channel: usize) -> Self {
+        assert!(
+            channel < space.channel_count(),
+            "{:?} has no channel {}",
+            space,
+            channel
+        );
+        Self {
+            transformation,
+            space,
+            channel,
+        }
+    }
+}
+
+impl<T: Transformation> Transformation for ChannelTransformation<T> {
+    fn apply(&self, image: &mut RgbImage) {
+        let mut planes = self.space.split(image);
+        let encoded = self.space.encode(&planes[self.channel], self.channel);
+        let mut channel = DynamicImage::from(encoded.clone()).to_rgb8();
+        self.transformation.apply(&mut channel);
+        assert_eq!(
+            encoded.dimensions(),
+            channel.dimensions(),
+            "Channel transformations must keep the size of the image"
+        );
+
+        let transformed = DynamicImage::from(channel).to_luma8();
+        let decoded = self.space.decode(&transformed, self.channel);
+        let pixels = planes[self.channel]
+            .pixels_mut()
+            .zip(encoded.pixels().zip(transformed.pixels()))
+            .zip(decoded.pixels());
+        for ((value, (before, after)), new_value) in pixels {
+            // unchanged pixels keep their exact value instead of the 8-bit one
+            if before != after {
+                *value = *new_value;
+            }
+        }
+        *image = self.space.merge(&planes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modifications::prelude::Negative;
+    use crate::modifications::IdTransform;
+    use image::Rgb;
+
+    fn sample() -> RgbImage {
+        RgbImage::from_fn(8, 8, |x, y| Rgb([x as u8 * 30, y as u8 * 30, 90]))
+    }
+
+    #[test]
+    fn identity_keeps_image() {
+        for space in [ColorSpace::Hsv, ColorSpace::Lab, ColorSpace::Cmyk] {
+            let mut image = sample();
+
+            ChannelTransformation::new(IdTransform, space, 0).apply(&mut image);
+
+            assert_eq!(sample(), image);
+        }
+    }
+
+    #[test]
+    fn only_selected_channel_changes() {
+        let mut image = sample();
+
+        ChannelTransformation::new(Negative {}, ColorSpace::Rgb, 1).apply(&mut image);
+
+        for (before, after) in sample().pixels().zip(image.pixels()) {
+            assert_eq!(before[0], after[0]);
+            assert_eq!(255 - before[1], after[1]);
+            assert_eq!(before[2], after[2]);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::modifications::Transformation;
+use image::{Rgb, RgbImage};
+
+/// Weights of the RGB channels in the luminance of a pixel
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum LuminanceWeights {
+    /// BT.601, as used by JPEG and `imageops::grayscale`
+    #[default]
+    Rec601,
+    /// BT.709, as used by HDTV and sRGB
+    Rec709,
+    /// Equal weights
+    Average,
+    /// Weights in the red, green, blue order, usually summing to 1
+    Custom([f64; 3]),
+}
+
+impl LuminanceWeights {
+    pub fn weights(&self) -> [f64; 3] {
+        match self {
+            LuminanceWeights::Rec601 => [0.299, 0.587, 0.114],
+            LuminanceWeights::Rec709 => [0.2126, 0.7152, 0.0722],
+            LuminanceWeights::Average => [1.0 / 3.0; 3],
+            LuminanceWeights::Custom(weights) => *weights,
+        }
+    }
+
+    pub fn luminance(&self, &Rgb(pixel): &Rgb<u8>) -> f64 {
+        let [r, g, b] = self.weights();
+        r * pixel[0] as f64 + g * pixel[1] as f64 + b * pixel[2] as f64
+    }
+}
+
+// channel sums of the classic sepia matrix, which is close to this tint times the BT.601 weights
+const SEPIA_TINT: [f64; 3] = [1.351, 1.203, 0.937];
+
+/// Every output channel is a weighted sum of the input channels,
+/// row `i` of the matrix holds the weights of the red, green and blue input for output channel `i`
+pub struct ChannelMixer {
+    matrix: [[f64; 3]; 3],
+}
+
+impl ChannelMixer {
+    pub fn new(matrix: [[f64; 3]; 3]) -> Self {
+        Self { matrix }
+    }
+
+    /// Every channel set to the luminance
+    pub fn grayscale(weights: LuminanceWeights) -> Self {
+        Self::new([weights.weights(); 3])
+    }
+
+    /// Luminance tinted with brown, the default weights give the classic sepia matrix
+    pub fn sepia(weights: LuminanceWeights) -> Self {
+        let weights = weights.weights();
+        Self::new(SEPIA_TINT.map(|tint| weights.map(|weight| tint * weight)))
+    }
+}
+
+impl Transformation for ChannelMixer {
+    fn apply(&self, image: &mut RgbImage) {
+        for pixel in image.pixels_mut() {
+            let input = pixel.0.map(|c| c as f64);
+            *pixel = Rgb(self.matrix.map(|row| {
+                let value: f64 = row.iter().zip(input).map(|(weight, c)| weight * c).sum();
+                value.round().clamp(0.0, u8::MAX as f64) as u8
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grayscale_uses_selected_weights() {
+        let mut image = RgbImage::from_pixel(1, 1, Rgb([255, 0, 0]));
+        let mut average = image.clone();
+
+        ChannelMixer::grayscale(LuminanceWeights::Rec709).apply(&mut image);
+        ChannelMixer::grayscale(LuminanceWeights::Average).apply(&mut average);
+
+        assert_eq!(&Rgb([54; 3]), image.get_pixel(0, 0));
+        assert_eq!(&Rgb([85; 3]), average.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn mixer_swaps_channels_and_sepia_tints() {
+        let mut image = RgbImage::from_pixel(1, 1, Rgb([10, 20, 30]));
+        let mut sepia = RgbImage::from_pixel(1, 1, Rgb([100; 3]));
+
+        ChannelMixer::new([[0.0, 0.0, 1.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]]).apply(&mut image);
+        ChannelMixer::sepia(LuminanceWeights::default()).apply(&mut sepia);
+
+        assert_eq!(&Rgb([30, 20, 10]), image.get_pixel(0, 0));
+        assert_eq!(&Rgb([135, 120, 94]), sepia.get_pixel(0, 0));
+    }
+}
@@ -0,0 +1,27 @@
+use super::LookupTable;
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+/// Power-law (gamma) transformation `s = r^gamma` of intensities normalised to `0..=1`.
+///
+/// Gamma below 1 brightens dark tones, above 1 darkens them.
+pub struct Gamma {
+    gamma: f64,
+}
+
+impl Gamma {
+    pub fn new(gamma: f64) -> Self {
+        assert!(gamma > 0.0, "Gamma must be positive");
+        Self { gamma }
+    }
+
+    pub fn lookup(&self) -> LookupTable {
+        LookupTable::from_normalized(|value| value.powf(self.gamma))
+    }
+}
+
+impl Transformation for Gamma {
+    fn apply(&self, image: &mut RgbImage) {
+        self.lookup().apply(image);
+    }
+}
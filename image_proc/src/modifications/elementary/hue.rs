@@ -0,0 +1,44 @@
+use crate::color_space::{hsv_to_rgb, rgb_to_hsv};
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+/// Rotates the hue of every pixel by the given angle in degrees, keeping its saturation and value
+pub struct HueRotation {
+    degrees: f64,
+}
+
+impl HueRotation {
+    pub fn new(degrees: f64) -> Self {
+        Self { degrees }
+    }
+}
+
+impl Transformation for HueRotation {
+    fn apply(&self, image: &mut RgbImage) {
+        for pixel in image.pixels_mut() {
+            let [hue, saturation, value] = rgb_to_hsv(pixel);
+            *pixel = hsv_to_rgb([hue + self.degrees, saturation, value]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn rotation_cycles_primaries() {
+        let mut image = RgbImage::from_fn(3, 1, |x, _| match x {
+            0 => Rgb([255, 0, 0]),
+            1 => Rgb([0, 200, 0]),
+            _ => Rgb([90; 3]),
+        });
+
+        HueRotation::new(-240.0).apply(&mut image);
+
+        assert_eq!(&Rgb([0, 255, 0]), image.get_pixel(0, 0));
+        assert_eq!(&Rgb([0, 0, 200]), image.get_pixel(1, 0));
+        assert_eq!(&Rgb([90; 3]), image.get_pixel(2, 0));
+    }
+}
@@ -0,0 +1,133 @@
+use super::LookupTable;
+use crate::histogram::{ChannelHistogram, Histogram};
+use crate::modifications::histogram_modifications::HistogramChannels;
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+/// Stretches the range between the black and the white point to the full range,
+/// with the power-law transformation of [`Gamma`](super::Gamma) applied to the midtones
+pub struct Levels {
+    black: u8,
+    white: u8,
+    gamma: f64,
+}
+
+impl Levels {
+    pub fn new(black: u8, white: u8) -> Self {
+        assert!(white > black, "White point must be above the black point");
+        Self {
+            black,
+            white,
+            gamma: 1.0,
+        }
+    }
+
+    pub fn with_gamma(self, gamma: f64) -> Self {
+        assert!(gamma > 0.0, "Gamma must be positive");
+        Self { gamma, ..self }
+    }
+
+    pub fn lookup(&self) -> LookupTable {
+        let (black, white) = (self.black as f64, self.white as f64);
+        LookupTable::from_fn(|value| {
+            let normalized = ((value as f64 - black) / (white - black)).clamp(0.0, 1.0);
+            (normalized.powf(self.gamma) * u8::MAX as f64).round() as u8
+        })
+    }
+}
+
+impl Transformation for Levels {
+    fn apply(&self, image: &mut RgbImage) {
+        self.lookup().apply(image);
+    }
+}
+
+/// Levels with the black and white points found in the histogram, ignoring
+/// `clip` percent of the darkest and of the brightest pixels, so that a few outliers do not limit the stretch
+pub struct AutoLevels {
+    clip: f64,
+    channels: HistogramChannels,
+}
+
+impl AutoLevels {
+    pub fn new(clip: f64) -> Self {
+        assert!(
+            (0.0..50.0).contains(&clip),
+            "Clipped percentage must be between 0 and 50"
+        );
+        Self {
+            clip,
+            channels: HistogramChannels::default(),
+        }
+    }
+
+    /// With `Luminance` the points are found in the luma histogram and applied to all channels alike,
+    /// which keeps the colour balance, otherwise every channel is stretched separately
+    pub fn with_channels(self, channels: HistogramChannels) -> Self {
+        Self { channels, ..self }
+    }
+
+    fn table(&self, histogram: &ChannelHistogram) -> [u8; 256] {
+        let black = histogram.percentile(self.clip).unwrap_or(0) as u8;
+        let white = histogram.percentile(100.0 - self.clip).unwrap_or(255) as u8;
+        match white > black {
+            true => Levels::new(black, white).lookup().tables()[0],
+            // nothing to stretch in a single value
+            false => std::array::from_fn(|value| value as u8),
+        }
+    }
+
+    pub fn lookup(&self, image: &RgbImage) -> LookupTable {
+        match self.channels {
+            HistogramChannels::PerChannel => {
+                let histogram = Histogram::new(image);
+                LookupTable::per_channel(
+                    [0, 1, 2].map(|channel| self.table(&histogram.channel(channel))),
+                )
+            }
+            HistogramChannels::Luminance => {
+                LookupTable::new(self.table(&Histogram::luminance(image)))
+            }
+        }
+    }
+}
+
+impl Transformation for AutoLevels {
+    fn apply(&self, image: &mut RgbImage) {
+        self.lookup(image).apply(image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn levels_map_points_to_full_range() {
+        let table = Levels::new(50, 150).lookup().tables()[0];
+
+        assert_eq!(0, table[20]);
+        assert_eq!(0, table[50]);
+        assert_eq!(128, table[100]);
+        assert_eq!(255, table[150]);
+        assert_eq!(255, table[220]);
+    }
+
+    #[test]
+    fn auto_levels_ignore_clipped_outliers() {
+        // values 100..=199 with single dark and bright outliers
+        let mut image = RgbImage::from_fn(10, 10, |x, y| Rgb([100 + (y * 10 + x) as u8; 3]));
+        image.put_pixel(0, 0, Rgb([0; 3]));
+        image.put_pixel(9, 9, Rgb([255; 3]));
+        let mut clipped = image.clone();
+
+        AutoLevels::new(0.0).apply(&mut image);
+        AutoLevels::new(2.0).apply(&mut clipped);
+
+        assert_eq!(&Rgb([0; 3]), image.get_pixel(0, 0));
+        assert!(clipped.get_pixel(1, 0)[0] < 10);
+        assert!(clipped.get_pixel(8, 9)[0] > 245);
+        assert!(clipped.get_pixel(1, 0)[0] < image.get_pixel(1, 0)[0]);
+    }
+}
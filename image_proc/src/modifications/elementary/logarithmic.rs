@@ -0,0 +1,83 @@
+use super::LookupTable;
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+/// Logarithmic transformation `s = ln(1 + strength * r) / ln(1 + strength)` of intensities normalised
+/// to `0..=1`, expanding dark tones and compressing bright ones.
+///
+/// A strength of 255 gives the classic `c * ln(1 + r)` on the `0..=255` range.
+pub struct Logarithmic {
+    strength: f64,
+}
+
+impl Logarithmic {
+    pub fn new(strength: f64) -> Self {
+        assert!(strength > 0.0, "Strength must be positive");
+        Self { strength }
+    }
+
+    pub fn lookup(&self) -> LookupTable {
+        LookupTable::from_normalized(|value| {
+            (self.strength * value).ln_1p() / self.strength.ln_1p()
+        })
+    }
+}
+
+impl Transformation for Logarithmic {
+    fn apply(&self, image: &mut RgbImage) {
+        self.lookup().apply(image);
+    }
+}
+
+/// Inverse of [`Logarithmic`] with the same strength, expanding bright tones and compressing dark ones
+pub struct Exponential {
+    strength: f64,
+}
+
+impl Exponential {
+    pub fn new(strength: f64) -> Self {
+        assert!(strength > 0.0, "Strength must be positive");
+        Self { strength }
+    }
+
+    pub fn lookup(&self) -> LookupTable {
+        LookupTable::from_normalized(|value| {
+            (value * self.strength.ln_1p()).exp_m1() / self.strength
+        })
+    }
+}
+
+impl Transformation for Exponential {
+    fn apply(&self, image: &mut RgbImage) {
+        self.lookup().apply(image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_inverts_logarithmic() {
+        for strength in [1.0, 10.0, 255.0] {
+            let round_trip = Logarithmic::new(strength)
+                .lookup()
+                .then(&Exponential::new(strength).lookup());
+
+            for (value, &result) in round_trip.tables()[0].iter().enumerate() {
+                // the flat part of the logarithm merges some bright values
+                assert!((value as i32 - result as i32).abs() <= 3);
+            }
+            assert_eq!(0, round_trip.tables()[0][0]);
+            assert_eq!(255, round_trip.tables()[0][255]);
+        }
+    }
+
+    #[test]
+    fn logarithm_brightens_dark_tones() {
+        let lookup = Logarithmic::new(255.0).lookup();
+
+        assert_eq!(128, lookup.tables()[0][15]);
+        assert!(lookup.tables()[0].windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
@@ -0,0 +1,76 @@
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+/// Maps every value of each channel through a 256-entry table.
+///
+/// The tone transformations of this module are computed once as a table and applied through it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupTable {
+    tables: [[u8; 256]; 3],
+}
+
+impl LookupTable {
+    /// The same table for all channels
+    pub fn new(table: [u8; 256]) -> Self {
+        Self { tables: [table; 3] }
+    }
+
+    pub fn per_channel(tables: [[u8; 256]; 3]) -> Self {
+        Self { tables }
+    }
+
+    pub fn from_fn<F: Fn(u8) -> u8>(function: F) -> Self {
+        Self::new(std::array::from_fn(|value| function(value as u8)))
+    }
+
+    /// Table of a function of intensities normalised to `0..=1`, rounding and clamping its results
+    pub(super) fn from_normalized<F: Fn(f64) -> f64>(function: F) -> Self {
+        Self::from_fn(|value| {
+            let result = function(value as f64 / u8::MAX as f64) * u8::MAX as f64;
+            result.round().clamp(0.0, u8::MAX as f64) as u8
+        })
+    }
+
+    pub fn tables(&self) -> &[[u8; 256]; 3] {
+        &self.tables
+    }
+
+    /// Table applying `self` first and `other` on its results
+    pub fn then(&self, other: &LookupTable) -> Self {
+        let tables = std::array::from_fn(|channel| {
+            self.tables[channel].map(|value| other.tables[channel][value as usize])
+        });
+        Self { tables }
+    }
+}
+
+impl Transformation for LookupTable {
+    fn apply(&self, image: &mut RgbImage) {
+        for pixel in image.pixels_mut() {
+            for channel in 0..3 {
+                pixel[channel] = self.tables[channel][pixel[channel] as usize];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn tables_apply_per_channel() {
+        let mut image = RgbImage::from_pixel(2, 2, Rgb([10, 20, 30]));
+        let invert = LookupTable::from_fn(|value| 255 - value);
+        let double = LookupTable::per_channel([
+            std::array::from_fn(|value| (value * 2).min(255) as u8),
+            std::array::from_fn(|value| value as u8),
+            std::array::from_fn(|_| 7),
+        ]);
+
+        double.then(&invert).apply(&mut image);
+
+        assert_eq!(&Rgb([235, 235, 248]), image.get_pixel(1, 1));
+    }
+}
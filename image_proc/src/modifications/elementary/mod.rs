@@ -1,7 +1,25 @@
 mod brightness;
+mod channel_mixer;
 mod contrast;
+mod gamma;
+mod hue;
+mod levels;
+mod logarithmic;
+mod lookup;
 mod negative;
+mod saturation;
+mod tone_curve;
+mod white_balance;
 
 pub use brightness::Brightness;
+pub use channel_mixer::{ChannelMixer, LuminanceWeights};
 pub use contrast::Contrast;
+pub use gamma::Gamma;
+pub use hue::HueRotation;
+pub use levels::{AutoLevels, Levels};
+pub use logarithmic::{Exponential, Logarithmic};
+pub use lookup::LookupTable;
 pub use negative::Negative;
+pub use saturation::{Saturation, Vibrance};
+pub use tone_curve::{CurveInterpolation, ToneCurve};
+pub use white_balance::{ColorTemperature, WhiteBalance, WhiteBalanceMethod};
@@ -0,0 +1,90 @@
+use super::LuminanceWeights;
+use crate::modifications::Transformation;
+use image::{Rgb, RgbImage};
+
+/// Moves every pixel `factor` times as far from its gray of the same luminance,
+/// 0 gives a grayscale image, 1 keeps the image and values above 1 make colours stronger
+fn scale_from_gray(pixel: &mut Rgb<u8>, factor: f64) {
+    let gray = LuminanceWeights::default().luminance(pixel);
+    *pixel = Rgb(pixel.0.map(|c| {
+        let value = gray + (c as f64 - gray) * factor;
+        value.round().clamp(0.0, u8::MAX as f64) as u8
+    }));
+}
+
+/// Scales the saturation of all pixels alike
+pub struct Saturation {
+    factor: f64,
+}
+
+impl Saturation {
+    /// 0 gives a grayscale image, 1 keeps the image
+    pub fn new(factor: f64) -> Self {
+        assert!(factor >= 0.0, "Saturation factor must not be negative");
+        Self { factor }
+    }
+}
+
+impl Transformation for Saturation {
+    fn apply(&self, image: &mut RgbImage) {
+        for pixel in image.pixels_mut() {
+            scale_from_gray(pixel, self.factor);
+        }
+    }
+}
+
+/// Raises the saturation of dull colours more than of already saturated ones,
+/// which avoids oversaturating skin tones and clipping strong colours
+pub struct Vibrance {
+    amount: f64,
+}
+
+impl Vibrance {
+    /// Positive amounts increase the saturation, negative down to -1 decrease it
+    pub fn new(amount: f64) -> Self {
+        assert!(amount >= -1.0, "Vibrance must be at least -1");
+        Self { amount }
+    }
+}
+
+impl Transformation for Vibrance {
+    fn apply(&self, image: &mut RgbImage) {
+        for pixel in image.pixels_mut() {
+            let max = *pixel.0.iter().max().unwrap() as f64;
+            let min = *pixel.0.iter().min().unwrap() as f64;
+            let saturation = (max - min) / u8::MAX as f64;
+            scale_from_gray(pixel, 1.0 + self.amount * (1.0 - saturation));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_saturation_gives_gray() {
+        let mut image = RgbImage::from_pixel(1, 1, Rgb([200, 100, 50]));
+
+        Saturation::new(0.0).apply(&mut image);
+
+        assert_eq!(&Rgb([124; 3]), image.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn vibrance_favours_dull_colours() {
+        let mut image = RgbImage::from_fn(2, 1, |x, _| match x {
+            0 => Rgb([140, 120, 110]),
+            _ => Rgb([250, 20, 10]),
+        });
+        let original = image.clone();
+
+        Vibrance::new(0.5).apply(&mut image);
+
+        let spread = |pixel: &Rgb<u8>| pixel[0] as f64 - pixel[2] as f64;
+        let dull = spread(image.get_pixel(0, 0)) / spread(original.get_pixel(0, 0));
+        let strong = spread(image.get_pixel(1, 0)) / spread(original.get_pixel(1, 0));
+        assert!(dull > 1.3, "dull colour scaled by {}", dull);
+        assert!(strong < 1.1, "strong colour scaled by {}", strong);
+    }
+}
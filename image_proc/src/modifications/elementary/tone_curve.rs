@@ -0,0 +1,157 @@
+use super::LookupTable;
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+/// How a tone curve passes between its control points
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum CurveInterpolation {
+    /// Straight segments
+    #[default]
+    Linear,
+    /// Monotone cubic (Fritsch-Carlson) spline, smooth without overshooting between the points
+    Spline,
+}
+
+/// Maps intensities through a curve defined by control points.
+/// Values before the first point and after the last one keep the output of the nearest point.
+pub struct ToneCurve {
+    /// Sorted by input
+    points: Vec<(u8, u8)>,
+    interpolation: CurveInterpolation,
+}
+
+impl ToneCurve {
+    /// Control points as `(input, output)` pairs, in any order
+    pub fn new(points: &[(u8, u8)]) -> Self {
+        let mut points = points.to_vec();
+        points.sort_by_key(|&(input, _)| input);
+        assert!(points.len() >= 2, "Tone curve needs at least two points");
+        assert!(
+            points.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "Control points must have different inputs"
+        );
+        Self {
+            points,
+            interpolation: CurveInterpolation::default(),
+        }
+    }
+
+    pub fn with_interpolation(self, interpolation: CurveInterpolation) -> Self {
+        Self {
+            interpolation,
+            ..self
+        }
+    }
+
+    pub fn lookup(&self) -> LookupTable {
+        let xs: Vec<f64> = self.points.iter().map(|&(x, _)| x as f64).collect();
+        let ys: Vec<f64> = self.points.iter().map(|&(_, y)| y as f64).collect();
+        let slopes: Vec<f64> = (0..xs.len() - 1)
+            .map(|k| (ys[k + 1] - ys[k]) / (xs[k + 1] - xs[k]))
+            .collect();
+        let tangents = match self.interpolation {
+            CurveInterpolation::Linear => Vec::new(),
+            CurveInterpolation::Spline => monotone_tangents(&slopes),
+        };
+
+        LookupTable::from_fn(|value| {
+            let x = value as f64;
+            let (first, last) = (xs[0], xs[xs.len() - 1]);
+            if x <= first || x >= last {
+                let y = if x <= first { ys[0] } else { ys[ys.len() - 1] };
+                return y as u8;
+            }
+            // segment k spans xs[k]..xs[k + 1]
+            let k = xs.partition_point(|&point| point <= x) - 1;
+            let h = xs[k + 1] - xs[k];
+            let t = (x - xs[k]) / h;
+            let y = match self.interpolation {
+                CurveInterpolation::Linear => ys[k] + t * (ys[k + 1] - ys[k]),
+                CurveInterpolation::Spline => {
+                    let (t2, t3) = (t * t, t * t * t);
+                    (2.0 * t3 - 3.0 * t2 + 1.0) * ys[k]
+                        + (t3 - 2.0 * t2 + t) * h * tangents[k]
+                        + (-2.0 * t3 + 3.0 * t2) * ys[k + 1]
+                        + (t3 - t2) * h * tangents[k + 1]
+                }
+            };
+            y.round().clamp(0.0, u8::MAX as f64) as u8
+        })
+    }
+}
+
+/// Tangents at the control points of a spline that stays monotone wherever the points are
+fn monotone_tangents(slopes: &[f64]) -> Vec<f64> {
+    let n = slopes.len() + 1;
+    let mut tangents = vec![0.0; n];
+    tangents[0] = slopes[0];
+    tangents[n - 1] = slopes[n - 2];
+    for k in 1..n - 1 {
+        // local extrema stay flat, so that the curve does not overshoot them
+        if slopes[k - 1] * slopes[k] > 0.0 {
+            tangents[k] = (slopes[k - 1] + slopes[k]) / 2.0;
+        }
+    }
+    for (k, &slope) in slopes.iter().enumerate() {
+        if slope == 0.0 {
+            tangents[k] = 0.0;
+            tangents[k + 1] = 0.0;
+            continue;
+        }
+        let (alpha, beta) = (tangents[k] / slope, tangents[k + 1] / slope);
+        let length = alpha.hypot(beta);
+        if length > 3.0 {
+            tangents[k] = 3.0 / length * alpha * slope;
+            tangents[k + 1] = 3.0 / length * beta * slope;
+        }
+    }
+    tangents
+}
+
+impl Transformation for ToneCurve {
+    fn apply(&self, image: &mut RgbImage) {
+        self.lookup().apply(image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const S_CURVE: [(u8, u8); 4] = [(0, 0), (64, 40), (192, 215), (255, 255)];
+
+    #[test]
+    fn curves_pass_through_points() {
+        for interpolation in [CurveInterpolation::Linear, CurveInterpolation::Spline] {
+            let lookup = ToneCurve::new(&S_CURVE)
+                .with_interpolation(interpolation)
+                .lookup();
+
+            for (input, output) in S_CURVE {
+                assert_eq!(output, lookup.tables()[0][input as usize]);
+            }
+            assert!(lookup.tables()[0].windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+    }
+
+    #[test]
+    fn linear_curve_interpolates_and_holds_ends() {
+        let lookup = ToneCurve::new(&[(200, 250), (100, 50)]).lookup();
+        let table = lookup.tables()[0];
+
+        assert_eq!(50, table[0]);
+        assert_eq!(150, table[150]);
+        assert_eq!(250, table[255]);
+    }
+
+    #[test]
+    fn spline_does_not_overshoot_plateau() {
+        let lookup = ToneCurve::new(&[(0, 0), (100, 200), (150, 200), (255, 255)])
+            .with_interpolation(CurveInterpolation::Spline)
+            .lookup();
+
+        assert!(lookup.tables()[0][100..=150]
+            .iter()
+            .all(|&value| value == 200));
+    }
+}
@@ -0,0 +1,166 @@
+use super::LookupTable;
+use crate::histogram::Histogram;
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+/// How the colour cast of an image is estimated
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WhiteBalanceMethod {
+    /// Assumes the scene averages to gray, so scales the channels to equal means
+    GrayWorld,
+    /// Assumes the brightest pixels are white, so scales the given percentile of every channel to 255,
+    /// a percentile slightly below 100 keeps a few specular highlights from deciding the result
+    WhitePatch { percentile: f64 },
+}
+
+/// Automatic white balance, multiplying each channel by a gain estimated from the image
+pub struct WhiteBalance {
+    method: WhiteBalanceMethod,
+}
+
+impl WhiteBalance {
+    pub fn new(method: WhiteBalanceMethod) -> Self {
+        if let WhiteBalanceMethod::WhitePatch { percentile } = method {
+            assert!(
+                (0.0..=100.0).contains(&percentile),
+                "Percentile must be between 0 and 100"
+            );
+        }
+        Self { method }
+    }
+
+    /// Gains of the red, green and blue channels, empty channels are left as they are
+    pub fn gains(&self, image: &RgbImage) -> [f64; 3] {
+        let histogram = Histogram::new(image);
+        let channels = [0, 1, 2].map(|channel| histogram.channel(channel));
+        match self.method {
+            WhiteBalanceMethod::GrayWorld => {
+                let means = channels.map(|channel| channel.mean());
+                let gray = means.iter().sum::<f64>() / 3.0;
+                means.map(|mean| if mean > 0.0 { gray / mean } else { 1.0 })
+            }
+            WhiteBalanceMethod::WhitePatch { percentile } => {
+                channels.map(|channel| match channel.percentile(percentile) {
+                    Some(white) if white > 0 => u8::MAX as f64 / white as f64,
+                    _ => 1.0,
+                })
+            }
+        }
+    }
+}
+
+impl Transformation for WhiteBalance {
+    fn apply(&self, image: &mut RgbImage) {
+        scale_channels(image, self.gains(image));
+    }
+}
+
+/// Tints the image as if the scene was lit by light of the given colour temperature instead of
+/// daylight, so lower temperatures warm the image and higher ones cool it
+pub struct ColorTemperature {
+    kelvin: f64,
+}
+
+// colour temperature of daylight, which keeps the image unchanged
+const DAYLIGHT: f64 = 6500.0;
+
+impl ColorTemperature {
+    /// Temperatures between 1000 K and 40000 K
+    pub fn new(kelvin: f64) -> Self {
+        assert!(
+            (1000.0..=40000.0).contains(&kelvin),
+            "Temperature must be between 1000 K and 40000 K"
+        );
+        Self { kelvin }
+    }
+
+    pub fn gains(&self) -> [f64; 3] {
+        let light = black_body_color(self.kelvin);
+        let daylight = black_body_color(DAYLIGHT);
+        let gains: Vec<f64> = light.iter().zip(daylight).map(|(c, d)| c / d).collect();
+        // the strongest channel keeps its values, so that nothing gets clipped
+        let strongest = gains.iter().cloned().fold(f64::MIN, f64::max);
+        [0, 1, 2].map(|channel| gains[channel] / strongest)
+    }
+}
+
+impl Transformation for ColorTemperature {
+    fn apply(&self, image: &mut RgbImage) {
+        scale_channels(image, self.gains());
+    }
+}
+
+/// Approximate sRGB colour of a black body, fitted by Tanner Helland to the data of Mitchell Charity
+fn black_body_color(kelvin: f64) -> [f64; 3] {
+    let temperature = kelvin / 100.0;
+    let red = match temperature <= 66.0 {
+        true => 255.0,
+        false => 329.698727446 * (temperature - 60.0).powf(-0.1332047592),
+    };
+    let green = match temperature <= 66.0 {
+        true => 99.4708025861 * temperature.ln() - 161.1195681661,
+        false => 288.1221695283 * (temperature - 60.0).powf(-0.0755148492),
+    };
+    let blue = if temperature >= 66.0 {
+        255.0
+    } else if temperature <= 19.0 {
+        0.0
+    } else {
+        138.5177312231 * (temperature - 10.0).ln() - 305.0447927307
+    };
+    // a tiny floor keeps the gains of very warm light finite
+    [red, green, blue].map(|c| c.clamp(1.0, 255.0))
+}
+
+fn scale_channels(image: &mut RgbImage, gains: [f64; 3]) {
+    let tables = gains.map(|gain| {
+        std::array::from_fn(|value| (value as f64 * gain).round().min(u8::MAX as f64) as u8)
+    });
+    LookupTable::per_channel(tables).apply(image);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    /// Gray scene under a yellowish light
+    fn tinted() -> RgbImage {
+        RgbImage::from_fn(4, 4, |x, y| {
+            let gray = 40 + 10 * (x + y) as u8;
+            Rgb([gray + gray / 4, gray + gray / 8, gray / 4 * 3])
+        })
+    }
+
+    #[test]
+    fn gray_world_equalises_means() {
+        let mut image = tinted();
+
+        WhiteBalance::new(WhiteBalanceMethod::GrayWorld).apply(&mut image);
+
+        let histogram = Histogram::new(&image);
+        let means = [0, 1, 2].map(|channel| histogram.channel(channel).mean());
+        assert!((means[0] - means[2]).abs() < 1.0, "means {:?}", means);
+        assert!((means[1] - means[2]).abs() < 1.0, "means {:?}", means);
+    }
+
+    #[test]
+    fn white_patch_stretches_brightest_to_white() {
+        let mut image = tinted();
+
+        WhiteBalance::new(WhiteBalanceMethod::WhitePatch { percentile: 100.0 }).apply(&mut image);
+
+        assert_eq!(&Rgb([255; 3]), image.get_pixel(3, 3));
+    }
+
+    #[test]
+    fn temperature_warms_and_cools() {
+        let daylight = ColorTemperature::new(DAYLIGHT).gains();
+        let warm = ColorTemperature::new(3000.0).gains();
+        let cool = ColorTemperature::new(12000.0).gains();
+
+        assert!(daylight.iter().all(|&gain| (gain - 1.0).abs() < 1e-9));
+        assert!(warm[0] > warm[2]);
+        assert!(cool[2] > cool[0]);
+    }
+}
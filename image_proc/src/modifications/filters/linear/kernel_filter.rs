@@ -0,0 +1,65 @@
+use crate::modifications::frequency_domain::convolution::{correlate, ConvolutionMethod};
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+/// Linear filter with a kernel of arbitrary size, applied the same way as by [`LinearFilter`](super::LinearFilter):
+/// the kernel is not flipped and pixels closer to the edge than its radius are left unchanged.
+/// Large kernels are applied in the frequency domain, see [`ConvolutionMethod`].
+pub struct KernelFilter {
+    kernel: Vec<Vec<f64>>,
+    mask_scale: f64,
+    method: ConvolutionMethod,
+}
+
+impl KernelFilter {
+    pub fn new(kernel: Vec<Vec<f64>>, mask_scale: Option<f64>) -> Self {
+        Self {
+            kernel,
+            mask_scale: mask_scale.unwrap_or(1.0),
+            method: ConvolutionMethod::default(),
+        }
+    }
+
+    pub fn with_method(self, method: ConvolutionMethod) -> Self {
+        Self { method, ..self }
+    }
+}
+
+/// Removes the rounding error of the frequency domain, so that integer sums
+/// get truncated to the same value as in the spatial domain
+fn snap_to_integer(value: f64) -> f64 {
+    let rounded = value.round();
+    if (value - rounded).abs() < 1e-6 {
+        rounded
+    } else {
+        value
+    }
+}
+
+impl Transformation for KernelFilter {
+    fn apply(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        let center_x = (self.kernel[0].len() / 2) as u32;
+        let center_y = (self.kernel.len() / 2) as u32;
+        let right_margin = self.kernel[0].len() as u32 - 1 - center_x;
+        let bottom_margin = self.kernel.len() as u32 - 1 - center_y;
+        let is_border = |x: u32, y: u32| {
+            x < center_x || y < center_y || x + right_margin >= width || y + bottom_margin >= height
+        };
+
+        for channel in 0..3 {
+            let plane: Vec<Vec<f64>> = image
+                .rows()
+                .map(|row| row.map(|pixel| pixel[channel] as f64).collect())
+                .collect();
+            let filtered = correlate(&plane, &self.kernel, self.method);
+            for (x, y, pixel) in image.enumerate_pixels_mut() {
+                if is_border(x, y) {
+                    continue;
+                }
+                let sum = snap_to_integer(filtered[y as usize][x as usize]);
+                pixel[channel] = (sum * self.mask_scale) as u8;
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
+mod kernel_filter;
 mod linear_filter;
+pub use kernel_filter::KernelFilter;
 pub use linear_filter::LinearFilter;
 
 pub mod optimized;
@@ -0,0 +1,271 @@
+use super::fourier_transform::{fft_2d, FTDirection};
+
+type Vec2D<T> = Vec<Vec<T>>;
+
+/// Cost of a single element of an FFT pass, relative to a multiply-add of the spatial correlation
+const FOURIER_COST_FACTOR: f64 = 14.0;
+
+/// Selects how [`correlate`] and [`convolve`] are computed
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum ConvolutionMethod {
+    /// Picks the method with the lower estimated cost. The frequency domain starts to pay off
+    /// for kernels of about 60x60 elements for a 512x512 image, less for smaller images.
+    #[default]
+    Auto,
+    Spatial,
+    /// Multiplication of spectra, with the data zero-padded to avoid wrapping around
+    Fourier,
+}
+
+/// Correlates `data` with `kernel`, i.e. applies the kernel without flipping it,
+/// the same way as [`LinearFilter`](crate::modifications::filters::LinearFilter) does.
+///
+/// The origin of the kernel is in its middle and values outside of `data` are treated as zeros.
+/// The result has the same size as `data`.
+pub fn correlate(data: &[Vec<f64>], kernel: &[Vec<f64>], method: ConvolutionMethod) -> Vec2D<f64> {
+    assert!(
+        !kernel.is_empty() && kernel.iter().all(|row| row.len() == kernel[0].len()),
+        "Kernel must be a non-empty rectangle"
+    );
+    match method {
+        ConvolutionMethod::Auto if prefers_fourier(data, kernel) => correlate_fourier(data, kernel),
+        ConvolutionMethod::Auto | ConvolutionMethod::Spatial => correlate_spatial(data, kernel),
+        ConvolutionMethod::Fourier => correlate_fourier(data, kernel),
+    }
+}
+
+/// Convolves `data` with `kernel`, which is [`correlate`] with the kernel rotated by 180 degrees
+pub fn convolve(data: &[Vec<f64>], kernel: &[Vec<f64>], method: ConvolutionMethod) -> Vec2D<f64> {
+    correlate(data, &rotate_180(kernel), method)
+}
+
+fn prefers_fourier(data: &[Vec<f64>], kernel: &[Vec<f64>]) -> bool {
+    let (height, width) = (data.len(), data[0].len());
+    let (kernel_height, kernel_width) = (kernel.len(), kernel[0].len());
+    let spatial_cost = (height * width * kernel_height * kernel_width) as f64;
+    let padded_size = ((height + kernel_height - 1).next_power_of_two()
+        * (width + kernel_width - 1).next_power_of_two()) as f64;
+    // two forward transforms and one inverse
+    let fourier_cost = 3.0 * FOURIER_COST_FACTOR * padded_size * padded_size.log2();
+    fourier_cost < spatial_cost
+}
+
+fn rotate_180(kernel: &[Vec<f64>]) -> Vec2D<f64> {
+    kernel
+        .iter()
+        .rev()
+        .map(|row| row.iter().rev().copied().collect())
+        .collect()
+}
+
+fn correlate_spatial(data: &[Vec<f64>], kernel: &[Vec<f64>]) -> Vec2D<f64> {
+    let (height, width) = (data.len() as isize, data[0].len() as isize);
+    let center_x = (kernel[0].len() / 2) as isize;
+    let center_y = (kernel.len() / 2) as isize;
+
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let mut sum = 0.0;
+                    for (j, row) in kernel.iter().enumerate() {
+                        let source_y = y + j as isize - center_y;
+                        if source_y < 0 || source_y >= height {
+                            continue;
+                        }
+                        for (i, weight) in row.iter().enumerate() {
+                            let source_x = x + i as isize - center_x;
+                            if source_x >= 0 && source_x < width {
+                                sum += weight * data[source_y as usize][source_x as usize];
+                            }
+                        }
+                    }
+                    sum
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn correlate_fourier(data: &[Vec<f64>], kernel: &[Vec<f64>]) -> Vec2D<f64> {
+    let (height, width) = (data.len(), data[0].len());
+    let (kernel_height, kernel_width) = (kernel.len(), kernel[0].len());
+    // linear convolution of both has size data + kernel - 1, anything smaller would wrap around
+    let padded_height = (height + kernel_height - 1).next_power_of_two();
+    let padded_width = (width + kernel_width - 1).next_power_of_two();
+
+    let pad = |matrix: &[Vec<f64>]| -> Vec2D<f64> {
+        let mut padded = vec![vec![0.0; padded_width]; padded_height];
+        for (padded_row, row) in padded.iter_mut().zip(matrix) {
+            padded_row[..row.len()].copy_from_slice(row);
+        }
+        padded
+    };
+
+    let data_spectrum = fft_2d(&pad(data), FTDirection::Forward);
+    let kernel_spectrum = fft_2d(&pad(&rotate_180(kernel)), FTDirection::Forward);
+    let product: Vec2D<_> = data_spectrum
+        .iter()
+        .zip(&kernel_spectrum)
+        .map(|(data, kernel)| data.iter().zip(kernel).map(|(d, k)| d * k).collect())
+        .collect();
+    let full = fft_2d(&product, FTDirection::Inverse);
+
+    // the full convolution starts where the last element of the kernel touches the first of data
+    let offset_y = kernel_height - 1 - kernel_height / 2;
+    let offset_x = kernel_width - 1 - kernel_width / 2;
+    full[offset_y..offset_y + height]
+        .iter()
+        .map(|row| {
+            row[offset_x..offset_x + width]
+                .iter()
+                .map(|x| x.re)
+                .collect()
+        })
+        .collect()
+}
+
+/// Normalised cross-correlation of `template` with every window of `image` it fits in entirely.
+///
+/// The value at `[y][x]` describes the window with its top-left corner at `x,y` and lies in `-1.0..=1.0`,
+/// where 1 is a perfect match up to brightness and contrast. Windows or templates with constant values yield 0.
+/// There are no windows if the template is empty or does not fit into the image.
+pub fn normalized_cross_correlation(image: &[Vec<f64>], template: &[Vec<f64>]) -> Vec2D<f64> {
+    let size = |matrix: &[Vec<f64>]| (matrix.len(), matrix.first().map_or(0, Vec::len));
+    let (height, width) = size(image);
+    let (template_height, template_width) = size(template);
+    if template_height * template_width == 0 || template_height > height || template_width > width {
+        return Vec::new();
+    }
+
+    let count = (template_height * template_width) as f64;
+    let template_mean = template.iter().flatten().sum::<f64>() / count;
+    let template: Vec2D<f64> = template
+        .iter()
+        .map(|row| row.iter().map(|x| x - template_mean).collect())
+        .collect();
+    let template_energy: f64 = template.iter().flatten().map(|x| x * x).sum();
+
+    // zero-mean template makes the mean of the window cancel out of the numerator
+    let numerator = correlate(image, &template, ConvolutionMethod::Auto);
+    let sums = summed_area_table(image, |x| x);
+    let squares = summed_area_table(image, |x| x * x);
+    let window_sum = |table: &Vec2D<f64>, x: usize, y: usize| {
+        let (right, bottom) = (x + template_width, y + template_height);
+        table[bottom][right] - table[y][right] - table[bottom][x] + table[y][x]
+    };
+
+    let (center_x, center_y) = (template_width / 2, template_height / 2);
+    (0..=height - template_height)
+        .map(|y| {
+            (0..=width - template_width)
+                .map(|x| {
+                    let sum = window_sum(&sums, x, y);
+                    let window_energy = window_sum(&squares, x, y) - sum * sum / count;
+                    let denominator = (window_energy * template_energy).sqrt();
+                    if denominator < 1e-9 {
+                        return 0.0;
+                    }
+                    let value = numerator[y + center_y][x + center_x] / denominator;
+                    value.clamp(-1.0, 1.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Table with sums of `map(x)` over all elements above and to the left, with an extra zero row and column
+fn summed_area_table<F: Fn(f64) -> f64>(data: &[Vec<f64>], map: F) -> Vec2D<f64> {
+    let width = data[0].len();
+    let mut table = vec![vec![0.0; width + 1]];
+    for (y, row) in data.iter().enumerate() {
+        let mut row_sum = 0.0;
+        let mut table_row = vec![0.0; width + 1];
+        for (x, &value) in row.iter().enumerate() {
+            row_sum += map(value);
+            table_row[x + 1] = table[y][x + 1] + row_sum;
+        }
+        table.push(table_row);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_data(width: usize, height: usize) -> Vec2D<f64> {
+        (0..height)
+            .map(|y| (0..width).map(|x| ((x * 7 + y * 13) % 17) as f64).collect())
+            .collect()
+    }
+
+    fn assert_matrices_equal(expected: &[Vec<f64>], actual: &[Vec<f64>]) {
+        assert_eq!(expected.len(), actual.len());
+        for (expected, actual) in expected.iter().flatten().zip(actual.iter().flatten()) {
+            assert!(
+                (expected - actual).abs() < 1e-6,
+                "{} != {}",
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn fourier_correlation_matches_spatial() {
+        let data = sample_data(21, 13);
+        for kernel in [sample_data(3, 3), sample_data(5, 3), sample_data(11, 11)] {
+            assert_matrices_equal(
+                &correlate(&data, &kernel, ConvolutionMethod::Spatial),
+                &correlate(&data, &kernel, ConvolutionMethod::Fourier),
+            );
+        }
+    }
+
+    #[test]
+    fn convolution_flips_kernel() {
+        let data = vec![
+            vec![0.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 0.0],
+        ];
+        let kernel = vec![
+            vec![1.0, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 9.0],
+        ];
+
+        for method in [ConvolutionMethod::Spatial, ConvolutionMethod::Fourier] {
+            assert_matrices_equal(&kernel, &convolve(&data, &kernel, method));
+            assert_matrices_equal(&rotate_180(&kernel), &correlate(&data, &kernel, method));
+        }
+    }
+
+    #[test]
+    fn normalized_cross_correlation_finds_template() {
+        let image = sample_data(32, 24);
+        let template: Vec2D<f64> = image[5..12]
+            .iter()
+            .map(|row| row[9..17].iter().map(|x| 2.0 * x + 10.0).collect())
+            .collect();
+
+        let correlation = normalized_cross_correlation(&image, &template);
+
+        assert_eq!(24 - 7 + 1, correlation.len());
+        assert_eq!(32 - 8 + 1, correlation[0].len());
+        assert!((correlation[5][9] - 1.0).abs() < 1e-9);
+        assert!(correlation
+            .iter()
+            .flatten()
+            .all(|x| (-1.0..=1.0).contains(x)));
+    }
+
+    #[test]
+    fn normalized_cross_correlation_of_larger_template_is_empty() {
+        let image = sample_data(8, 6);
+
+        assert!(normalized_cross_correlation(&image, &sample_data(9, 2)).is_empty());
+        assert!(normalized_cross_correlation(&image, &sample_data(2, 7)).is_empty());
+    }
+}
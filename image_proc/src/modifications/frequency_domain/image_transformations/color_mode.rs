@@ -0,0 +1,90 @@
+use super::util::image_to_matrix;
+use crate::color_space::{lab_to_rgb, rgb_to_lab, rgb_to_ycbcr, ycbcr_to_rgb};
+use image::{Rgb, RgbImage};
+
+type Plane = Vec<Vec<f64>>;
+
+/// Selects which channels of an image a frequency-domain filter operates on,
+/// set on the filters and deconvolutions with their `with_color_mode` methods
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum ColorMode {
+    /// Filter the grayscale image and output a grayscale result
+    #[default]
+    Grayscale,
+    /// Filter each of the RGB channels separately
+    Rgb,
+    /// Filter the luma (Y) channel of YCbCr, preserving chroma
+    YCbCr,
+    /// Filter the lightness (L*) channel of CIE Lab, preserving chroma
+    Lab,
+}
+
+impl ColorMode {
+    /// Number of leading planes returned by [`ColorMode::split`] that should be filtered.
+    /// The remaining planes carry chroma and are passed through unchanged.
+    pub(crate) fn filtered_planes(&self) -> usize {
+        match self {
+            ColorMode::Rgb => 3,
+            _ => 1,
+        }
+    }
+
+    /// Splits the image into planes. Filtered planes are scaled to `0.0..=1.0`.
+    pub(crate) fn split(&self, image: &RgbImage) -> Vec<Plane> {
+        match self {
+            ColorMode::Grayscale => vec![image_to_matrix(image)],
+            ColorMode::Rgb => split_planes(image, |&Rgb(pixel)| pixel.map(|c| c as f64 / 255.0)),
+            ColorMode::YCbCr => split_planes(image, |pixel| {
+                let [y, cb, cr] = rgb_to_ycbcr(pixel);
+                [y / 255.0, cb, cr]
+            }),
+            ColorMode::Lab => split_planes(image, |pixel| {
+                let [l, a, b] = rgb_to_lab(pixel);
+                [l / 100.0, a, b]
+            }),
+        }
+    }
+
+    /// Reassembles an image from planes produced by [`ColorMode::split`]
+    pub(crate) fn merge(&self, planes: &[Plane], width: u32, height: u32) -> RgbImage {
+        let to_u8 = |value: f64| (value * 255.0).clamp(0.0, 255.0) as u8;
+        RgbImage::from_fn(width, height, |x, y| {
+            let (x, y) = (x as usize, y as usize);
+            match self {
+                ColorMode::Grayscale => Rgb([to_u8(planes[0][y][x]); 3]),
+                ColorMode::Rgb => Rgb([
+                    to_u8(planes[0][y][x]),
+                    to_u8(planes[1][y][x]),
+                    to_u8(planes[2][y][x]),
+                ]),
+                ColorMode::YCbCr => {
+                    ycbcr_to_rgb([planes[0][y][x] * 255.0, planes[1][y][x], planes[2][y][x]])
+                }
+                ColorMode::Lab => {
+                    lab_to_rgb([planes[0][y][x] * 100.0, planes[1][y][x], planes[2][y][x]])
+                }
+            }
+        })
+    }
+}
+
+fn split_planes<F>(image: &RgbImage, convert: F) -> Vec<Plane>
+where
+    F: Fn(&Rgb<u8>) -> [f64; 3],
+{
+    let mut planes: Vec<Plane> = (0..3)
+        .map(|_| Plane::with_capacity(image.height() as usize))
+        .collect();
+    for row in image.rows() {
+        let mut rows = [(); 3].map(|_| Vec::with_capacity(image.width() as usize));
+        for pixel in row {
+            for (channel, value) in convert(pixel).into_iter().enumerate() {
+                rows[channel].push(value);
+            }
+        }
+        for (plane, row) in planes.iter_mut().zip(rows) {
+            plane.push(row);
+        }
+    }
+    planes
+}
@@ -0,0 +1,349 @@
+use super::{
+    color_mode::ColorMode,
+    image_fourier_transforms::{ImageFourierTransform, FFT},
+};
+use crate::modifications::frequency_domain::fourier_transform::{fft_2d, FTDirection};
+use crate::modifications::Transformation;
+use image::{GrayImage, RgbImage};
+use num::Complex;
+use std::f64::consts::PI;
+
+type Vec2D<T> = Vec<Vec<T>>;
+
+/// Point-spread function, describing how a single point of light gets blurred
+pub enum PointSpreadFunction {
+    /// Linear motion of `length` pixels in the direction of `angle` degrees
+    MotionBlur {
+        length: f64,
+        angle: f64,
+    },
+    /// Out-of-focus blur, a uniform disk of the given radius
+    Disk {
+        radius: f64,
+    },
+    /// Gaussian blur of the given standard deviation, no blur at all for zero
+    Gaussian {
+        sigma: f64,
+    },
+    /// Arbitrary kernel with its origin in the middle, does not need to be normalized
+    Kernel(Vec2D<f64>),
+}
+
+impl PointSpreadFunction {
+    pub fn from_image(image: &GrayImage) -> Self {
+        Self::Kernel(
+            image
+                .rows()
+                .map(|row| row.map(|pixel| pixel[0] as f64).collect())
+                .collect(),
+        )
+    }
+
+    /// Returns the kernel, normalized so that its values sum up to 1. A kernel that does not sum up
+    /// to a positive value, such as an all-black image, becomes a single point and does not blur.
+    fn kernel(&self) -> Vec2D<f64> {
+        let kernel = match self {
+            PointSpreadFunction::MotionBlur { length, angle } => {
+                // the line spans `length` pixels, from the middle of the first to the last one
+                let extent = f64::max(length - 1.0, 0.0);
+                let radius = (extent / 2.0).ceil() as i32;
+                let size = 2 * radius as usize + 1;
+                let mut kernel = vec![vec![0.0; size]; size];
+                let (sin, cos) = (angle * PI / 180.0).sin_cos();
+                let steps = (extent * 10.0).ceil().max(1.0) as i32;
+                for step in 0..=steps {
+                    let t = extent * (step as f64 / steps as f64 - 0.5);
+                    let x = (radius as f64 + t * cos).round() as usize;
+                    let y = (radius as f64 - t * sin).round() as usize;
+                    kernel[y][x] = 1.0;
+                }
+                kernel
+            }
+            PointSpreadFunction::Disk { radius } => {
+                let extent = radius.ceil() as i32;
+                (-extent..=extent)
+                    .map(|y| {
+                        (-extent..=extent)
+                            .map(|x| match (x * x + y * y) as f64 <= radius * radius {
+                                true => 1.0,
+                                false => 0.0,
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+            PointSpreadFunction::Gaussian { sigma } if *sigma <= 0.0 => vec![vec![1.0]],
+            PointSpreadFunction::Gaussian { sigma } => {
+                let extent = (3.0 * sigma).ceil() as i32;
+                (-extent..=extent)
+                    .map(|y| {
+                        (-extent..=extent)
+                            .map(|x| f64::exp(-((x * x + y * y) as f64) / (2.0 * sigma * sigma)))
+                            .collect()
+                    })
+                    .collect()
+            }
+            PointSpreadFunction::Kernel(kernel) => kernel.clone(),
+        };
+
+        let sum: f64 = kernel.iter().flatten().sum();
+        if sum.is_nan() || sum <= 0.0 {
+            return vec![vec![1.0]];
+        }
+        kernel
+            .into_iter()
+            .map(|row| row.into_iter().map(|x| x / sum).collect())
+            .collect()
+    }
+
+    /// Returns the optical transfer function - the spectrum of the kernel padded to the given size
+    pub(crate) fn transfer_function(&self, width: u32, height: u32) -> Vec2D<Complex<f64>> {
+        spectrum_of_kernel(&self.kernel(), width, height)
+    }
+}
+
+/// Pads the kernel with zeros to `width`x`height`, with its middle moved to the origin.
+/// A kernel larger than that wraps around, like the image in the circular convolution of the FFT.
+fn spectrum_of_kernel(kernel: &[Vec<f64>], width: u32, height: u32) -> Vec2D<Complex<f64>> {
+    let (width, height) = (width as usize, height as usize);
+    let (center_x, center_y) = (kernel[0].len() / 2, kernel.len() / 2);
+    let wrap = |index: usize, center: usize, size: usize| {
+        (index as i64 - center as i64).rem_euclid(size as i64) as usize
+    };
+
+    let mut padded = vec![vec![0.0; width]; height];
+    for (y, row) in kernel.iter().enumerate() {
+        for (x, &value) in row.iter().enumerate() {
+            padded[wrap(y, center_y, height)][wrap(x, center_x, width)] += value;
+        }
+    }
+    fft_2d(&padded, FTDirection::Forward)
+}
+
+/// Replaces the spectrum of every filtered plane with `restoration(x, y, H, G)`,
+/// where `H` is the transfer function of the blur and `G` the spectrum of the image
+fn restore<F>(
+    image: &mut RgbImage,
+    psf: &PointSpreadFunction,
+    color_mode: ColorMode,
+    restoration: F,
+) where
+    F: Fn(usize, usize, Complex<f64>, Complex<f64>) -> Complex<f64>,
+{
+    let (width, height) = image.dimensions();
+    let transfer_function = psf.transfer_function(width, height);
+    let mut planes = color_mode.split(image);
+
+    for plane in planes[..color_mode.filtered_planes()].iter_mut() {
+        let mut transform = FFT::transform_matrix(plane);
+        for (y, row) in transform.iter_mut().enumerate() {
+            for (x, value) in row.iter_mut().enumerate() {
+                *value = restoration(x, y, transfer_function[y][x], *value);
+            }
+        }
+        *plane = FFT::inverse(&transform)
+            .iter()
+            .map(|row| row.iter().map(|x| x.re.clamp(0.0, 1.0)).collect())
+            .collect();
+    }
+
+    *image = color_mode.merge(&planes, width, height);
+}
+
+/// Inverse filtering, `F = G / H`.
+/// Frequencies where `|H|` is below the threshold are divided by the threshold instead,
+/// as the plain inverse amplifies noise without bounds.
+pub struct InverseFilter {
+    psf: PointSpreadFunction,
+    threshold: f64,
+    color_mode: ColorMode,
+}
+
+impl InverseFilter {
+    pub fn new(psf: PointSpreadFunction, threshold: f64) -> Self {
+        Self {
+            psf,
+            threshold,
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+}
+
+impl Transformation for InverseFilter {
+    fn apply(&self, image: &mut RgbImage) {
+        restore(image, &self.psf, self.color_mode, |_, _, h, g| {
+            let magnitude = h.norm();
+            if magnitude >= self.threshold {
+                g / h
+            } else if magnitude > 0.0 {
+                g * (magnitude / h) / self.threshold
+            } else {
+                g / self.threshold
+            }
+        });
+    }
+}
+
+/// Wiener filtering, `F = conj(H) / (|H|^2 + K) * G`, where `K` is the noise-to-signal power ratio
+pub struct WienerFilter {
+    psf: PointSpreadFunction,
+    noise_to_signal: f64,
+    color_mode: ColorMode,
+}
+
+impl WienerFilter {
+    pub fn new(psf: PointSpreadFunction, noise_to_signal: f64) -> Self {
+        Self {
+            psf,
+            noise_to_signal,
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+}
+
+impl Transformation for WienerFilter {
+    fn apply(&self, image: &mut RgbImage) {
+        restore(image, &self.psf, self.color_mode, |_, _, h, g| {
+            h.conj() / (h.norm_sqr() + self.noise_to_signal) * g
+        });
+    }
+}
+
+/// Constrained least squares restoration, `F = conj(H) / (|H|^2 + gamma * |P|^2) * G`,
+/// where `P` is the spectrum of the Laplacian, so `gamma` controls the smoothness of the result
+pub struct ConstrainedLeastSquaresFilter {
+    psf: PointSpreadFunction,
+    gamma: f64,
+    color_mode: ColorMode,
+}
+
+impl ConstrainedLeastSquaresFilter {
+    pub fn new(psf: PointSpreadFunction, gamma: f64) -> Self {
+        Self {
+            psf,
+            gamma,
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+}
+
+impl Transformation for ConstrainedLeastSquaresFilter {
+    fn apply(&self, image: &mut RgbImage) {
+        let laplacian = [
+            vec![0.0, -1.0, 0.0],
+            vec![-1.0, 4.0, -1.0],
+            vec![0.0, -1.0, 0.0],
+        ];
+        let laplacian = spectrum_of_kernel(&laplacian, image.width(), image.height());
+        restore(image, &self.psf, self.color_mode, |x, y, h, g| {
+            h.conj() / (h.norm_sqr() + self.gamma * laplacian[y][x].norm_sqr()) * g
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::PSNR;
+
+    fn sample_image() -> RgbImage {
+        RgbImage::from_fn(64, 64, |x, y| {
+            let luma = match ((x / 8) + (y / 16)) % 3 {
+                0 => 30,
+                1 => 128,
+                _ => 220,
+            };
+            image::Rgb([luma, luma, luma])
+        })
+    }
+
+    fn blur(image: &RgbImage, psf: &PointSpreadFunction) -> RgbImage {
+        let mut blurred = image.clone();
+        restore(&mut blurred, psf, ColorMode::Grayscale, |_, _, h, g| h * g);
+        blurred
+    }
+
+    fn test_restoration<T: Transformation>(psf: PointSpreadFunction, filter: T) {
+        let original = sample_image();
+        let blurred = blur(&original, &psf);
+        let mut restored = blurred.clone();
+
+        filter.apply(&mut restored);
+
+        let blurred_psnr = PSNR::compare(&original, &blurred);
+        let restored_psnr = PSNR::compare(&original, &restored);
+        assert!(
+            restored_psnr > blurred_psnr + 3.0,
+            "PSNR of restored image {} is not better than of blurred image {}",
+            restored_psnr,
+            blurred_psnr
+        );
+    }
+
+    #[test]
+    fn motion_blur_kernel_is_normalized_line() {
+        let psf = PointSpreadFunction::MotionBlur {
+            length: 5.0,
+            angle: 0.0,
+        };
+        let kernel = psf.kernel();
+        assert_eq!(5, kernel.len());
+        assert_eq!(vec![0.2; 5], kernel[2]);
+    }
+
+    #[test]
+    fn degenerate_kernels_do_not_blur() {
+        let black = PointSpreadFunction::from_image(&GrayImage::new(3, 3));
+        let point = PointSpreadFunction::Gaussian { sigma: 0.0 };
+
+        assert_eq!(vec![vec![1.0]], black.kernel());
+        assert_eq!(vec![vec![1.0]], point.kernel());
+    }
+
+    #[test]
+    fn kernel_larger_than_image_wraps_around() {
+        let psf = PointSpreadFunction::MotionBlur {
+            length: 12.0,
+            angle: 0.0,
+        };
+
+        // three pixels of the line fall on each column
+        let transfer_function = psf.transfer_function(4, 4);
+
+        assert!((transfer_function[0][0] - Complex::new(1.0, 0.0)).norm() < 1e-9);
+        assert!(transfer_function[0][1..].iter().all(|value| value.norm() < 1e-9));
+    }
+
+    #[test]
+    fn inverse_filter_restores_gaussian_blur() {
+        let psf = || PointSpreadFunction::Gaussian { sigma: 1.0 };
+        test_restoration(psf(), InverseFilter::new(psf(), 0.1));
+    }
+
+    #[test]
+    fn wiener_filter_restores_motion_blur() {
+        let psf = || PointSpreadFunction::MotionBlur {
+            length: 7.0,
+            angle: 30.0,
+        };
+        test_restoration(psf(), WienerFilter::new(psf(), 0.001));
+    }
+
+    #[test]
+    fn cls_filter_restores_disk_blur() {
+        let psf = || PointSpreadFunction::Disk { radius: 3.0 };
+        test_restoration(psf(), ConstrainedLeastSquaresFilter::new(psf(), 0.001));
+    }
+}
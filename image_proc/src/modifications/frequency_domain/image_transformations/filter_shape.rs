@@ -0,0 +1,104 @@
+/// Transfer function profile of a frequency-domain filter,
+/// set on the filters with their `with_shape` methods
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum FilterShape {
+    /// Hard cut-off, causes ringing around edges
+    #[default]
+    Ideal,
+    /// Butterworth filter of the given order
+    Butterworth(u32),
+    /// Gaussian falloff with the radius as its standard deviation, no ringing
+    Gaussian,
+}
+
+impl FilterShape {
+    /// Low-pass response for a frequency at `distance_squared` from the centre of the spectrum
+    pub(crate) fn low_pass(&self, distance_squared: f64, radius: f64) -> f64 {
+        if distance_squared == 0.0 {
+            return 1.0;
+        }
+        let radius_squared = radius * radius;
+        match *self {
+            FilterShape::Ideal => {
+                if distance_squared <= radius_squared {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            FilterShape::Butterworth(order) => {
+                1.0 / (1.0 + (distance_squared / radius_squared).powi(order as i32))
+            }
+            FilterShape::Gaussian => f64::exp(-distance_squared / (2.0 * radius_squared)),
+        }
+    }
+
+    pub(crate) fn high_pass(&self, distance_squared: f64, radius: f64) -> f64 {
+        1.0 - self.low_pass(distance_squared, radius)
+    }
+
+    /// Band-cut response for a band spanning radii `from..=to`
+    pub(crate) fn band_cut(&self, distance_squared: f64, from: f64, to: f64) -> f64 {
+        let center_squared = f64::powi((from + to) / 2.0, 2);
+        let width_squared = f64::powi(to - from, 2);
+        let offset_squared = f64::powi(distance_squared - center_squared, 2);
+        match *self {
+            FilterShape::Ideal => {
+                if distance_squared < from * from || distance_squared > to * to {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            FilterShape::Butterworth(order) => {
+                if offset_squared == 0.0 {
+                    return 0.0;
+                }
+                let ratio = distance_squared * width_squared / offset_squared;
+                1.0 / (1.0 + ratio.powi(order as i32))
+            }
+            FilterShape::Gaussian => {
+                if distance_squared * width_squared == 0.0 {
+                    return if offset_squared == 0.0 { 0.0 } else { 1.0 };
+                }
+                1.0 - f64::exp(-offset_squared / (distance_squared * width_squared))
+            }
+        }
+    }
+
+    pub(crate) fn band_pass(&self, distance_squared: f64, from: f64, to: f64) -> f64 {
+        1.0 - self.band_cut(distance_squared, from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FilterShape;
+
+    const SHAPES: [FilterShape; 3] = [
+        FilterShape::Ideal,
+        FilterShape::Butterworth(2),
+        FilterShape::Gaussian,
+    ];
+
+    #[test]
+    fn low_pass_is_monotonic() {
+        for shape in SHAPES {
+            let responses: Vec<_> = (0..50)
+                .map(|d| shape.low_pass((d * d) as f64, 10.0))
+                .collect();
+            assert_eq!(1.0, responses[0]);
+            assert!(responses.windows(2).all(|w| w[0] >= w[1]));
+            assert!(responses[49] < 0.01);
+        }
+    }
+
+    #[test]
+    fn band_cut_rejects_band_center() {
+        for shape in SHAPES {
+            assert!(shape.band_cut(15.0 * 15.0, 10.0, 20.0) < 1e-6);
+            assert!(shape.band_cut(1.0, 10.0, 20.0) > 0.9);
+            assert!(shape.band_cut(100.0 * 100.0, 10.0, 20.0) > 0.9);
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use super::{
+    color_mode::ColorMode,
+    filter_shape::FilterShape,
     image_fourier_transforms::{ImageFourierTransform, FFT},
     util::*,
 };
@@ -6,7 +8,6 @@ use crate::modifications::Transformation;
 use image::{GrayImage, Luma, RgbImage};
 use num::complex::ComplexFloat;
 use num::Complex;
-use std::cmp::Ordering::*;
 use std::convert::identity;
 use std::f64::consts::PI;
 use std::ops::Mul;
@@ -46,105 +47,141 @@ mod debug_utils {
     }
 }
 
-fn apply_mask_filter<TFourier, TMask, TMaskResult>(image: &mut RgbImage, mask: &TMask)
-where
-    TFourier: ImageFourierTransform,
+/// Multiplies the spectrum by the mask, which is addressed in quadrant-swapped coordinates
+fn multiply_by_mask<TMask, TMaskResult>(
+    transform: &mut [Vec<Complex<f64>>],
+    mask: &TMask,
+    skip_center: bool,
+) where
     TMask: Fn(u32, u32) -> TMaskResult,
     TMaskResult: Mul<Complex<f64>, Output = Complex<f64>>,
 {
-    let mut transform = TFourier::transform(image);
+    let height = transform.len() as u32;
+    let width = transform[0].len() as u32;
     for (y, row) in transform.iter_mut().enumerate() {
         for (x, pixel) in row.iter_mut().enumerate() {
-            let (x, y) =
-                swap_quadrant_coordinates(x as u32, y as u32, image.width(), image.height());
-            if x == image.width() / 2 && y == image.height() / 2 {
+            let (x, y) = swap_quadrant_coordinates(x as u32, y as u32, width, height);
+            if skip_center && x == width / 2 && y == height / 2 {
                 continue;
             }
-            let mask_value = mask(x as u32, y as u32);
+            let mask_value = mask(x, y);
             *pixel = mask_value * (*pixel);
         }
     }
+}
 
-    if cfg!(debug_assertions) {
-        debug_utils::save_image(&transform, "_mask.debug.bmp", true);
-    }
+pub(super) fn apply_mask_filter<TFourier, TMask, TMaskResult>(
+    image: &mut RgbImage,
+    mask: &TMask,
+    color_mode: ColorMode,
+) where
+    TFourier: ImageFourierTransform,
+    TMask: Fn(u32, u32) -> TMaskResult,
+    TMaskResult: Mul<Complex<f64>, Output = Complex<f64>>,
+{
+    let (width, height) = image.dimensions();
+    let mut planes = color_mode.split(image);
 
-    let inverse = TFourier::inverse(&transform);
+    let filtered: Vec<_> = planes[..color_mode.filtered_planes()]
+        .iter()
+        .enumerate()
+        .map(|(index, plane)| {
+            let mut transform = TFourier::transform_matrix(plane);
+            multiply_by_mask(&mut transform, mask, true);
 
-    let max_value = {
-        let mut max = 0.0;
-        for value in inverse.iter().flat_map(identity) {
-            if value.abs() > max {
-                max = value.abs();
+            if cfg!(debug_assertions) && index == 0 {
+                debug_utils::save_image(&transform, "_mask.debug.bmp", true);
             }
-        }
-        max
-    };
 
-    let result = GrayImage::from_fn(image.width(), image.height(), |x, y| {
-        let (x, y) = (x as usize, y as usize);
-        let luma = inverse[y][x].abs() / max_value * u8::MAX as f64;
-        Luma([luma as u8])
-    });
+            TFourier::inverse(&transform)
+        })
+        .collect();
+
+    // normalize all filtered planes together, so that the balance between channels is kept
+    let max_value = filtered
+        .iter()
+        .map(|inverse| max(inverse, |x| x.abs()))
+        .fold(0.0, f64::max);
+
+    for (plane, inverse) in planes.iter_mut().zip(filtered) {
+        *plane = inverse
+            .iter()
+            .map(|row| row.iter().map(|x| x.abs() / max_value).collect())
+            .collect();
+    }
 
-    *image = to_rgb(result);
+    *image = color_mode.merge(&planes, width, height);
 }
 
 //(F1) Low-pass filter (high-cut filter)
 pub struct LowPassFilter {
     radius: u32,
+    shape: FilterShape,
+    color_mode: ColorMode,
 }
 
 impl LowPassFilter {
     pub fn new(radius: u32) -> Self {
-        Self { radius }
+        Self {
+            radius,
+            shape: FilterShape::default(),
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+
+    pub fn with_shape(self, shape: FilterShape) -> Self {
+        Self { shape, ..self }
     }
 }
 
 impl Transformation for LowPassFilter {
     fn apply(&self, image: &mut RgbImage) {
-        let radius_squared = self.radius * self.radius;
-        let half_width = image.width() / 2;
-        let half_height = image.height() / 2;
+        let (width, height) = image.dimensions();
         let mask = |x: u32, y: u32| {
-            let x = u32::abs_diff(x, half_width);
-            let y = u32::abs_diff(y, half_height);
-            if x * x + y * y <= radius_squared {
-                1.0
-            } else {
-                0.0
-            }
+            let distance_squared = distance_squared(x, y, width, height);
+            self.shape.low_pass(distance_squared, self.radius as f64)
         };
-        apply_mask_filter::<FFT, _, _>(image, &mask);
+        apply_mask_filter::<FFT, _, _>(image, &mask, self.color_mode);
     }
 }
 
 //(F2) High-pass filter (low-cut filter)
 pub struct HighPassFilter {
     radius: u32,
+    shape: FilterShape,
+    color_mode: ColorMode,
 }
 
 impl HighPassFilter {
     pub fn new(radius: u32) -> Self {
-        Self { radius }
+        Self {
+            radius,
+            shape: FilterShape::default(),
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+
+    pub fn with_shape(self, shape: FilterShape) -> Self {
+        Self { shape, ..self }
     }
 }
 
 impl Transformation for HighPassFilter {
     fn apply(&self, image: &mut RgbImage) {
-        let radius_squared = self.radius * self.radius;
-        let half_width = image.width() / 2;
-        let half_height = image.height() / 2;
+        let (width, height) = image.dimensions();
         let mask = |x: u32, y: u32| {
-            let x = u32::abs_diff(x, half_width);
-            let y = u32::abs_diff(y, half_height);
-            if x * x + y * y > radius_squared {
-                1.0
-            } else {
-                0.0
-            }
+            let distance_squared = distance_squared(x, y, width, height);
+            self.shape.high_pass(distance_squared, self.radius as f64)
         };
-        apply_mask_filter::<FFT, _, _>(image, &mask);
+        apply_mask_filter::<FFT, _, _>(image, &mask, self.color_mode);
     }
 }
 
@@ -152,6 +189,8 @@ impl Transformation for HighPassFilter {
 pub struct BandPassFilter {
     from_radius: u32,
     to_radius: u32,
+    shape: FilterShape,
+    color_mode: ColorMode,
 }
 
 impl BandPassFilter {
@@ -160,30 +199,32 @@ impl BandPassFilter {
         Self {
             from_radius: from,
             to_radius: to,
+            shape: FilterShape::default(),
+            color_mode: ColorMode::default(),
         }
     }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+
+    pub fn with_shape(self, shape: FilterShape) -> Self {
+        Self { shape, ..self }
+    }
 }
 
 impl Transformation for BandPassFilter {
     fn apply(&self, image: &mut RgbImage) {
-        let from_squared = self.from_radius.pow(2);
-        let to_squared = self.to_radius.pow(2);
-        let half_width = image.width() / 2;
-        let half_height = image.height() / 2;
+        let (width, height) = image.dimensions();
         let mask = |x: u32, y: u32| {
-            let x = u32::abs_diff(x, half_width);
-            let y = u32::abs_diff(y, half_height);
-            let distance_squared = x * x + y * y;
-            match (
-                distance_squared.cmp(&from_squared),
-                distance_squared.cmp(&to_squared),
-            ) {
-                (Less, _) => 0.0,
-                (_, Greater) => 0.0,
-                (_, _) => 1.0,
-            }
+            let distance_squared = distance_squared(x, y, width, height);
+            self.shape.band_pass(
+                distance_squared,
+                self.from_radius as f64,
+                self.to_radius as f64,
+            )
         };
-        apply_mask_filter::<FFT, _, _>(image, &mask);
+        apply_mask_filter::<FFT, _, _>(image, &mask, self.color_mode);
     }
 }
 
@@ -191,6 +232,8 @@ impl Transformation for BandPassFilter {
 pub struct BandCutFilter {
     from_radius: u32,
     to_radius: u32,
+    shape: FilterShape,
+    color_mode: ColorMode,
 }
 
 impl BandCutFilter {
@@ -199,41 +242,51 @@ impl BandCutFilter {
         Self {
             from_radius: from,
             to_radius: to,
+            shape: FilterShape::default(),
+            color_mode: ColorMode::default(),
         }
     }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+
+    pub fn with_shape(self, shape: FilterShape) -> Self {
+        Self { shape, ..self }
+    }
 }
 
 impl Transformation for BandCutFilter {
     fn apply(&self, image: &mut RgbImage) {
-        let from_squared = self.from_radius.pow(2);
-        let to_squared = self.to_radius.pow(2);
-        let half_width = image.width() / 2;
-        let half_height = image.height() / 2;
+        let (width, height) = image.dimensions();
         let mask = |x: u32, y: u32| {
-            let x = u32::abs_diff(x, half_width);
-            let y = u32::abs_diff(y, half_height);
-            let distance_squared = x * x + y * y;
-            match (
-                distance_squared.cmp(&from_squared),
-                distance_squared.cmp(&to_squared),
-            ) {
-                (Less, _) => 1.0,
-                (_, Greater) => 1.0,
-                (_, _) => 0.0,
-            }
+            let distance_squared = distance_squared(x, y, width, height);
+            self.shape.band_cut(
+                distance_squared,
+                self.from_radius as f64,
+                self.to_radius as f64,
+            )
         };
-        apply_mask_filter::<FFT, _, _>(image, &mask);
+        apply_mask_filter::<FFT, _, _>(image, &mask, self.color_mode);
     }
 }
 
 //(F5) High-pass filter with detection of edge direction
 pub struct HighPassFilterWithEdgeDetection {
     mask: GrayImage,
+    color_mode: ColorMode,
 }
 
 impl HighPassFilterWithEdgeDetection {
     pub fn new(mask: GrayImage) -> Self {
-        Self { mask }
+        Self {
+            mask,
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
     }
 }
 
@@ -253,7 +306,7 @@ impl Transformation for HighPassFilterWithEdgeDetection {
             }
         };
 
-        apply_mask_filter::<FFT, _, _>(image, &mask);
+        apply_mask_filter::<FFT, _, _>(image, &mask, self.color_mode);
     }
 }
 
@@ -261,11 +314,20 @@ impl Transformation for HighPassFilterWithEdgeDetection {
 pub struct PhaseFilter {
     k: f64,
     l: f64,
+    color_mode: ColorMode,
 }
 
 impl PhaseFilter {
     pub fn new(k: f64, l: f64) -> Self {
-        Self { k, l }
+        Self {
+            k,
+            l,
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
     }
 }
 
@@ -282,7 +344,153 @@ impl Transformation for PhaseFilter {
             )
         };
         let mut image_clone = image.clone();
-        apply_mask_filter::<FFT, _, _>(&mut image_clone, &mask);
+        apply_mask_filter::<FFT, _, _>(&mut image_clone, &mask, self.color_mode);
         *image = image_clone;
     }
 }
+
+/// High-frequency emphasis filter, `offset + multiplier * H`, where `H` is a high-pass response.
+/// Sharpens the image while keeping its low-frequency content.
+pub struct HighFrequencyEmphasisFilter {
+    radius: u32,
+    offset: f64,
+    multiplier: f64,
+    shape: FilterShape,
+    color_mode: ColorMode,
+}
+
+impl HighFrequencyEmphasisFilter {
+    pub fn new(radius: u32, offset: f64, multiplier: f64) -> Self {
+        Self {
+            radius,
+            offset,
+            multiplier,
+            shape: FilterShape::default(),
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+
+    pub fn with_shape(self, shape: FilterShape) -> Self {
+        Self { shape, ..self }
+    }
+}
+
+impl Transformation for HighFrequencyEmphasisFilter {
+    fn apply(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        let mask = |x: u32, y: u32| {
+            let distance_squared = distance_squared(x, y, width, height);
+            self.offset
+                + self.multiplier * self.shape.high_pass(distance_squared, self.radius as f64)
+        };
+        apply_mask_filter::<FFT, _, _>(image, &mask, self.color_mode);
+    }
+}
+
+//(F7) Homomorphic filter
+/// Separates illumination from reflectance by filtering the logarithm of the image.
+/// Frequencies are scaled from `gamma_low` at the centre of the spectrum to `gamma_high`,
+/// so `gamma_low < 1 < gamma_high` evens out lighting and enhances detail.
+/// The result is not rescaled, so `gamma_low` also acts as a power on the average brightness.
+pub struct HomomorphicFilter {
+    gamma_low: f64,
+    gamma_high: f64,
+    cutoff: u32,
+    shape: FilterShape,
+    color_mode: ColorMode,
+}
+
+impl HomomorphicFilter {
+    pub fn new(gamma_low: f64, gamma_high: f64, cutoff: u32) -> Self {
+        Self {
+            gamma_low,
+            gamma_high,
+            cutoff,
+            shape: FilterShape::Gaussian,
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+
+    pub fn with_shape(self, shape: FilterShape) -> Self {
+        Self { shape, ..self }
+    }
+}
+
+impl Transformation for HomomorphicFilter {
+    fn apply(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        let mask = |x: u32, y: u32| {
+            let distance_squared = distance_squared(x, y, width, height);
+            let high_pass = self.shape.high_pass(distance_squared, self.cutoff as f64);
+            self.gamma_low + (self.gamma_high - self.gamma_low) * high_pass
+        };
+
+        let mut planes = self.color_mode.split(image);
+        let filtered: Vec<Vec<Vec<f64>>> = planes[..self.color_mode.filtered_planes()]
+            .iter()
+            .map(|plane| {
+                let logarithm: Vec<Vec<f64>> = plane
+                    .iter()
+                    .map(|row| row.iter().map(|x| f64::ln_1p(x * 255.0)).collect())
+                    .collect();
+                let mut transform = FFT::transform_matrix(&logarithm);
+                multiply_by_mask(&mut transform, &mask, false);
+                FFT::inverse(&transform)
+                    .iter()
+                    .map(|row| row.iter().map(|x| f64::exp_m1(x.re)).collect())
+                    .collect()
+            })
+            .collect();
+
+        // the exponentiated values are brightnesses again, those pushed out of range are clipped
+        // so that a few outliers do not change the brightness of the whole image
+        for (plane, result) in planes.iter_mut().zip(filtered) {
+            *plane = result
+                .iter()
+                .map(|row| row.iter().map(|x| (x / 255.0).clamp(0.0, 1.0)).collect())
+                .collect();
+        }
+
+        *image = self.color_mode.merge(&planes, width, height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn homomorphic_filter_with_unit_gain_preserves_image() {
+        let image = RgbImage::from_fn(8, 8, |x, y| {
+            let luma = (255 * (y * 8 + x) / 63) as u8;
+            image::Rgb([luma, luma, luma])
+        });
+        let mut filtered = image.clone();
+
+        HomomorphicFilter::new(1.0, 1.0, 2).apply(&mut filtered);
+
+        for (original, filtered) in image.pixels().zip(filtered.pixels()) {
+            assert!(u8::abs_diff(original[0], filtered[0]) <= 1);
+        }
+    }
+
+    #[test]
+    fn homomorphic_filter_keeps_brightness_despite_outlier() {
+        let mut image = RgbImage::from_pixel(8, 8, image::Rgb([100, 100, 100]));
+        image.put_pixel(3, 3, image::Rgb([255, 255, 255]));
+        let mut filtered = image.clone();
+
+        HomomorphicFilter::new(1.0, 1.0, 2).apply(&mut filtered);
+
+        assert!(u8::abs_diff(100, filtered.get_pixel(0, 0)[0]) <= 1);
+        assert!(u8::abs_diff(255, filtered.get_pixel(3, 3)[0]) <= 1);
+    }
+}
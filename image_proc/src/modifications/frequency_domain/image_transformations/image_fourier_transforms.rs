@@ -1,59 +1,206 @@
 use super::super::fourier_transform::{dft_2d, fft_2d, FTDirection};
 use super::util::*;
 use crate::modifications::Transformation;
-use image::{GrayImage, RgbImage};
+use image::{Rgb, RgbImage};
 use num::complex::ComplexFloat;
 use num::Complex;
+use std::marker::PhantomData;
 
 pub trait ImageFourierTransform {
-    fn transform(image: &RgbImage) -> Vec<Vec<Complex<f64>>>;
+    fn transform(image: &RgbImage) -> Vec<Vec<Complex<f64>>> {
+        Self::transform_matrix(&image_to_matrix(image))
+    }
+    fn transform_matrix(data: &[Vec<f64>]) -> Vec<Vec<Complex<f64>>>;
     fn inverse(data: &Vec<Vec<Complex<f64>>>) -> Vec<Vec<Complex<f64>>>;
 }
 
-pub struct DFT;
+/// Quantity of the spectrum that is shown
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum SpectrumComponent {
+    #[default]
+    Magnitude,
+    /// Squared magnitude
+    Power,
+    /// Phase encoded as hue, with brightness given by the magnitude
+    Phase,
+}
 
-impl ImageFourierTransform for DFT {
-    fn transform(image: &RgbImage) -> Vec<Vec<Complex<f64>>> {
-        assert_pow_2(image.height());
-        assert_pow_2(image.width());
+/// Mapping of magnitudes to brightness
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum SpectrumScale {
+    #[default]
+    Logarithmic,
+    Linear,
+}
+
+/// Lines drawn over the spectrum
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum SpectrumOverlay {
+    #[default]
+    None,
+    /// Frequency axes crossing at the zero frequency
+    Axes,
+    /// Axes and lines every `spacing` frequencies from them
+    Grid { spacing: u32 },
+}
 
-        let pixels = image_to_matrix(image);
+const AXIS_COLOR: Rgb<u8> = Rgb([255, 0, 0]);
+const GRID_COLOR: Rgb<u8> = Rgb([0, 160, 0]);
+
+/// How [`DFT`] and [`FFT`] render the spectrum
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SpectrumOptions {
+    pub component: SpectrumComponent,
+    pub scale: SpectrumScale,
+    /// Moves the zero frequency from the top-left corner to the centre (fftshift)
+    pub centered: bool,
+    pub overlay: SpectrumOverlay,
+}
 
-        dft_2d(&pixels, FTDirection::Forward)
+impl Default for SpectrumOptions {
+    fn default() -> Self {
+        Self {
+            component: SpectrumComponent::default(),
+            scale: SpectrumScale::default(),
+            centered: true,
+            overlay: SpectrumOverlay::default(),
+        }
     }
+}
 
-    fn inverse(data: &Vec<Vec<Complex<f64>>>) -> Vec<Vec<Complex<f64>>> {
+impl SpectrumOptions {
+    pub fn render(&self, spectrum: &[Vec<Complex<f64>>]) -> RgbImage {
+        let (width, height) = (spectrum[0].len() as u32, spectrum.len() as u32);
+        let strength = |value: &Complex<f64>| match self.component {
+            SpectrumComponent::Power => value.norm_sqr(),
+            SpectrumComponent::Magnitude | SpectrumComponent::Phase => value.abs(),
+        };
+        let max_value = spectrum.iter().flatten().map(strength).fold(0.0, f64::max);
+        let brightness = |value: &Complex<f64>| match self.scale {
+            SpectrumScale::Logarithmic => normalize(strength(value), max_value).0[0],
+            SpectrumScale::Linear => (strength(value) / max_value * u8::MAX as f64) as u8,
+        };
+
+        let mut image = RgbImage::from_fn(width, height, |x, y| {
+            let (x, y) = match self.centered {
+                true => swap_quadrant_coordinates(x, y, width, height),
+                false => (x, y),
+            };
+            let value = &spectrum[y as usize][x as usize];
+            match self.component {
+                SpectrumComponent::Phase => hue_to_rgb(value.arg(), brightness(value)),
+                _ => Rgb([brightness(value); 3]),
+            }
+        });
+        self.draw_overlay(&mut image);
+        image
+    }
+
+    fn draw_overlay(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        let (center_x, center_y) = match self.centered {
+            true => (width / 2, height / 2),
+            false => (0, 0),
+        };
+        let spacing = match self.overlay {
+            SpectrumOverlay::None => return,
+            SpectrumOverlay::Axes => None,
+            SpectrumOverlay::Grid { spacing } => Some(spacing.max(1)),
+        };
+        let on_grid = |coordinate: u32, center: u32| match spacing {
+            Some(spacing) => coordinate.abs_diff(center).is_multiple_of(spacing),
+            None => false,
+        };
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            if x == center_x || y == center_y {
+                *pixel = AXIS_COLOR;
+            } else if on_grid(x, center_x) || on_grid(y, center_y) {
+                *pixel = GRID_COLOR;
+            }
+        }
+    }
+}
+
+/// Fully saturated colour with the hue given by the angle in radians and the given brightness
+fn hue_to_rgb(angle: f64, value: u8) -> Rgb<u8> {
+    let hue = angle.rem_euclid(std::f64::consts::TAU) / std::f64::consts::FRAC_PI_3;
+    let falling = 1.0 - hue.fract();
+    let rising = hue.fract();
+    let (r, g, b) = match hue as u32 {
+        0 => (1.0, rising, 0.0),
+        1 => (falling, 1.0, 0.0),
+        2 => (0.0, 1.0, rising),
+        3 => (0.0, falling, 1.0),
+        4 => (rising, 0.0, 1.0),
+        _ => (1.0, 0.0, falling),
+    };
+    let value = value as f64;
+    Rgb([r, g, b].map(|c: f64| (c * value).round() as u8))
+}
+
+/// Spectrum of the transform `T` rendered with the given options,
+/// e.g. `FFT.with_options(options)`
+pub struct Spectrum<T> {
+    options: SpectrumOptions,
+    transform: PhantomData<T>,
+}
+
+impl<T> Spectrum<T> {
+    pub fn new(options: SpectrumOptions) -> Self {
+        Self {
+            options,
+            transform: PhantomData,
+        }
+    }
+}
+
+impl<T: ImageFourierTransform> Transformation for Spectrum<T> {
+    fn apply(&self, image: &mut RgbImage) {
+        *image = self.options.render(&T::transform(image));
+    }
+}
+
+pub struct DFT;
+
+impl DFT {
+    pub fn with_options(self, options: SpectrumOptions) -> Spectrum<Self> {
+        Spectrum::new(options)
+    }
+}
+
+impl ImageFourierTransform for DFT {
+    fn transform_matrix(data: &[Vec<f64>]) -> Vec<Vec<Complex<f64>>> {
         assert_pow_2(data.len() as u32);
         assert_pow_2(data[0].len() as u32);
         dft_2d(data, FTDirection::Forward)
     }
+
+    fn inverse(data: &Vec<Vec<Complex<f64>>>) -> Vec<Vec<Complex<f64>>> {
+        assert_pow_2(data.len() as u32);
+        assert_pow_2(data[0].len() as u32);
+        dft_2d(data, FTDirection::Inverse)
+    }
 }
 
 impl Transformation for DFT {
     fn apply(&self, image: &mut RgbImage) {
-        let transformed = Self::transform(image);
-
-        let max_value = max(&transformed, |x| x.abs());
-
-        let magnitude = GrayImage::from_fn(image.width(), image.height(), |x, y| {
-            let (x, y) = swap_quadrant_coordinates(x, y, image.width(), image.height());
-            normalize(transformed[y as usize][x as usize].abs(), max_value)
-        });
-
-        *image = to_rgb(magnitude);
+        Spectrum::<Self>::new(SpectrumOptions::default()).apply(image);
     }
 }
 
 pub struct FFT;
 
-impl ImageFourierTransform for FFT {
-    fn transform(image: &RgbImage) -> Vec<Vec<Complex<f64>>> {
-        assert_pow_2(image.height());
-        assert_pow_2(image.width());
-
-        let pixels = image_to_matrix(image);
+impl FFT {
+    pub fn with_options(self, options: SpectrumOptions) -> Spectrum<Self> {
+        Spectrum::new(options)
+    }
+}
 
-        fft_2d(&pixels, FTDirection::Forward)
+impl ImageFourierTransform for FFT {
+    fn transform_matrix(data: &[Vec<f64>]) -> Vec<Vec<Complex<f64>>> {
+        assert_pow_2(data.len() as u32);
+        assert_pow_2(data[0].len() as u32);
+        fft_2d(data, FTDirection::Forward)
     }
 
     fn inverse(data: &Vec<Vec<Complex<f64>>>) -> Vec<Vec<Complex<f64>>> {
@@ -65,15 +212,99 @@ impl ImageFourierTransform for FFT {
 
 impl Transformation for FFT {
     fn apply(&self, image: &mut RgbImage) {
-        let transformed = Self::transform(image);
+        Spectrum::<Self>::new(SpectrumOptions::default()).apply(image);
+    }
+}
+
+/// Replaces the image with the inverse transform of a spectrum, such as one computed by
+/// [`ImageFourierTransform::transform`] and saved with
+/// [`write_spectrum`](super::spectrum_file::write_spectrum). The result is a grayscale image of the size of the spectrum.
+pub struct InverseFourierTransform {
+    spectrum: Vec<Vec<Complex<f64>>>,
+}
 
-        let max_value = max(&transformed, |x| x.abs());
+impl InverseFourierTransform {
+    pub fn new(spectrum: Vec<Vec<Complex<f64>>>) -> Self {
+        Self { spectrum }
+    }
+}
 
-        let magnitude = GrayImage::from_fn(image.width(), image.height(), |x, y| {
-            let (x, y) = swap_quadrant_coordinates(x, y, image.width(), image.height());
-            normalize(transformed[y as usize][x as usize].abs(), max_value)
+impl Transformation for InverseFourierTransform {
+    fn apply(&self, image: &mut RgbImage) {
+        let samples = FFT::inverse(&self.spectrum);
+        let (width, height) = (samples[0].len() as u32, samples.len() as u32);
+        *image = RgbImage::from_fn(width, height, |x, y| {
+            let value = samples[y as usize][x as usize].re * u8::MAX as f64;
+            Rgb([value.round().clamp(0.0, u8::MAX as f64) as u8; 3])
         });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_image() -> RgbImage {
+        RgbImage::from_fn(8, 4, |x, y| Rgb([(x * 30 + y * 7) as u8; 3]))
+    }
+
+    #[test]
+    fn inverse_dft_restores_image() {
+        let image = sample_image();
+        let matrix = image_to_matrix(&image);
+
+        let restored = DFT::inverse(&DFT::transform(&image));
+
+        for (expected, actual) in matrix.iter().flatten().zip(restored.iter().flatten()) {
+            assert!((expected - actual.re).abs() < 1e-9);
+            assert!(actual.im.abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn inverse_transform_of_spectrum_restores_image() {
+        let image = sample_image();
+        let mut restored = RgbImage::new(1, 1);
+
+        InverseFourierTransform::new(FFT::transform(&image)).apply(&mut restored);
+
+        assert_eq!(image, restored);
+    }
+
+    #[test]
+    fn unit_transforms_render_default_spectrum() {
+        let (mut dft, mut fft, mut rendered) = (sample_image(), sample_image(), sample_image());
+
+        DFT {}.apply(&mut dft);
+        FFT {}.apply(&mut fft);
+        FFT.with_options(SpectrumOptions::default())
+            .apply(&mut rendered);
+
+        assert_eq!(rendered, fft);
+        assert_eq!(rendered, dft);
+    }
+
+    #[test]
+    fn overlay_marks_zero_frequency() {
+        let spectrum = FFT::transform(&sample_image());
+        let options = SpectrumOptions {
+            overlay: SpectrumOverlay::Grid { spacing: 2 },
+            ..SpectrumOptions::default()
+        };
+
+        let rendered = options.render(&spectrum);
+
+        assert_eq!(AXIS_COLOR, *rendered.get_pixel(4, 0));
+        assert_eq!(AXIS_COLOR, *rendered.get_pixel(0, 2));
+        assert_eq!(GRID_COLOR, *rendered.get_pixel(2, 1));
+        assert_ne!(GRID_COLOR, *rendered.get_pixel(1, 3));
+    }
 
-        *image = to_rgb(magnitude);
+    #[test]
+    fn hue_follows_phase() {
+        let white = u8::MAX;
+        assert_eq!(Rgb([255, 0, 0]), hue_to_rgb(0.0, white));
+        assert_eq!(Rgb([0, 255, 255]), hue_to_rgb(std::f64::consts::PI, white));
+        assert_eq!(Rgb([255, 0, 0]), hue_to_rgb(-std::f64::consts::TAU, white));
     }
 }
@@ -0,0 +1,108 @@
+use super::super::fourier_transform::FTDirection;
+use super::super::real_transforms::{dct_2d, dct_2d_blocks, haar_2d, walsh_hadamard_2d};
+use super::util::*;
+use crate::modifications::Transformation;
+use image::{GrayImage, RgbImage};
+
+pub trait ImageRealTransform {
+    fn transform(&self, image: &RgbImage) -> Vec<Vec<f64>> {
+        self.transform_matrix(&image_to_matrix(image))
+    }
+    fn transform_matrix(&self, data: &[Vec<f64>]) -> Vec<Vec<f64>>;
+    fn inverse(&self, data: &[Vec<f64>]) -> Vec<Vec<f64>>;
+}
+
+/// Shows magnitudes of the coefficients in logarithmic scale, with the DC coefficient in the top-left corner
+fn visualize<T: ImageRealTransform>(transform: &T, image: &mut RgbImage) {
+    let transformed = transform.transform(image);
+
+    // orthonormal coefficients of 0..1 samples are too small for the logarithm to bring out details,
+    // so they are scaled back to the range of pixel values
+    let magnitude = |x: &f64| x.abs() * u8::MAX as f64;
+    let max_value = max(&transformed, magnitude);
+
+    let coefficients = GrayImage::from_fn(image.width(), image.height(), |x, y| {
+        normalize(magnitude(&transformed[y as usize][x as usize]), max_value)
+    });
+
+    *image = to_rgb(coefficients);
+}
+
+/// Discrete cosine transform of the whole image, or of blocks as in JPEG
+#[derive(Default)]
+pub struct DCT {
+    block_size: Option<usize>,
+}
+
+impl DCT {
+    pub fn new() -> Self {
+        Self { block_size: None }
+    }
+
+    /// Transforms every `block_size`x`block_size` block separately
+    pub fn with_blocks(self, block_size: usize) -> Self {
+        assert!(block_size > 0, "Block size must be positive");
+        Self {
+            block_size: Some(block_size),
+        }
+    }
+
+    fn apply_direction(&self, data: &[Vec<f64>], direction: FTDirection) -> Vec<Vec<f64>> {
+        match self.block_size {
+            Some(block_size) => dct_2d_blocks(data, block_size, direction),
+            None => dct_2d(data, direction),
+        }
+    }
+}
+
+impl ImageRealTransform for DCT {
+    fn transform_matrix(&self, data: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        self.apply_direction(data, FTDirection::Forward)
+    }
+
+    fn inverse(&self, data: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        self.apply_direction(data, FTDirection::Inverse)
+    }
+}
+
+impl Transformation for DCT {
+    fn apply(&self, image: &mut RgbImage) {
+        visualize(self, image);
+    }
+}
+
+pub struct WalshHadamard;
+
+impl ImageRealTransform for WalshHadamard {
+    fn transform_matrix(&self, data: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        walsh_hadamard_2d(data, FTDirection::Forward)
+    }
+
+    fn inverse(&self, data: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        walsh_hadamard_2d(data, FTDirection::Inverse)
+    }
+}
+
+impl Transformation for WalshHadamard {
+    fn apply(&self, image: &mut RgbImage) {
+        visualize(self, image);
+    }
+}
+
+pub struct Haar;
+
+impl ImageRealTransform for Haar {
+    fn transform_matrix(&self, data: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        haar_2d(data, FTDirection::Forward)
+    }
+
+    fn inverse(&self, data: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        haar_2d(data, FTDirection::Inverse)
+    }
+}
+
+impl Transformation for Haar {
+    fn apply(&self, image: &mut RgbImage) {
+        visualize(self, image);
+    }
+}
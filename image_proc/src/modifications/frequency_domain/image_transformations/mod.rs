@@ -1,4 +1,12 @@
 pub mod image_fourier_transforms;
+pub mod image_real_transforms;
+pub mod color_mode;
+pub mod deconvolution;
+pub mod filter_shape;
 pub mod filtration;
+pub mod notch;
+pub mod spectrum_file;
+pub mod template_matching;
+pub mod wavelets;
 
 mod util;
\ No newline at end of file
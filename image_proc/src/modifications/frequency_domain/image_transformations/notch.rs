@@ -0,0 +1,249 @@
+use super::{
+    color_mode::ColorMode,
+    filter_shape::FilterShape,
+    filtration::apply_mask_filter,
+    image_fourier_transforms::{ImageFourierTransform, FFT},
+    util::swap_quadrant_coordinates,
+};
+use crate::modifications::Transformation;
+use image::RgbImage;
+use num::Complex;
+
+/// Position of a notch, as an offset `(u, v)` from the centre of the spectrum
+pub type NotchCenter = (i32, i32);
+
+/// Neighbourhood radius used to estimate the background level of the spectrum
+const BACKGROUND_RADIUS: i32 = 4;
+
+pub enum NotchCenters {
+    /// Explicit list of notches. The notch symmetric around the centre of the spectrum
+    /// is added for each of them automatically.
+    Manual(Vec<NotchCenter>),
+    /// Detects spikes in the magnitude spectrum, at least `min_distance` away from its centre,
+    /// which stand out from their surroundings by more than `threshold` standard deviations
+    Automatic { threshold: f64, min_distance: u32 },
+}
+
+impl NotchCenters {
+    fn resolve(&self, image: &RgbImage) -> Vec<NotchCenter> {
+        let centers = match self {
+            NotchCenters::Manual(centers) => centers.clone(),
+            NotchCenters::Automatic {
+                threshold,
+                min_distance,
+            } => detect_spikes(&FFT::transform(image), *threshold, *min_distance),
+        };
+        centers
+            .into_iter()
+            .flat_map(|(u, v)| [(u, v), (-u, -v)])
+            .collect()
+    }
+}
+
+/// Finds local maxima of the log-magnitude spectrum that are outliers
+/// compared to the mean of their neighbourhood.
+/// Only one notch of each symmetric pair is returned.
+pub(crate) fn detect_spikes(
+    transform: &[Vec<Complex<f64>>],
+    threshold: f64,
+    min_distance: u32,
+) -> Vec<NotchCenter> {
+    let height = transform.len() as u32;
+    let width = transform[0].len() as u32;
+    let (center_x, center_y) = ((width / 2) as i32, (height / 2) as i32);
+
+    let magnitude: Vec<Vec<f64>> = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let (x, y) = swap_quadrant_coordinates(x, y, width, height);
+                    transform[y as usize][x as usize].norm().ln_1p()
+                })
+                .collect()
+        })
+        .collect();
+    let get = |x: i32, y: i32| -> Option<f64> {
+        if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+            return None;
+        }
+        Some(magnitude[y as usize][x as usize])
+    };
+    let neighbours = |x: i32, y: i32, radius: i32| {
+        (-radius..=radius)
+            .flat_map(move |j| (-radius..=radius).map(move |i| (i, j)))
+            .filter(|&offset| offset != (0, 0))
+            .filter_map(move |(i, j)| get(x + i, y + j))
+    };
+
+    let candidates: Vec<(NotchCenter, f64, bool)> = (0..height as i32)
+        .flat_map(|y| (0..width as i32).map(move |x| (x, y)))
+        .filter_map(|(x, y)| {
+            let (u, v) = (x - center_x, y - center_y);
+            let is_upper_half = v > 0 || (v == 0 && u > 0);
+            if !is_upper_half || u * u + v * v <= (min_distance * min_distance) as i32 {
+                return None;
+            }
+            let value = magnitude[y as usize][x as usize];
+            let (sum, count) = neighbours(x, y, BACKGROUND_RADIUS)
+                .fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+            let is_local_max = neighbours(x, y, 1).all(|neighbour| neighbour < value);
+            Some(((u, v), value - sum / count as f64, is_local_max))
+        })
+        .collect();
+
+    let count = candidates.len() as f64;
+    let mean = candidates.iter().map(|(_, r, _)| r).sum::<f64>() / count;
+    let variance = candidates
+        .iter()
+        .map(|(_, r, _)| (r - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    let limit = mean + threshold * variance.sqrt();
+
+    candidates
+        .into_iter()
+        .filter(|&(_, residual, is_local_max)| is_local_max && residual > limit)
+        .map(|(center, _, _)| center)
+        .collect()
+}
+
+/// Response of a notch reject filter at the point `x,y` of a quadrant-swapped spectrum
+fn notch_reject(
+    centers: &[NotchCenter],
+    shape: FilterShape,
+    radius: f64,
+    (x, y): (u32, u32),
+    (width, height): (u32, u32),
+) -> f64 {
+    let u = x as f64 - (width / 2) as f64;
+    let v = y as f64 - (height / 2) as f64;
+    centers
+        .iter()
+        .map(|&(notch_u, notch_v)| {
+            let distance_squared = (u - notch_u as f64).powi(2) + (v - notch_v as f64).powi(2);
+            shape.high_pass(distance_squared, radius)
+        })
+        .product()
+}
+
+/// Notch reject filter, removes periodic noise visible as spikes in the spectrum
+pub struct NotchRejectFilter {
+    centers: NotchCenters,
+    radius: u32,
+    shape: FilterShape,
+    color_mode: ColorMode,
+}
+
+impl NotchRejectFilter {
+    pub fn new(centers: NotchCenters, radius: u32) -> Self {
+        Self {
+            centers,
+            radius,
+            shape: FilterShape::default(),
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+
+    pub fn with_shape(self, shape: FilterShape) -> Self {
+        Self { shape, ..self }
+    }
+}
+
+impl Transformation for NotchRejectFilter {
+    fn apply(&self, image: &mut RgbImage) {
+        let centers = self.centers.resolve(image);
+        let size = image.dimensions();
+        let mask =
+            |x: u32, y: u32| notch_reject(&centers, self.shape, self.radius as f64, (x, y), size);
+        apply_mask_filter::<FFT, _, _>(image, &mask, self.color_mode);
+    }
+}
+
+/// Notch pass filter, keeps only the frequencies around the notches,
+/// which allows to inspect the periodic pattern itself. Without any notches the image is kept.
+pub struct NotchPassFilter {
+    centers: NotchCenters,
+    radius: u32,
+    shape: FilterShape,
+    color_mode: ColorMode,
+}
+
+impl NotchPassFilter {
+    pub fn new(centers: NotchCenters, radius: u32) -> Self {
+        Self {
+            centers,
+            radius,
+            shape: FilterShape::default(),
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+
+    pub fn with_shape(self, shape: FilterShape) -> Self {
+        Self { shape, ..self }
+    }
+}
+
+impl Transformation for NotchPassFilter {
+    fn apply(&self, image: &mut RgbImage) {
+        let centers = self.centers.resolve(image);
+        if centers.is_empty() {
+            // nothing was detected, passing no frequencies at all would just blank the image
+            return;
+        }
+        let size = image.dimensions();
+        let mask = |x: u32, y: u32| {
+            1.0 - notch_reject(&centers, self.shape, self.radius as f64, (x, y), size)
+        };
+        apply_mask_filter::<FFT, _, _>(image, &mask, self.color_mode);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn detects_periodic_noise() {
+        let image = RgbImage::from_fn(64, 64, |x, y| {
+            let (x, y) = (x as f64 / 64.0, y as f64 / 64.0);
+            let background = 128.0 + 60.0 * f64::sin(2.0 * PI * x) * f64::cos(2.0 * PI * y);
+            let noise = 20.0 * f64::cos(2.0 * PI * 8.0 * x);
+            let luma = (background + noise).round() as u8;
+            image::Rgb([luma, luma, luma])
+        });
+
+        let spikes = detect_spikes(&FFT::transform(&image), 5.0, 4);
+
+        assert_eq!(vec![(8, 0)], spikes);
+    }
+
+    #[test]
+    fn notch_reject_removes_symmetric_pairs() {
+        let centers = NotchCenters::Manual(vec![(3, -2)]).resolve(&RgbImage::new(16, 16));
+        let response = |x, y| notch_reject(&centers, FilterShape::Ideal, 1.0, (x, y), (16, 16));
+
+        assert_eq!(0.0, response(8 + 3, 8 - 2));
+        assert_eq!(0.0, response(8 - 3, 8 + 2));
+        assert_eq!(1.0, response(8, 8));
+        assert_eq!(1.0, response(0, 0));
+    }
+
+    #[test]
+    fn notch_pass_without_notches_keeps_image() {
+        let image = RgbImage::from_fn(16, 16, |x, y| image::Rgb([(x * 16 + y) as u8; 3]));
+        let mut filtered = image.clone();
+
+        NotchPassFilter::new(NotchCenters::Manual(vec![]), 2).apply(&mut filtered);
+
+        assert_eq!(image, filtered);
+    }
+}
@@ -0,0 +1,87 @@
+//! Binary file format for complex spectra, so that they can be modified or inverted later.
+//!
+//! The file starts with the magic bytes `CSPC`, followed by the width and height as little-endian `u32`
+//! and the real and imaginary parts of every value as little-endian `f64`, row by row.
+
+use num::Complex;
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+const MAGIC: &[u8; 4] = b"CSPC";
+
+pub fn write_spectrum<W: Write>(spectrum: &[Vec<Complex<f64>>], writer: &mut W) -> Result<()> {
+    let (width, height) = (spectrum[0].len(), spectrum.len());
+    assert!(spectrum.iter().all(|row| row.len() == width));
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&(width as u32).to_le_bytes())?;
+    writer.write_all(&(height as u32).to_le_bytes())?;
+    for value in spectrum.iter().flatten() {
+        writer.write_all(&value.re.to_le_bytes())?;
+        writer.write_all(&value.im.to_le_bytes())?;
+    }
+    writer.flush()
+}
+
+pub fn read_spectrum<R: Read>(reader: &mut R) -> Result<Vec<Vec<Complex<f64>>>> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(Error::new(ErrorKind::InvalidData, "Not a spectrum file"));
+    }
+
+    let mut read_u32 = || -> Result<usize> {
+        let mut bytes = [0u8; 4];
+        reader.read_exact(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes) as usize)
+    };
+    let (width, height) = (read_u32()?, read_u32()?);
+    if width == 0 || height == 0 {
+        return Err(Error::new(ErrorKind::InvalidData, "Spectrum is empty"));
+    }
+
+    let mut read_f64 = || -> Result<f64> {
+        let mut bytes = [0u8; 8];
+        reader.read_exact(&mut bytes)?;
+        Ok(f64::from_le_bytes(bytes))
+    };
+    (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| Ok(Complex::new(read_f64()?, read_f64()?)))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spectrum_survives_round_trip() {
+        let spectrum: Vec<Vec<Complex<f64>>> = (0..3)
+            .map(|y| {
+                (0..5)
+                    .map(|x| Complex::new(x as f64 * 1.5, -(y as f64) / 3.0))
+                    .collect()
+            })
+            .collect();
+        let mut buffer = Vec::new();
+
+        write_spectrum(&spectrum, &mut buffer).unwrap();
+        let read = read_spectrum(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(4 + 8 + 3 * 5 * 16, buffer.len());
+        assert_eq!(spectrum, read);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let error = read_spectrum(&mut b"\x89PNG\r\n\x1a\n".as_slice()).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+
+        let truncated = [MAGIC.as_slice(), &[2, 0, 0, 0, 2, 0, 0, 0, 1]].concat();
+        let error = read_spectrum(&mut truncated.as_slice()).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, error.kind());
+    }
+}
@@ -0,0 +1,140 @@
+use super::util::image_to_matrix;
+use crate::modifications::frequency_domain::convolution::normalized_cross_correlation;
+use crate::modifications::Transformation;
+use image::{Rgb, RgbImage};
+
+/// What [`TemplateMatching`] replaces the image with
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum MatchOutput {
+    /// Normalised cross-correlation mapped from `-1.0..=1.0` to black..white,
+    /// placed at the centres of the windows, with black borders where the template does not fit
+    #[default]
+    CorrelationMap,
+    /// The original image with the best matching window outlined in red
+    BestMatch,
+}
+
+/// Location of a template found in an image
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TemplateMatch {
+    /// Top-left corner of the matching window
+    pub x: u32,
+    pub y: u32,
+    /// Normalised cross-correlation, 1 for a perfect match
+    pub score: f64,
+}
+
+/// Finds the window of the image with the highest normalised cross-correlation with the template,
+/// none if the template does not fit into the image
+pub fn find_template(image: &RgbImage, template: &RgbImage) -> Option<TemplateMatch> {
+    let correlation =
+        normalized_cross_correlation(&image_to_matrix(image), &image_to_matrix(template));
+    best_match(&correlation)
+}
+
+fn best_match(correlation: &[Vec<f64>]) -> Option<TemplateMatch> {
+    let mut best: Option<TemplateMatch> = None;
+    for (y, row) in correlation.iter().enumerate() {
+        for (x, &score) in row.iter().enumerate() {
+            if best.is_none_or(|best| score > best.score) {
+                best = Some(TemplateMatch {
+                    x: x as u32,
+                    y: y as u32,
+                    score,
+                });
+            }
+        }
+    }
+    best
+}
+
+/// Template matching with normalised cross-correlation, computed in the frequency domain for larger templates
+pub struct TemplateMatching {
+    template: RgbImage,
+    output: MatchOutput,
+}
+
+impl TemplateMatching {
+    pub fn new(template: RgbImage) -> Self {
+        Self {
+            template,
+            output: MatchOutput::default(),
+        }
+    }
+
+    pub fn with_output(self, output: MatchOutput) -> Self {
+        Self { output, ..self }
+    }
+}
+
+impl Transformation for TemplateMatching {
+    fn apply(&self, image: &mut RgbImage) {
+        let correlation =
+            normalized_cross_correlation(&image_to_matrix(image), &image_to_matrix(&self.template));
+        let (template_width, template_height) = self.template.dimensions();
+
+        match self.output {
+            MatchOutput::CorrelationMap => {
+                let (offset_x, offset_y) = (template_width / 2, template_height / 2);
+                *image = RgbImage::from_fn(image.width(), image.height(), |x, y| {
+                    let value = (y.checked_sub(offset_y), x.checked_sub(offset_x));
+                    match value {
+                        (Some(y), Some(x))
+                            if (y as usize) < correlation.len()
+                                && (x as usize) < correlation[0].len() =>
+                        {
+                            let value = (correlation[y as usize][x as usize] + 1.0) / 2.0;
+                            Rgb([(value * 255.0).round() as u8; 3])
+                        }
+                        _ => Rgb([0, 0, 0]),
+                    }
+                });
+            }
+            MatchOutput::BestMatch => {
+                let Some(TemplateMatch { x, y, .. }) = best_match(&correlation) else {
+                    return;
+                };
+                let (right, bottom) = (x + template_width - 1, y + template_height - 1);
+                for (pixel_x, pixel_y, pixel) in image.enumerate_pixels_mut() {
+                    let inside = (x..=right).contains(&pixel_x) && (y..=bottom).contains(&pixel_y);
+                    let on_outline =
+                        pixel_x == x || pixel_x == right || pixel_y == y || pixel_y == bottom;
+                    if inside && on_outline {
+                        *pixel = Rgb([255, 0, 0]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_template_cut_from_image() {
+        let image = RgbImage::from_fn(48, 40, |x, y| {
+            let luma = ((x * x + 3 * y * y + x * y) % 251) as u8;
+            Rgb([luma, luma / 2, 255 - luma])
+        });
+        let template = image::imageops::crop_imm(&image, 17, 9, 12, 10).to_image();
+
+        let found = find_template(&image, &template).unwrap();
+
+        assert_eq!((17, 9), (found.x, found.y));
+        assert!(found.score > 0.999);
+    }
+
+    #[test]
+    fn larger_template_is_not_found() {
+        let mut image = RgbImage::from_pixel(8, 8, Rgb([40, 80, 120]));
+        let template = RgbImage::new(9, 4);
+
+        assert_eq!(None, find_template(&image, &template));
+        TemplateMatching::new(template)
+            .with_output(MatchOutput::BestMatch)
+            .apply(&mut image);
+        assert_eq!(RgbImage::from_pixel(8, 8, Rgb([40, 80, 120])), image);
+    }
+}
@@ -31,6 +31,13 @@ pub fn swap_quadrant_coordinates(x: u32, y: u32, width: u32, height: u32) -> (u3
     }
 }
 
+/// Squared distance of the point `x,y` of a quadrant-swapped spectrum from its centre
+pub fn distance_squared(x: u32, y: u32, width: u32, height: u32) -> f64 {
+    let x = u32::abs_diff(x, width / 2) as f64;
+    let y = u32::abs_diff(y, height / 2) as f64;
+    x * x + y * y
+}
+
 pub fn max<TSource, TResult, TMap>(data: &Vec<Vec<TSource>>, map: TMap) -> TResult
 where
     TMap: Fn(&TSource) -> TResult,
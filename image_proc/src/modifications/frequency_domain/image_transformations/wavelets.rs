@@ -0,0 +1,369 @@
+use super::color_mode::ColorMode;
+use super::util::*;
+use crate::modifications::frequency_domain::fourier_transform::FTDirection;
+use crate::modifications::frequency_domain::wavelet::{
+    dwt_2d, max_levels, subband_region, Subband, Wavelet,
+};
+use crate::modifications::Transformation;
+use image::{imageops, GrayImage, Luma, RgbImage};
+use std::ops::Range;
+
+type Vec2D<T> = Vec<Vec<T>>;
+
+/// Median absolute deviation of Gaussian noise divided by its standard deviation
+const MAD_TO_SIGMA: f64 = 0.6745;
+
+/// Shows the subbands of the wavelet decomposition in the Mallat layout.
+/// The approximation is shown linearly, details as magnitudes in logarithmic scale,
+/// each subband normalized separately. Images of any size are mirrored as by [`WaveletDenoise`],
+/// with the layout of the mirrored image cropped to the size of the image.
+/// Levels are limited the same way.
+pub struct DWT {
+    wavelet: Wavelet,
+    levels: u32,
+}
+
+impl DWT {
+    pub fn new(wavelet: Wavelet, levels: u32) -> Self {
+        Self { wavelet, levels }
+    }
+}
+
+impl Transformation for DWT {
+    fn apply(&self, image: &mut RgbImage) {
+        let levels = usable_levels(self.levels, image.width() as usize, image.height() as usize);
+        let padded = mirror_padded(&image_to_matrix(image), levels);
+        let (width, height) = (padded[0].len(), padded.len());
+        let coefficients = dwt_2d(&padded, self.wavelet, levels, FTDirection::Forward);
+        let mut visualization = GrayImage::new(width as u32, height as u32);
+
+        let approximation = (0..height >> levels, 0..width >> levels);
+        draw_region(
+            &mut visualization,
+            &coefficients,
+            approximation,
+            |x, max| Luma([(x / max * u8::MAX as f64).clamp(0.0, u8::MAX as f64) as u8]),
+        );
+        for level in 1..=levels {
+            for subband in Subband::ALL {
+                let region = subband_region(subband, level, width, height);
+                draw_region(&mut visualization, &coefficients, region, |x, max| {
+                    normalize(x.abs() * u8::MAX as f64, max * u8::MAX as f64)
+                });
+            }
+        }
+
+        let visualization =
+            imageops::crop_imm(&visualization, 0, 0, image.width(), image.height()).to_image();
+        *image = to_rgb(visualization);
+    }
+}
+
+fn draw_region<F>(
+    image: &mut GrayImage,
+    coefficients: &[Vec<f64>],
+    (rows, columns): (Range<usize>, Range<usize>),
+    draw: F,
+) where
+    F: Fn(f64, f64) -> Luma<u8>,
+{
+    let max_value = coefficients[rows.clone()]
+        .iter()
+        .flat_map(|row| row[columns.clone()].iter())
+        .fold(0.0, |max, x| f64::max(max, x.abs()));
+    if max_value == 0.0 {
+        return;
+    }
+    for y in rows {
+        for x in columns.clone() {
+            image.put_pixel(x as u32, y as u32, draw(coefficients[y][x], max_value));
+        }
+    }
+}
+
+/// How [`WaveletDenoise`] selects the threshold
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum ThresholdRule {
+    /// Universal threshold `sigma * sqrt(2 ln N)`, shared by all subbands
+    #[default]
+    VisuShrink,
+    /// Threshold `sigma^2 / sigma_x` adapted to the signal variance of each subband
+    BayesShrink,
+}
+
+/// How [`WaveletDenoise`] treats coefficients above the threshold
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum Thresholding {
+    /// Shrinks the coefficients towards zero by the threshold
+    #[default]
+    Soft,
+    /// Keeps the coefficients unchanged
+    Hard,
+}
+
+impl Thresholding {
+    fn apply(&self, value: f64, threshold: f64) -> f64 {
+        match self {
+            _ if value.abs() <= threshold => 0.0,
+            Thresholding::Soft => value.signum() * (value.abs() - threshold),
+            Thresholding::Hard => value,
+        }
+    }
+}
+
+/// Wavelet shrinkage: thresholds the detail coefficients and reconstructs the image.
+/// The noise level is estimated from the finest diagonal subband. The image is decomposed
+/// into at most as many levels as its shorter side can be halved.
+pub struct WaveletDenoise {
+    wavelet: Wavelet,
+    levels: u32,
+    rule: ThresholdRule,
+    thresholding: Thresholding,
+    color_mode: ColorMode,
+}
+
+impl WaveletDenoise {
+    pub fn new(wavelet: Wavelet, levels: u32) -> Self {
+        assert!(levels > 0, "At least one level of decomposition is needed");
+        Self {
+            wavelet,
+            levels,
+            rule: ThresholdRule::default(),
+            thresholding: Thresholding::default(),
+            color_mode: ColorMode::default(),
+        }
+    }
+
+    pub fn with_rule(self, rule: ThresholdRule) -> Self {
+        Self { rule, ..self }
+    }
+
+    pub fn with_thresholding(self, thresholding: Thresholding) -> Self {
+        Self {
+            thresholding,
+            ..self
+        }
+    }
+
+    pub fn with_color_mode(self, color_mode: ColorMode) -> Self {
+        Self { color_mode, ..self }
+    }
+
+    fn denoise(&self, plane: &[Vec<f64>]) -> Vec2D<f64> {
+        let (height, width) = (plane.len(), plane[0].len());
+        let levels = usable_levels(self.levels, width, height);
+        if levels == 0 {
+            // too small to estimate the noise from its details
+            return plane.to_vec();
+        }
+        // the image is mirrored up to a size the decomposition can handle and cropped afterwards
+        let padded = mirror_padded(plane, levels);
+        let (padded_width, padded_height) = (padded[0].len(), padded.len());
+
+        let mut coefficients = dwt_2d(&padded, self.wavelet, levels, FTDirection::Forward);
+        let sigma = estimate_noise(&coefficients);
+        let count = (padded_width * padded_height) as f64;
+
+        for level in 1..=levels {
+            for subband in Subband::ALL {
+                let (rows, columns) = subband_region(subband, level, padded_width, padded_height);
+                let threshold = match self.rule {
+                    ThresholdRule::VisuShrink => sigma * (2.0 * count.ln()).sqrt(),
+                    ThresholdRule::BayesShrink => {
+                        bayes_threshold(&coefficients, (rows.clone(), columns.clone()), sigma)
+                    }
+                };
+                for row in &mut coefficients[rows] {
+                    for value in &mut row[columns.clone()] {
+                        *value = self.thresholding.apply(*value, threshold);
+                    }
+                }
+            }
+        }
+
+        dwt_2d(&coefficients, self.wavelet, levels, FTDirection::Inverse)
+            .into_iter()
+            .take(height)
+            .map(|row| row.into_iter().take(width).collect())
+            .collect()
+    }
+}
+
+impl Transformation for WaveletDenoise {
+    fn apply(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        let mut planes = self.color_mode.split(image);
+        for plane in planes[..self.color_mode.filtered_planes()].iter_mut() {
+            *plane = self.denoise(plane);
+        }
+        *image = self.color_mode.merge(&planes, width, height);
+    }
+}
+
+/// Levels of decomposition used for an image of the given size, at most as many as its shorter side
+/// can be halved, so that mirroring never has to repeat the image
+fn usable_levels(levels: u32, width: usize, height: usize) -> u32 {
+    levels.min(width.min(height).checked_ilog2().unwrap_or(0))
+}
+
+/// Plane mirrored at the right and bottom up to a size divisible by `2^levels`
+fn mirror_padded(plane: &[Vec<f64>], levels: u32) -> Vec2D<f64> {
+    let (height, width) = (plane.len(), plane[0].len());
+    let block = 1 << levels;
+    let padded_width = width.div_ceil(block) * block;
+    let padded_height = height.div_ceil(block) * block;
+    let padded: Vec2D<f64> = (0..padded_height)
+        .map(|y| {
+            (0..padded_width)
+                .map(|x| plane[mirror(y, height)][mirror(x, width)])
+                .collect()
+        })
+        .collect();
+    debug_assert!(max_levels(padded_width, padded_height) >= levels);
+    padded
+}
+
+/// Symmetric extension of the index, repeating the edge samples
+fn mirror(index: usize, length: usize) -> usize {
+    let index = index % (2 * length);
+    match index < length {
+        true => index,
+        false => 2 * length - 1 - index,
+    }
+}
+
+/// Robust estimate of the standard deviation of the noise, from the median of the finest diagonal details
+fn estimate_noise(coefficients: &[Vec<f64>]) -> f64 {
+    let (height, width) = (coefficients.len(), coefficients[0].len());
+    let (rows, columns) = subband_region(Subband::Diagonal, 1, width, height);
+    let mut magnitudes: Vec<f64> = coefficients[rows]
+        .iter()
+        .flat_map(|row| row[columns.clone()].iter().map(|x| x.abs()))
+        .collect();
+    magnitudes.sort_by(f64::total_cmp);
+    magnitudes[magnitudes.len() / 2] / MAD_TO_SIGMA
+}
+
+fn bayes_threshold(
+    coefficients: &[Vec<f64>],
+    (rows, columns): (Range<usize>, Range<usize>),
+    sigma: f64,
+) -> f64 {
+    let values: Vec<f64> = coefficients[rows]
+        .iter()
+        .flat_map(|row| row[columns.clone()].iter().copied())
+        .collect();
+    let variance = values.iter().map(|x| x * x).sum::<f64>() / values.len() as f64;
+    let signal_deviation = (variance - sigma * sigma).max(0.0).sqrt();
+    match signal_deviation > 0.0 {
+        true => sigma * sigma / signal_deviation,
+        // the subband is all noise
+        false => values.iter().fold(0.0, |max, x| f64::max(max, x.abs())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::comparers::MeanSquareError;
+    use crate::modifications::filters::basic::MedianFilter;
+    use image::Rgb;
+
+    fn clean_image() -> RgbImage {
+        RgbImage::from_fn(64, 48, |x, y| {
+            let value = 128.0 + 60.0 * (x as f64 / 5.0).sin() * (y as f64 / 7.0).cos();
+            Rgb([value.round() as u8; 3])
+        })
+    }
+
+    fn with_gaussian_noise(clean: &RgbImage) -> RgbImage {
+        // deterministic, roughly normal noise from a sum of uniform values
+        let mut state = 12345u32;
+        let mut uniform = || {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) as f64 / 65536.0
+        };
+        RgbImage::from_fn(clean.width(), clean.height(), |x, y| {
+            let noise: f64 = (0..12).map(|_| uniform()).sum::<f64>() - 6.0;
+            let value = clean.get_pixel(x, y)[0] as f64 + 15.0 * noise;
+            Rgb([value.round().clamp(0.0, 255.0) as u8; 3])
+        })
+    }
+
+    #[test]
+    fn denoising_reduces_gaussian_noise() {
+        let clean = clean_image();
+        let noisy = with_gaussian_noise(&clean);
+
+        for wavelet in [Wavelet::Haar, Wavelet::Daubechies4, Wavelet::Cdf97] {
+            for rule in [ThresholdRule::VisuShrink, ThresholdRule::BayesShrink] {
+                for thresholding in [Thresholding::Soft, Thresholding::Hard] {
+                    let mut denoised = noisy.clone();
+                    WaveletDenoise::new(wavelet, 3)
+                        .with_rule(rule)
+                        .with_thresholding(thresholding)
+                        .apply(&mut denoised);
+                    assert!(
+                        MeanSquareError::compare(&clean, &denoised)
+                            < MeanSquareError::compare(&clean, &noisy),
+                        "{:?} {:?} {:?}",
+                        wavelet,
+                        rule,
+                        thresholding
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn denoising_beats_median_filter_on_gaussian_noise() {
+        let clean = clean_image();
+        let noisy = with_gaussian_noise(&clean);
+        let mut denoised = noisy.clone();
+        let mut median = noisy.clone();
+
+        WaveletDenoise::new(Wavelet::Daubechies4, 3)
+            .with_rule(ThresholdRule::BayesShrink)
+            .apply(&mut denoised);
+        MedianFilter::new(3, 3).apply(&mut median);
+
+        let wavelet_error = MeanSquareError::compare(&clean, &denoised);
+        let median_error = MeanSquareError::compare(&clean, &median);
+        assert!(
+            wavelet_error < median_error,
+            "{} {}",
+            wavelet_error,
+            median_error
+        );
+    }
+
+    #[test]
+    fn too_many_levels_are_limited_by_image_size() {
+        let image = RgbImage::from_fn(12, 9, |x, y| Rgb([(x * 20 + y) as u8; 3]));
+        let (mut limited, mut excessive) = (image.clone(), image.clone());
+
+        DWT::new(Wavelet::Haar, 3).apply(&mut limited);
+        DWT::new(Wavelet::Haar, 200).apply(&mut excessive);
+        WaveletDenoise::new(Wavelet::Haar, 200).apply(&mut RgbImage::new(1, 1));
+
+        assert_eq!(limited, excessive);
+    }
+
+    #[test]
+    fn decomposition_of_any_size_keeps_size() {
+        let mut image = RgbImage::from_fn(37, 21, |x, y| Rgb([(x * 6 + y) as u8; 3]));
+
+        DWT::new(Wavelet::Daubechies4, 3).apply(&mut image);
+
+        assert_eq!((37, 21), image.dimensions());
+        // the approximation of the mirrored 40x24 image takes its top-left 5x3 pixels
+        assert!(image.get_pixel(4, 2)[0] > image.get_pixel(0, 0)[0]);
+    }
+
+    #[test]
+    fn mirror_repeats_edges() {
+        let indices: Vec<usize> = (0..8).map(|i| mirror(i, 3)).collect();
+        assert_eq!(vec![0, 1, 2, 2, 1, 0, 0, 1], indices);
+    }
+}
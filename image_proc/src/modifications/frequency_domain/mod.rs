@@ -1,2 +1,5 @@
+pub mod convolution;
 pub mod fourier_transform;
-pub mod image_transformations;
\ No newline at end of file
+pub mod image_transformations;
+pub mod real_transforms;
+pub mod wavelet;
\ No newline at end of file
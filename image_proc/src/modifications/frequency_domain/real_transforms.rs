@@ -0,0 +1,169 @@
+//! Separable transforms with real coefficients. All of them are orthonormal,
+//! so the inverse is the transpose and the energy of the data is preserved.
+
+use super::fourier_transform::FTDirection;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+type Vec2D<T> = Vec<Vec<T>>;
+
+/// Applies `transform` to every row, then to every column
+fn separable_2d<F>(samples: &[Vec<f64>], transform: F) -> Vec2D<f64>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    let size_y = samples.len();
+    let size_x = samples[0].len();
+    assert!(samples.iter().all(|x| x.len() == size_x));
+
+    let horizontal_pass: Vec2D<f64> = samples.iter().map(|row| transform(row)).collect();
+    let mut result = vec![vec![0.0; size_x]; size_y];
+
+    for column in 0..size_x {
+        let data: Vec<f64> = horizontal_pass.iter().map(|row| row[column]).collect();
+        for (row, value) in transform(&data).into_iter().enumerate() {
+            result[row][column] = value;
+        }
+    }
+    result
+}
+
+fn assert_pow_2(length: usize) {
+    assert!(length.is_power_of_two(), "Length must be a power of 2");
+}
+
+/// Orthonormal DCT-II basis, `matrix[k][n]` is the weight of sample `n` in coefficient `k`
+fn dct_matrix(length: usize) -> Vec2D<f64> {
+    (0..length)
+        .map(|k| {
+            let scale = match k {
+                0 => (1.0 / length as f64).sqrt(),
+                _ => (2.0 / length as f64).sqrt(),
+            };
+            (0..length)
+                .map(|n| scale * (PI * (2 * n + 1) as f64 * k as f64 / (2 * length) as f64).cos())
+                .collect()
+        })
+        .collect()
+}
+
+fn multiply(matrix: &[Vec<f64>], samples: &[f64], direction: FTDirection) -> Vec<f64> {
+    match direction {
+        FTDirection::Forward => matrix
+            .iter()
+            .map(|row| row.iter().zip(samples).map(|(m, s)| m * s).sum())
+            .collect(),
+        FTDirection::Inverse => (0..samples.len())
+            .map(|n| matrix.iter().zip(samples).map(|(row, s)| row[n] * s).sum())
+            .collect(),
+    }
+}
+
+/// Discrete cosine transform, DCT-II forward and DCT-III inverse, of any length
+pub fn dct(samples: &[f64], direction: FTDirection) -> Vec<f64> {
+    multiply(&dct_matrix(samples.len()), samples, direction)
+}
+
+pub fn dct_2d(samples: &[Vec<f64>], direction: FTDirection) -> Vec2D<f64> {
+    // the basis is computed once per dimension, rather than for every row
+    let row_basis = dct_matrix(samples[0].len());
+    let column_basis = dct_matrix(samples.len());
+    separable_2d(samples, |data| {
+        let basis = match data.len() == row_basis.len() {
+            true => &row_basis,
+            false => &column_basis,
+        };
+        multiply(basis, data, direction)
+    })
+}
+
+/// [`dct_2d`] of every `block_size`x`block_size` block separately, as in JPEG compression.
+/// Blocks at the right and bottom edges may be smaller.
+pub fn dct_2d_blocks(
+    samples: &[Vec<f64>],
+    block_size: usize,
+    direction: FTDirection,
+) -> Vec2D<f64> {
+    assert!(block_size > 0, "Block size must be positive");
+    let mut result: Vec2D<f64> = samples.iter().map(|row| vec![0.0; row.len()]).collect();
+
+    for top in (0..samples.len()).step_by(block_size) {
+        let bottom = usize::min(top + block_size, samples.len());
+        for left in (0..samples[0].len()).step_by(block_size) {
+            let right = usize::min(left + block_size, samples[0].len());
+            let block: Vec2D<f64> = samples[top..bottom]
+                .iter()
+                .map(|row| row[left..right].to_vec())
+                .collect();
+            for (y, row) in dct_2d(&block, direction).into_iter().enumerate() {
+                result[top + y][left..right].copy_from_slice(&row);
+            }
+        }
+    }
+    result
+}
+
+/// Walsh-Hadamard transform in natural (Hadamard) order. It is its own inverse,
+/// the direction is accepted only for symmetry with the other transforms.
+pub fn walsh_hadamard(samples: &[f64], _direction: FTDirection) -> Vec<f64> {
+    assert_pow_2(samples.len());
+    let mut data = samples.to_vec();
+    let mut half = 1;
+    while half < data.len() {
+        for start in (0..data.len()).step_by(2 * half) {
+            for i in start..start + half {
+                let (a, b) = (data[i], data[i + half]);
+                data[i] = a + b;
+                data[i + half] = a - b;
+            }
+        }
+        half *= 2;
+    }
+    let scale = 1.0 / (data.len() as f64).sqrt();
+    data.iter().map(|x| x * scale).collect()
+}
+
+pub fn walsh_hadamard_2d(samples: &[Vec<f64>], direction: FTDirection) -> Vec2D<f64> {
+    separable_2d(samples, |data| walsh_hadamard(data, direction))
+}
+
+/// Full decomposition with the Haar wavelet: averages followed by details from the coarsest
+/// to the finest level, `[a, d_1, d_2, d_2, d_3, d_3, d_3, d_3, ...]`
+pub fn haar(samples: &[f64], direction: FTDirection) -> Vec<f64> {
+    assert_pow_2(samples.len());
+    let mut data = samples.to_vec();
+    let mut buffer = vec![0.0; data.len()];
+
+    match direction {
+        FTDirection::Forward => {
+            let mut length = data.len();
+            while length > 1 {
+                let half = length / 2;
+                for i in 0..half {
+                    let (a, b) = (data[2 * i], data[2 * i + 1]);
+                    buffer[i] = (a + b) * FRAC_1_SQRT_2;
+                    buffer[half + i] = (a - b) * FRAC_1_SQRT_2;
+                }
+                data[..length].copy_from_slice(&buffer[..length]);
+                length = half;
+            }
+        }
+        FTDirection::Inverse => {
+            let mut length = 2;
+            while length <= data.len() {
+                let half = length / 2;
+                for i in 0..half {
+                    let (average, detail) = (data[i], data[half + i]);
+                    buffer[2 * i] = (average + detail) * FRAC_1_SQRT_2;
+                    buffer[2 * i + 1] = (average - detail) * FRAC_1_SQRT_2;
+                }
+                data[..length].copy_from_slice(&buffer[..length]);
+                length *= 2;
+            }
+        }
+    }
+    data
+}
+
+pub fn haar_2d(samples: &[Vec<f64>], direction: FTDirection) -> Vec2D<f64> {
+    separable_2d(samples, |data| haar(data, direction))
+}
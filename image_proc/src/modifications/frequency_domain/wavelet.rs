@@ -0,0 +1,337 @@
+//! Discrete wavelet transform with multi-level (pyramid) decomposition.
+//!
+//! Coefficients are stored in place in the Mallat layout: the approximation of the coarsest level
+//! in the top-left corner, surrounded by the detail subbands of every level, the finest outermost.
+
+use super::fourier_transform::FTDirection;
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::ops::Range;
+
+type Vec2D<T> = Vec<Vec<T>>;
+
+const DAUBECHIES_2: [f64; 4] = [
+    0.482962913144534,
+    0.836516303737808,
+    0.224143868042013,
+    -0.129409522551260,
+];
+const DAUBECHIES_3: [f64; 6] = [
+    0.332670552950083,
+    0.806891509311093,
+    0.459877502118491,
+    -0.135011020010255,
+    -0.085441273882027,
+    0.035226291885710,
+];
+const DAUBECHIES_4: [f64; 8] = [
+    0.230377813308897,
+    0.714846570552916,
+    0.630880767929859,
+    -0.027983769416860,
+    -0.187034811719093,
+    0.030841381835561,
+    0.032883011666885,
+    -0.010597401785069,
+];
+
+// lifting steps of the CDF 9/7 wavelet, as used by JPEG 2000
+const CDF_ALPHA: f64 = -1.586134342059924;
+const CDF_BETA: f64 = -0.052980118572961;
+const CDF_GAMMA: f64 = 0.882911075530934;
+const CDF_DELTA: f64 = 0.443506852043971;
+const CDF_ZETA: f64 = 1.149604398860241;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum Wavelet {
+    #[default]
+    Haar,
+    /// Daubechies wavelets with 2, 3 and 4 vanishing moments
+    Daubechies2,
+    Daubechies3,
+    Daubechies4,
+    /// Biorthogonal Cohen-Daubechies-Feauveau 9/7 wavelet, computed with lifting and symmetric extension
+    Cdf97,
+}
+
+impl Wavelet {
+    /// Low-pass decomposition filter of the orthogonal wavelets
+    fn low_pass(&self) -> Option<&'static [f64]> {
+        match self {
+            Wavelet::Haar => Some(&[FRAC_1_SQRT_2, FRAC_1_SQRT_2]),
+            Wavelet::Daubechies2 => Some(&DAUBECHIES_2),
+            Wavelet::Daubechies3 => Some(&DAUBECHIES_3),
+            Wavelet::Daubechies4 => Some(&DAUBECHIES_4),
+            Wavelet::Cdf97 => None,
+        }
+    }
+}
+
+/// Detail subbands of a single decomposition level, named after the edges they respond to
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Subband {
+    /// Bottom-left, low-pass horizontally and high-pass vertically
+    Horizontal,
+    /// Top-right, high-pass horizontally and low-pass vertically
+    Vertical,
+    /// Bottom-right, high-pass in both directions
+    Diagonal,
+}
+
+impl Subband {
+    pub const ALL: [Subband; 3] = [Subband::Horizontal, Subband::Vertical, Subband::Diagonal];
+}
+
+/// Highest number of levels the data of the given size can be decomposed into
+pub fn max_levels(width: usize, height: usize) -> u32 {
+    u32::min(width.trailing_zeros(), height.trailing_zeros())
+}
+
+/// Rows and columns of `subband` of `level` in the Mallat layout, the finest level is 1
+pub fn subband_region(
+    subband: Subband,
+    level: u32,
+    width: usize,
+    height: usize,
+) -> (Range<usize>, Range<usize>) {
+    assert!(level > 0, "Levels are numbered from 1");
+    let (half_width, half_height) = (width >> level, height >> level);
+    let (low_x, high_x) = (0..half_width, half_width..2 * half_width);
+    let (low_y, high_y) = (0..half_height, half_height..2 * half_height);
+    match subband {
+        Subband::Horizontal => (high_y, low_x),
+        Subband::Vertical => (low_y, high_x),
+        Subband::Diagonal => (high_y, high_x),
+    }
+}
+
+/// Single level of the 1-D transform: approximation followed by details. The length must be even.
+pub fn dwt(samples: &[f64], wavelet: Wavelet, direction: FTDirection) -> Vec<f64> {
+    assert!(
+        samples.len().is_multiple_of(2),
+        "Length must be even, got {}",
+        samples.len()
+    );
+    match (wavelet.low_pass(), direction) {
+        (Some(low_pass), FTDirection::Forward) => analyze(samples, low_pass),
+        (Some(low_pass), FTDirection::Inverse) => synthesize(samples, low_pass),
+        (None, FTDirection::Forward) => cdf97_forward(samples),
+        (None, FTDirection::Inverse) => cdf97_inverse(samples),
+    }
+}
+
+/// Multi-level 2-D transform. Both dimensions must be divisible by `2^levels`.
+pub fn dwt_2d(
+    samples: &[Vec<f64>],
+    wavelet: Wavelet,
+    levels: u32,
+    direction: FTDirection,
+) -> Vec2D<f64> {
+    let (height, width) = (samples.len(), samples[0].len());
+    assert!(samples.iter().all(|row| row.len() == width));
+    assert!(
+        levels <= max_levels(width, height),
+        "{}x{} data cannot be decomposed into {} levels",
+        width,
+        height,
+        levels
+    );
+
+    let mut result = samples.to_vec();
+    let level_sizes = (0..levels).map(|level| (width >> level, height >> level));
+    match direction {
+        FTDirection::Forward => {
+            for (width, height) in level_sizes {
+                transform_region(&mut result, width, height, wavelet, direction);
+            }
+        }
+        FTDirection::Inverse => {
+            for (width, height) in level_sizes.rev() {
+                transform_region(&mut result, width, height, wavelet, direction);
+            }
+        }
+    }
+    result
+}
+
+/// Transforms the rows and columns of the top-left `width`x`height` corner
+fn transform_region(
+    data: &mut [Vec<f64>],
+    width: usize,
+    height: usize,
+    wavelet: Wavelet,
+    direction: FTDirection,
+) {
+    let transform_rows = |data: &mut [Vec<f64>]| {
+        for row in data[..height].iter_mut() {
+            let transformed = dwt(&row[..width], wavelet, direction);
+            row[..width].copy_from_slice(&transformed);
+        }
+    };
+    let transform_columns = |data: &mut [Vec<f64>]| {
+        for x in 0..width {
+            let column: Vec<f64> = data[..height].iter().map(|row| row[x]).collect();
+            for (row, value) in data.iter_mut().zip(dwt(&column, wavelet, direction)) {
+                row[x] = value;
+            }
+        }
+    };
+    // the inverse undoes the passes in reverse order, which matters for the biorthogonal wavelet
+    match direction {
+        FTDirection::Forward => {
+            transform_rows(data);
+            transform_columns(data);
+        }
+        FTDirection::Inverse => {
+            transform_columns(data);
+            transform_rows(data);
+        }
+    }
+}
+
+/// Filter bank of an orthogonal wavelet with periodic extension
+fn analyze(samples: &[f64], low_pass: &[f64]) -> Vec<f64> {
+    let length = samples.len();
+    let half = length / 2;
+    let mut result = vec![0.0; length];
+    for k in 0..half {
+        for (n, &low) in low_pass.iter().enumerate() {
+            let sample = samples[(2 * k + n) % length];
+            result[k] += low * sample;
+            result[half + k] += high_pass(low_pass, n) * sample;
+        }
+    }
+    result
+}
+
+/// Transpose of [`analyze`], which is its inverse for orthogonal filters
+fn synthesize(coefficients: &[f64], low_pass: &[f64]) -> Vec<f64> {
+    let length = coefficients.len();
+    let half = length / 2;
+    let mut result = vec![0.0; length];
+    for k in 0..half {
+        for (n, &low) in low_pass.iter().enumerate() {
+            result[(2 * k + n) % length] +=
+                low * coefficients[k] + high_pass(low_pass, n) * coefficients[half + k];
+        }
+    }
+    result
+}
+
+/// Quadrature mirror of the low-pass filter
+fn high_pass(low_pass: &[f64], n: usize) -> f64 {
+    let value = low_pass[low_pass.len() - 1 - n];
+    match n % 2 {
+        0 => value,
+        _ => -value,
+    }
+}
+
+fn cdf97_forward(samples: &[f64]) -> Vec<f64> {
+    let mut even: Vec<f64> = samples.iter().step_by(2).copied().collect();
+    let mut odd: Vec<f64> = samples.iter().skip(1).step_by(2).copied().collect();
+
+    predict(&mut odd, &even, CDF_ALPHA);
+    update(&mut even, &odd, CDF_BETA);
+    predict(&mut odd, &even, CDF_GAMMA);
+    update(&mut even, &odd, CDF_DELTA);
+
+    even.iter()
+        .map(|x| x * CDF_ZETA)
+        .chain(odd.iter().map(|x| x / CDF_ZETA))
+        .collect()
+}
+
+fn cdf97_inverse(coefficients: &[f64]) -> Vec<f64> {
+    let half = coefficients.len() / 2;
+    let mut even: Vec<f64> = coefficients[..half].iter().map(|x| x / CDF_ZETA).collect();
+    let mut odd: Vec<f64> = coefficients[half..].iter().map(|x| x * CDF_ZETA).collect();
+
+    update(&mut even, &odd, -CDF_DELTA);
+    predict(&mut odd, &even, -CDF_GAMMA);
+    update(&mut even, &odd, -CDF_BETA);
+    predict(&mut odd, &even, -CDF_ALPHA);
+
+    even.into_iter()
+        .zip(odd)
+        .flat_map(|(even, odd)| [even, odd])
+        .collect()
+}
+
+/// `odd[i] += weight * (even[i] + even[i + 1])`, mirrored at the right edge
+fn predict(odd: &mut [f64], even: &[f64], weight: f64) {
+    for i in 0..odd.len() {
+        let next = even[usize::min(i + 1, even.len() - 1)];
+        odd[i] += weight * (even[i] + next);
+    }
+}
+
+/// `even[i] += weight * (odd[i - 1] + odd[i])`, mirrored at the left edge
+fn update(even: &mut [f64], odd: &[f64], weight: f64) {
+    for i in 0..even.len() {
+        let previous = odd[i.saturating_sub(1)];
+        even[i] += weight * (previous + odd[i]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAVELETS: [Wavelet; 5] = [
+        Wavelet::Haar,
+        Wavelet::Daubechies2,
+        Wavelet::Daubechies3,
+        Wavelet::Daubechies4,
+        Wavelet::Cdf97,
+    ];
+
+    #[test]
+    fn low_pass_filters_are_normalized() {
+        for wavelet in WAVELETS {
+            if let Some(low_pass) = wavelet.low_pass() {
+                let sum: f64 = low_pass.iter().sum();
+                let energy: f64 = low_pass.iter().map(|x| x * x).sum();
+                assert!((sum - 2.0f64.sqrt()).abs() < 1e-9, "{:?}", wavelet);
+                assert!((energy - 1.0).abs() < 1e-9, "{:?}", wavelet);
+            }
+        }
+    }
+
+    #[test]
+    fn constant_signal_has_no_details() {
+        let samples = vec![3.0; 16];
+        for wavelet in WAVELETS {
+            let transformed = dwt(&samples, wavelet, FTDirection::Forward);
+            assert!(
+                transformed[8..].iter().all(|x| x.abs() < 1e-9),
+                "{:?}",
+                wavelet
+            );
+            assert!(transformed[..8]
+                .iter()
+                .all(|x| (x - 3.0 * 2.0f64.sqrt()).abs() < 1e-6));
+        }
+    }
+
+    #[test]
+    fn subband_regions_tile_level() {
+        let (width, height) = (16, 8);
+        let mut covered = vec![vec![0; width]; height];
+        for level in 1..=2 {
+            for subband in Subband::ALL {
+                let (rows, columns) = subband_region(subband, level, width, height);
+                for row in &mut covered[rows] {
+                    for value in &mut row[columns.clone()] {
+                        *value += 1;
+                    }
+                }
+            }
+        }
+        for (y, row) in covered.iter().enumerate() {
+            for (x, &value) in row.iter().enumerate() {
+                let approximation = x < width / 4 && y < height / 4;
+                assert_eq!(if approximation { 0 } else { 1 }, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use super::interpolation::{sample, Interpolation};
+use crate::modifications::Transformation;
+use image::{Rgb, RgbImage};
+
+/// Size of the image after a warp
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Canvas {
+    /// The size of the source image, cropping whatever is moved out of it
+    #[default]
+    Crop,
+    /// Large enough for the whole warped image
+    Expand,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Warp {
+    Matrix([[f64; 3]; 2]),
+    Rotation(f64),
+    Shear(f64, f64),
+    Translation(f64, f64),
+}
+
+/// Affine warp of the image, with areas not covered by the source image filled with the background colour
+pub struct AffineTransform {
+    warp: Warp,
+    interpolation: Interpolation,
+    background: Rgb<u8>,
+    canvas: Canvas,
+}
+
+impl AffineTransform {
+    /// Maps source coordinates to target ones as `x' = m[0][0] x + m[0][1] y + m[0][2]` and
+    /// `y' = m[1][0] x + m[1][1] y + m[1][2]`, with pixel centres at integer coordinates
+    pub fn new(matrix: [[f64; 3]; 2]) -> Self {
+        assert!(
+            determinant(&matrix).abs() > 1e-12,
+            "Affine matrix must be invertible"
+        );
+        Self::from_warp(Warp::Matrix(matrix))
+    }
+
+    /// Rotation clockwise by the angle in degrees around the centre of the image
+    pub fn rotation(degrees: f64) -> Self {
+        Self::from_warp(Warp::Rotation(degrees))
+    }
+
+    /// Shear around the centre of the image, `x' = x + x_factor * y` and `y' = y + y_factor * x`
+    pub fn shear(x_factor: f64, y_factor: f64) -> Self {
+        assert!(
+            (1.0 - x_factor * y_factor).abs() > 1e-12,
+            "Shear factors must not flatten the image"
+        );
+        Self::from_warp(Warp::Shear(x_factor, y_factor))
+    }
+
+    /// Shift by the given number of pixels, right and down for positive values
+    pub fn translation(x: f64, y: f64) -> Self {
+        Self::from_warp(Warp::Translation(x, y))
+    }
+
+    fn from_warp(warp: Warp) -> Self {
+        Self {
+            warp,
+            interpolation: Interpolation::Bilinear,
+            background: Rgb([0, 0, 0]),
+            canvas: Canvas::default(),
+        }
+    }
+
+    /// Bilinear by default
+    pub fn with_interpolation(self, interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            ..self
+        }
+    }
+
+    /// Black by default
+    pub fn with_background(self, background: Rgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+
+    pub fn with_canvas(self, canvas: Canvas) -> Self {
+        Self { canvas, ..self }
+    }
+
+    /// Matrix of the warp of an image of the given size, before any shift of an expanded canvas
+    fn matrix(&self, width: u32, height: u32) -> [[f64; 3]; 2] {
+        let (centre_x, centre_y) = ((width as f64 - 1.0) / 2.0, (height as f64 - 1.0) / 2.0);
+        // linear part applied around the centre of the image
+        let around_centre = |[[a, b], [c, d]]: [[f64; 2]; 2]| {
+            [
+                [a, b, centre_x - a * centre_x - b * centre_y],
+                [c, d, centre_y - c * centre_x - d * centre_y],
+            ]
+        };
+        match self.warp {
+            Warp::Matrix(matrix) => matrix,
+            Warp::Rotation(degrees) => {
+                // y points down, so this turns clockwise on the screen
+                let (sin, cos) = degrees.to_radians().sin_cos();
+                around_centre([[cos, -sin], [sin, cos]])
+            }
+            Warp::Shear(x_factor, y_factor) => around_centre([[1.0, x_factor], [y_factor, 1.0]]),
+            Warp::Translation(x, y) => [[1.0, 0.0, x], [0.0, 1.0, y]],
+        }
+    }
+}
+
+impl Transformation for AffineTransform {
+    fn apply(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        let mut matrix = self.matrix(width, height);
+        let (target_width, target_height) = match self.canvas {
+            Canvas::Crop => (width, height),
+            Canvas::Expand => {
+                // bounds of the warped outer edges of the corner pixels
+                let corners = [
+                    (-0.5, -0.5),
+                    (width as f64 - 0.5, -0.5),
+                    (-0.5, height as f64 - 0.5),
+                ]
+                .into_iter()
+                .chain([(width as f64 - 0.5, height as f64 - 0.5)])
+                .map(|point| apply_matrix(&matrix, point));
+                let (mut min, mut max) = ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN));
+                for (x, y) in corners {
+                    min = (min.0.min(x), min.1.min(y));
+                    max = (max.0.max(x), max.1.max(y));
+                }
+                matrix[0][2] -= min.0 + 0.5;
+                matrix[1][2] -= min.1 + 0.5;
+                // tolerance for rounding errors of exact sizes
+                let size = |extent: f64| ((extent - 1e-9).ceil() as u32).max(1);
+                (size(max.0 - min.0), size(max.1 - min.1))
+            }
+        };
+
+        let inverse = invert(&matrix);
+        *image = RgbImage::from_fn(target_width, target_height, |x, y| {
+            let source = apply_matrix(&inverse, (x as f64, y as f64));
+            sample(image, source, self.interpolation, self.background)
+        });
+    }
+}
+
+fn determinant(matrix: &[[f64; 3]; 2]) -> f64 {
+    matrix[0][0] * matrix[1][1] - matrix[0][1] * matrix[1][0]
+}
+
+fn invert(matrix: &[[f64; 3]; 2]) -> [[f64; 3]; 2] {
+    let [[a, b, tx], [c, d, ty]] = *matrix;
+    let det = determinant(matrix);
+    let (a, b, c, d) = (d / det, -b / det, -c / det, a / det);
+    [[a, b, -(a * tx + b * ty)], [c, d, -(c * tx + d * ty)]]
+}
+
+fn apply_matrix(matrix: &[[f64; 3]; 2], (x, y): (f64, f64)) -> (f64, f64) {
+    (
+        matrix[0][0] * x + matrix[0][1] * y + matrix[0][2],
+        matrix[1][0] * x + matrix[1][1] * y + matrix[1][2],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::imageops;
+
+    fn sample_image() -> RgbImage {
+        RgbImage::from_fn(7, 5, |x, y| Rgb([x as u8 * 30, y as u8 * 50, 77]))
+    }
+
+    #[test]
+    fn quarter_turn_matches_lossless_rotation() {
+        let mut image = sample_image();
+
+        AffineTransform::rotation(90.0)
+            .with_interpolation(Interpolation::Nearest)
+            .with_canvas(Canvas::Expand)
+            .apply(&mut image);
+
+        assert_eq!(imageops::rotate90(&sample_image()), image);
+    }
+
+    #[test]
+    fn expanded_canvas_fits_rotated_image() {
+        let mut image = RgbImage::from_pixel(10, 10, Rgb([200; 3]));
+
+        AffineTransform::rotation(45.0)
+            .with_canvas(Canvas::Expand)
+            .apply(&mut image);
+
+        assert_eq!((15, 15), image.dimensions());
+        assert_eq!(&Rgb([0; 3]), image.get_pixel(0, 0));
+        assert_eq!(&Rgb([200; 3]), image.get_pixel(7, 7));
+    }
+
+    #[test]
+    fn translation_fills_background() {
+        let mut whole = sample_image();
+        let mut half = sample_image();
+
+        AffineTransform::translation(2.0, -1.0)
+            .with_background(Rgb([1, 2, 3]))
+            .apply(&mut whole);
+        AffineTransform::translation(0.5, 0.0).apply(&mut half);
+
+        assert_eq!(&Rgb([1, 2, 3]), whole.get_pixel(1, 1));
+        assert_eq!(&Rgb([1, 2, 3]), whole.get_pixel(4, 4));
+        assert_eq!(sample_image().get_pixel(2, 3), whole.get_pixel(4, 2));
+        assert_eq!(&Rgb([45, 100, 77]), half.get_pixel(2, 2));
+    }
+
+    #[test]
+    fn shear_and_matrix_agree() {
+        let mut sheared = sample_image();
+        let mut warped = sample_image();
+
+        AffineTransform::shear(0.5, 0.0).apply(&mut sheared);
+        // the same shear around the centre (3, 2)
+        AffineTransform::new([[1.0, 0.5, -1.0], [0.0, 1.0, 0.0]]).apply(&mut warped);
+
+        assert_eq!(sheared, warped);
+    }
+}
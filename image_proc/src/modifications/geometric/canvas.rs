@@ -0,0 +1,306 @@
+use crate::modifications::Transformation;
+use image::{imageops, Rgb, RgbImage};
+
+/// Cuts out a rectangle of the image, the parts of the rectangle outside the image are left out.
+/// A rectangle entirely outside the image keeps the image.
+pub struct Crop {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Crop {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "Crop size must not be zero");
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Part of the rectangle inside an image of the given size as `x, y, width, height`,
+    /// none if the rectangle lies outside the image
+    pub fn region(&self, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        (self.x < width && self.y < height).then(|| {
+            (
+                self.x,
+                self.y,
+                self.width.min(width - self.x),
+                self.height.min(height - self.y),
+            )
+        })
+    }
+}
+
+impl Transformation for Crop {
+    fn apply(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        if let Some((x, y, width, height)) = self.region(width, height) {
+            *image = imageops::crop_imm(image, x, y, width, height).to_image();
+        }
+    }
+}
+
+/// Removes borders of the colour of the top-left pixel,
+/// with channels differing by at most `tolerance` counted as the same colour
+pub struct AutoCrop {
+    tolerance: u8,
+}
+
+impl AutoCrop {
+    pub fn new(tolerance: u8) -> Self {
+        Self { tolerance }
+    }
+
+    /// Bounds of the content as `x, y, width, height`,
+    /// none for an empty image or one of a single colour
+    pub fn content_bounds(&self, image: &RgbImage) -> Option<(u32, u32, u32, u32)> {
+        let Rgb(border) = *image.get_pixel_checked(0, 0)?;
+        let is_content = |x: u32, y: u32| {
+            let Rgb(pixel) = image.get_pixel(x, y);
+            pixel
+                .iter()
+                .zip(border)
+                .any(|(&c, b)| c.abs_diff(b) > self.tolerance)
+        };
+        let (width, height) = image.dimensions();
+        let row_has_content = |y: u32| (0..width).any(|x| is_content(x, y));
+        let column_has_content = |x: u32| (0..height).any(|y| is_content(x, y));
+
+        let top = (0..height).find(|&y| row_has_content(y))?;
+        let bottom = (0..height).rfind(|&y| row_has_content(y))?;
+        let left = (0..width).find(|&x| column_has_content(x))?;
+        let right = (0..width).rfind(|&x| column_has_content(x))?;
+        Some((left, top, right - left + 1, bottom - top + 1))
+    }
+}
+
+impl Transformation for AutoCrop {
+    fn apply(&self, image: &mut RgbImage) {
+        if let Some((x, y, width, height)) = self.content_bounds(image) {
+            Crop::new(x, y, width, height).apply(image);
+        }
+    }
+}
+
+/// How the pixels added by padding get their values
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PaddingMode {
+    /// A single colour
+    Constant(Rgb<u8>),
+    /// Copies of the edge pixels
+    Replicate,
+    /// The image mirrored at its edges, without repeating the edge pixels
+    Reflect,
+}
+
+impl Default for PaddingMode {
+    fn default() -> Self {
+        PaddingMode::Constant(Rgb([0, 0, 0]))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Padding {
+    Sides {
+        left: u32,
+        top: u32,
+        right: u32,
+        bottom: u32,
+    },
+    PowerOfTwo,
+}
+
+/// Adds borders around the image
+pub struct Pad {
+    padding: Padding,
+    mode: PaddingMode,
+}
+
+impl Pad {
+    pub fn new(left: u32, top: u32, right: u32, bottom: u32) -> Self {
+        Self {
+            padding: Padding::Sides {
+                left,
+                top,
+                right,
+                bottom,
+            },
+            mode: PaddingMode::default(),
+        }
+    }
+
+    /// Pads the right and bottom side to the nearest powers of two, as required by the FFT
+    pub fn to_power_of_two() -> Self {
+        Self {
+            padding: Padding::PowerOfTwo,
+            mode: PaddingMode::default(),
+        }
+    }
+
+    /// Black constant padding by default
+    pub fn with_mode(self, mode: PaddingMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    /// Padding of the left, top, right and bottom side of an image of the given size,
+    /// none if the padded size does not fit into `u32`
+    fn sides(&self, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        match self.padding {
+            Padding::Sides {
+                left,
+                top,
+                right,
+                bottom,
+            } => Some((left, top, right, bottom)),
+            Padding::PowerOfTwo => Some((
+                0,
+                0,
+                width.checked_next_power_of_two()? - width,
+                height.checked_next_power_of_two()? - height,
+            )),
+        }
+    }
+
+    /// Size of an image of the given size after padding, none if it does not fit into `u32`
+    pub fn padded_size(&self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (left, top, right, bottom) = self.sides(width, height)?;
+        Some((
+            width.checked_add(left)?.checked_add(right)?,
+            height.checked_add(top)?.checked_add(bottom)?,
+        ))
+    }
+}
+
+impl Transformation for Pad {
+    fn apply(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        let (left, top, _, _) = self.sides(width, height).expect("Padding is too large");
+        let (padded_width, padded_height) = self
+            .padded_size(width, height)
+            .expect("Padding is too large");
+        let source = |position: u32, offset: u32, size: u32| {
+            let position = position as i64 - offset as i64;
+            match self.mode {
+                PaddingMode::Constant(_) => u32::try_from(position).ok().filter(|&p| p < size),
+                PaddingMode::Replicate => Some(position.clamp(0, size as i64 - 1) as u32),
+                PaddingMode::Reflect => Some(reflect(position, size)),
+            }
+        };
+        let background = match self.mode {
+            PaddingMode::Constant(color) => color,
+            _ => Rgb([0, 0, 0]),
+        };
+        *image = RgbImage::from_fn(padded_width, padded_height, |x, y| {
+            match (source(x, left, width), source(y, top, height)) {
+                (Some(x), Some(y)) => *image.get_pixel(x, y),
+                _ => background,
+            }
+        });
+    }
+}
+
+/// Index mirrored into `0..size` without repeating the edges, repeatedly for large paddings
+fn reflect(position: i64, size: u32) -> u32 {
+    if size == 1 {
+        return 0;
+    }
+    let period = 2 * (size as i64 - 1);
+    let position = position.rem_euclid(period);
+    match position < size as i64 {
+        true => position as u32,
+        false => (period - position) as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(values: &[u8]) -> RgbImage {
+        RgbImage::from_fn(values.len() as u32, 1, |x, _| Rgb([values[x as usize]; 3]))
+    }
+
+    fn values(image: &RgbImage) -> Vec<u8> {
+        image.pixels().map(|pixel| pixel[0]).collect()
+    }
+
+    #[test]
+    fn padding_modes() {
+        let pad = |mode: PaddingMode| {
+            let mut image = row(&[1, 2, 3]);
+            Pad::new(4, 0, 2, 0).with_mode(mode).apply(&mut image);
+            values(&image)
+        };
+
+        assert_eq!(
+            vec![9, 9, 9, 9, 1, 2, 3, 9, 9],
+            pad(PaddingMode::Constant(Rgb([9; 3])))
+        );
+        assert_eq!(vec![1, 1, 1, 1, 1, 2, 3, 3, 3], pad(PaddingMode::Replicate));
+        assert_eq!(vec![1, 2, 3, 2, 1, 2, 3, 2, 1], pad(PaddingMode::Reflect));
+    }
+
+    #[test]
+    fn pads_to_power_of_two() {
+        let mut image = RgbImage::new(100, 64);
+
+        Pad::to_power_of_two().apply(&mut image);
+
+        assert_eq!((128, 64), image.dimensions());
+    }
+
+    #[test]
+    fn auto_crop_removes_uniform_border() {
+        let mut image = RgbImage::from_pixel(20, 10, Rgb([250; 3]));
+        image.put_pixel(4, 3, Rgb([0; 3]));
+        image.put_pixel(12, 6, Rgb([0; 3]));
+        image.put_pixel(15, 8, Rgb([245; 3]));
+
+        AutoCrop::new(5).apply(&mut image);
+
+        assert_eq!((9, 4), image.dimensions());
+        assert_eq!(&Rgb([0; 3]), image.get_pixel(0, 0));
+        assert_eq!(&Rgb([0; 3]), image.get_pixel(8, 3));
+    }
+
+    #[test]
+    fn crop_cuts_rectangle() {
+        let mut image = row(&[1, 2, 3, 4, 5]);
+
+        Crop::new(1, 0, 3, 1).apply(&mut image);
+
+        assert_eq!(vec![2, 3, 4], values(&image));
+    }
+
+    #[test]
+    fn crop_outside_image_keeps_image() {
+        let mut image = row(&[1, 2, 3]);
+
+        Crop::new(3, 0, 2, 1).apply(&mut image);
+
+        assert_eq!(None, Crop::new(3, 0, 2, 1).region(3, 1));
+        assert_eq!(Some((1, 0, 2, 1)), Crop::new(1, 0, 5, 5).region(3, 1));
+        assert_eq!(vec![1, 2, 3], values(&image));
+    }
+
+    #[test]
+    fn auto_crop_of_empty_image_finds_no_content() {
+        let mut image = RgbImage::new(0, 0);
+
+        AutoCrop::new(0).apply(&mut image);
+
+        assert_eq!(None, AutoCrop::new(0).content_bounds(&image));
+        assert_eq!((0, 0), image.dimensions());
+    }
+
+    #[test]
+    fn oversized_padding_has_no_size() {
+        assert_eq!(None, Pad::new(u32::MAX, 0, 1, 0).padded_size(1, 1));
+        assert_eq!(None, Pad::to_power_of_two().padded_size(u32::MAX, 1));
+        assert_eq!(Some((4, 7)), Pad::new(1, 2, 2, 4).padded_size(1, 1));
+    }
+}
@@ -0,0 +1,103 @@
+use image::{Rgb, RgbImage};
+use std::f64::consts::PI;
+
+/// How the values of a resampled image are computed from the source pixels
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Value of the nearest source pixel, fast but jagged
+    #[default]
+    Nearest,
+    /// Linear interpolation of the 2x2 nearest pixels
+    Bilinear,
+    /// Cubic convolution (Keys, a = -0.5) of the 4x4 nearest pixels, sharper than bilinear
+    Bicubic,
+    /// Windowed sinc of the 6x6 nearest pixels, the sharpest, with slight ringing at edges
+    Lanczos3,
+    /// Average of the source pixels covered by the target pixel, weighted by the covered area,
+    /// best for shrinking without aliasing
+    Area,
+}
+
+/// Value of the interpolation kernel at the given distance in pixels
+pub(super) fn kernel(interpolation: Interpolation, distance: f64) -> f64 {
+    let distance = distance.abs();
+    match interpolation {
+        Interpolation::Bilinear => (1.0 - distance).max(0.0),
+        Interpolation::Bicubic => {
+            const A: f64 = -0.5;
+            if distance < 1.0 {
+                ((A + 2.0) * distance - (A + 3.0)) * distance * distance + 1.0
+            } else if distance < 2.0 {
+                ((A * distance - 5.0 * A) * distance + 8.0 * A) * distance - 4.0 * A
+            } else {
+                0.0
+            }
+        }
+        Interpolation::Lanczos3 => match distance {
+            d if d < 1e-9 => 1.0,
+            d if d < 3.0 => 3.0 * (PI * d).sin() * (PI * d / 3.0).sin() / (PI * PI * d * d),
+            _ => 0.0,
+        },
+        Interpolation::Nearest | Interpolation::Area => unreachable!(),
+    }
+}
+
+/// Distance at which the kernel drops to zero
+pub(super) fn kernel_support(interpolation: Interpolation) -> f64 {
+    match interpolation {
+        Interpolation::Bilinear => 1.0,
+        Interpolation::Bicubic => 2.0,
+        Interpolation::Lanczos3 => 3.0,
+        Interpolation::Nearest | Interpolation::Area => unreachable!(),
+    }
+}
+
+/// Value of the image at a point, with pixel centres at integer coordinates.
+/// Points outside the image get the background, samples of points near the border repeat the edge pixels.
+/// Area averaging has no meaning for a single point, so it samples like bilinear interpolation.
+pub(super) fn sample(
+    image: &RgbImage,
+    (x, y): (f64, f64),
+    interpolation: Interpolation,
+    background: Rgb<u8>,
+) -> Rgb<u8> {
+    let (width, height) = (image.width() as f64, image.height() as f64);
+    if !(-0.5..width - 0.5).contains(&x) || !(-0.5..height - 0.5).contains(&y) {
+        return background;
+    }
+    let interpolation = match interpolation {
+        Interpolation::Nearest => {
+            return *image.get_pixel(
+                (x.round() as u32).min(image.width() - 1),
+                (y.round() as u32).min(image.height() - 1),
+            );
+        }
+        Interpolation::Area => Interpolation::Bilinear,
+        interpolation => interpolation,
+    };
+
+    let support = kernel_support(interpolation);
+    let taps = |centre: f64, last: u32| {
+        ((centre - support).ceil() as i64..=(centre + support).floor() as i64)
+            .map(|i| {
+                (
+                    i.clamp(0, last as i64) as u32,
+                    kernel(interpolation, i as f64 - centre),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let (columns, rows) = (taps(x, image.width() - 1), taps(y, image.height() - 1));
+    let mut value = [0.0; 3];
+    let mut total = 0.0;
+    for &(row, weight_y) in &rows {
+        for &(column, weight_x) in &columns {
+            let weight = weight_x * weight_y;
+            for (channel, c) in value.iter_mut().zip(image.get_pixel(column, row).0) {
+                *channel += weight * c as f64;
+            }
+            total += weight;
+        }
+    }
+    Rgb(value.map(|c| (c / total).round().clamp(0.0, u8::MAX as f64) as u8))
+}
@@ -0,0 +1,188 @@
+use super::interpolation::{sample, Interpolation};
+use crate::modifications::Transformation;
+use image::{Rgb, RgbImage};
+
+/// Brown–Conrady lens model with radial (`k1`, `k2`) and tangential (`p1`, `p2`) coefficients,
+/// acting on coordinates relative to the optical centre divided by the focal length.
+/// Negative `k1` gives barrel distortion, positive pincushion.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct BrownConrady {
+    pub k1: f64,
+    pub k2: f64,
+    pub p1: f64,
+    pub p2: f64,
+}
+
+impl BrownConrady {
+    pub fn new(k1: f64, k2: f64, p1: f64, p2: f64) -> Self {
+        Self { k1, k2, p1, p2 }
+    }
+
+    /// Point where the lens images the undistorted point
+    pub fn distort(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let r2 = x * x + y * y;
+        let radial = 1.0 + self.k1 * r2 + self.k2 * r2 * r2;
+        (
+            x * radial + 2.0 * self.p1 * x * y + self.p2 * (r2 + 2.0 * x * x),
+            y * radial + self.p1 * (r2 + 2.0 * y * y) + 2.0 * self.p2 * x * y,
+        )
+    }
+
+    /// Undistorted point imaged at the given point, the inverse of [`BrownConrady::distort`]
+    /// found by fixed-point iteration, none where the iteration does not converge, e.g. outside
+    /// the image circle of strong barrel distortion
+    pub fn undistort(&self, (x, y): (f64, f64)) -> Option<(f64, f64)> {
+        let (mut u, mut v) = (x, y);
+        for _ in 0..20 {
+            let r2 = u * u + v * v;
+            let radial = 1.0 + self.k1 * r2 + self.k2 * r2 * r2;
+            let tangential_x = 2.0 * self.p1 * u * v + self.p2 * (r2 + 2.0 * u * u);
+            let tangential_y = self.p1 * (r2 + 2.0 * v * v) + 2.0 * self.p2 * u * v;
+            (u, v) = ((x - tangential_x) / radial, (y - tangential_y) / radial);
+        }
+        let (distorted_x, distorted_y) = self.distort((u, v));
+        match (distorted_x - x).hypot(distorted_y - y) < 1e-6 {
+            true => Some((u, v)),
+            false => None,
+        }
+    }
+}
+
+/// Removes the distortion of the lens from the image, or with [`LensCorrection::inverse`]
+/// adds it, with areas not covered by the source image filled with the background colour
+pub struct LensCorrection {
+    model: BrownConrady,
+    inverse: bool,
+    center: Option<(f64, f64)>,
+    focal_length: Option<f64>,
+    interpolation: Interpolation,
+    background: Rgb<u8>,
+}
+
+impl LensCorrection {
+    pub fn new(model: BrownConrady) -> Self {
+        Self {
+            model,
+            inverse: false,
+            center: None,
+            focal_length: None,
+            interpolation: Interpolation::Bilinear,
+            background: Rgb([0, 0, 0]),
+        }
+    }
+
+    /// Distorts the image as the lens would, undoing the correction
+    pub fn inverse(self) -> Self {
+        Self {
+            inverse: !self.inverse,
+            ..self
+        }
+    }
+
+    /// Optical centre in pixels, the centre of the image by default
+    pub fn with_center(self, x: f64, y: f64) -> Self {
+        Self {
+            center: Some((x, y)),
+            ..self
+        }
+    }
+
+    /// Focal length in pixels, half the diagonal of the image by default
+    pub fn with_focal_length(self, focal_length: f64) -> Self {
+        assert!(focal_length > 0.0, "Focal length must be positive");
+        Self {
+            focal_length: Some(focal_length),
+            ..self
+        }
+    }
+
+    /// Bilinear by default
+    pub fn with_interpolation(self, interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            ..self
+        }
+    }
+
+    /// Black by default
+    pub fn with_background(self, background: Rgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+}
+
+impl Transformation for LensCorrection {
+    fn apply(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        let (cx, cy) = self
+            .center
+            .unwrap_or(((width as f64 - 1.0) / 2.0, (height as f64 - 1.0) / 2.0));
+        let focal = self
+            .focal_length
+            .unwrap_or((width as f64).hypot(height as f64) / 2.0);
+        *image = RgbImage::from_fn(width, height, |x, y| {
+            let point = ((x as f64 - cx) / focal, (y as f64 - cy) / focal);
+            // the source of every target pixel is where the target point is imaged from
+            let source = match self.inverse {
+                false => Some(self.model.distort(point)),
+                true => self.model.undistort(point),
+            };
+            match source {
+                Some((u, v)) => sample(
+                    image,
+                    (cx + u * focal, cy + v * focal),
+                    self.interpolation,
+                    self.background,
+                ),
+                None => self.background,
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undistort_inverts_distort() {
+        let model = BrownConrady::new(-0.2, 0.05, 0.01, -0.005);
+        for point in [(0.0, 0.0), (0.3, -0.2), (-0.6, 0.5), (0.7, 0.7)] {
+            let (x, y) = model.undistort(model.distort(point)).unwrap();
+
+            assert!((x - point.0).abs() < 1e-9 && (y - point.1).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn undistort_fails_outside_image_circle() {
+        let model = BrownConrady::new(-0.3, 0.0, 0.0, 0.0);
+
+        // the distorted radius never exceeds about 0.7
+        assert_eq!(None, model.undistort((0.9, 0.0)));
+    }
+
+    #[test]
+    fn barrel_distortion_moves_corners_inwards() {
+        let model = BrownConrady::new(-0.2, 0.0, 0.0, 0.0);
+
+        let (x, y) = model.distort((0.6, 0.8));
+
+        assert!((x - 0.48).abs() < 1e-12 && (y - 0.64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn correction_undoes_distortion() {
+        let original = RgbImage::from_fn(64, 48, |x, y| Rgb([(x * 4) as u8, (y * 5) as u8, 90]));
+        let model = BrownConrady::new(-0.15, 0.02, 0.0, 0.0);
+        let mut image = original.clone();
+
+        LensCorrection::new(model).apply(&mut image);
+        LensCorrection::new(model).inverse().apply(&mut image);
+
+        // the corners of the corrected image are lost outside of it, so only the middle survives
+        for (x, y) in [(32, 24), (20, 30), (44, 16)] {
+            let (a, b) = (original.get_pixel(x, y), image.get_pixel(x, y));
+            assert!(a.0.iter().zip(b.0).all(|(&a, b)| a.abs_diff(b) <= 2));
+        }
+    }
+}
@@ -1,5 +1,21 @@
+mod affine;
+mod canvas;
 mod flips;
+mod interpolation;
+mod lens;
+mod perspective;
+mod polar;
+mod rotation;
 mod scale;
+mod tiles;
 
+pub use affine::*;
+pub use canvas::*;
 pub use flips::*;
+pub use interpolation::Interpolation;
+pub use lens::*;
+pub use perspective::*;
+pub use polar::*;
+pub use rotation::*;
 pub use scale::*;
+pub use tiles::*;
@@ -0,0 +1,247 @@
+use super::interpolation::{sample, Interpolation};
+use crate::modifications::Transformation;
+use image::{Rgb, RgbImage};
+
+type Homography = [[f64; 3]; 3];
+
+/// Projective warp of the image by a homography, e.g. to look straight at a document
+/// photographed at an angle, with areas not covered by the source image filled with the background colour
+pub struct PerspectiveTransform {
+    homography: Homography,
+    interpolation: Interpolation,
+    background: Rgb<u8>,
+    size: Option<(u32, u32)>,
+}
+
+impl PerspectiveTransform {
+    /// Maps source coordinates to target ones as `(x', y', w') = H (x, y, 1)` divided by `w'`,
+    /// with pixel centres at integer coordinates. `H` and `-H` give the same mapping.
+    pub fn new(homography: Homography) -> Self {
+        assert!(
+            determinant(&homography).abs() > 1e-12,
+            "Homography must be invertible"
+        );
+        // the sign is chosen so that the origin lies in front of the camera, with `w' > 0`
+        let homography = match homography[2][2] < 0.0 {
+            true => homography.map(|row| row.map(|value| -value)),
+            false => homography,
+        };
+        Self {
+            homography,
+            interpolation: Interpolation::Bilinear,
+            background: Rgb([0, 0, 0]),
+            size: None,
+        }
+    }
+
+    /// Homography mapping each of the four source points to the target point of the same index,
+    /// found with the direct linear transformation. No three of the points may lie on a line.
+    pub fn from_points(source: [(f64, f64); 4], target: [(f64, f64); 4]) -> Self {
+        // each correspondence gives two equations of the eight unknown entries, with h33 = 1
+        let mut system = [[0.0; 9]; 8];
+        for (index, (&(x, y), &(u, v))) in source.iter().zip(&target).enumerate() {
+            system[2 * index] = [x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y, u];
+            system[2 * index + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y, v];
+        }
+        let h = solve(system).expect("No three of the points may lie on a line");
+        Self::new([[h[0], h[1], h[2]], [h[3], h[4], h[5]], [h[6], h[7], 1.0]])
+    }
+
+    /// Maps the quadrilateral with the given corners, in the top-left, top-right, bottom-right,
+    /// bottom-left order, onto the whole image, sized by the longer of the opposite sides
+    pub fn rectify(corners: [(f64, f64); 4]) -> Self {
+        let length = |a: usize, b: usize| {
+            let ((x1, y1), (x2, y2)) = (corners[a], corners[b]);
+            (x2 - x1).hypot(y2 - y1)
+        };
+        let width = length(0, 1).max(length(3, 2)).round().max(1.0);
+        let height = length(0, 3).max(length(1, 2)).round().max(1.0);
+        Self::rectify_to_size(corners, width as u32, height as u32)
+    }
+
+    /// Maps the quadrilateral with the given corners, as for [`PerspectiveTransform::rectify`],
+    /// onto the whole image of the given size
+    pub fn rectify_to_size(corners: [(f64, f64); 4], width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "Target size must not be zero");
+        let (right, bottom) = (width as f64 - 1.0, height as f64 - 1.0);
+        Self::from_points(
+            corners,
+            [(0.0, 0.0), (right, 0.0), (right, bottom), (0.0, bottom)],
+        )
+        .with_size(width, height)
+    }
+
+    /// Bilinear by default
+    pub fn with_interpolation(self, interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            ..self
+        }
+    }
+
+    /// Black by default
+    pub fn with_background(self, background: Rgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+
+    /// Size of the warped image, the size of the source image by default
+    pub fn with_size(self, width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "Target size must not be zero");
+        Self {
+            size: Some((width, height)),
+            ..self
+        }
+    }
+
+    pub fn homography(&self) -> Homography {
+        self.homography
+    }
+}
+
+impl Transformation for PerspectiveTransform {
+    fn apply(&self, image: &mut RgbImage) {
+        let (width, height) = self.size.unwrap_or(image.dimensions());
+        let inverse = invert(&self.homography);
+        *image = RgbImage::from_fn(width, height, |x, y| {
+            match project(&inverse, (x as f64, y as f64)) {
+                Some(source) => sample(image, source, self.interpolation, self.background),
+                None => self.background,
+            }
+        });
+    }
+}
+
+/// Point mapped by the homography, none for points sent to infinity or from behind the camera
+fn project(homography: &Homography, (x, y): (f64, f64)) -> Option<(f64, f64)> {
+    let [u, v, w] = homography.map(|row| row[0] * x + row[1] * y + row[2]);
+    match w > 1e-12 {
+        true => Some((u / w, v / w)),
+        false => None,
+    }
+}
+
+fn determinant(m: &Homography) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+/// Inverse by the adjugate, scaled so that the sign of the projective coordinate is kept
+fn invert(m: &Homography) -> Homography {
+    let cofactor = |row: usize, column: usize| {
+        let (r1, r2) = ((row + 1) % 3, (row + 2) % 3);
+        let (c1, c2) = ((column + 1) % 3, (column + 2) % 3);
+        m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]
+    };
+    let det = determinant(m);
+    std::array::from_fn(|row| std::array::from_fn(|column| cofactor(column, row) / det))
+}
+
+/// Solves a linear system given as rows of coefficients followed by the right-hand side,
+/// with Gaussian elimination and partial pivoting, none for singular systems
+fn solve<const N: usize, const M: usize>(mut system: [[f64; M]; N]) -> Option<[f64; N]> {
+    for column in 0..N {
+        let pivot = (column..N)
+            .max_by(|&a, &b| system[a][column].abs().total_cmp(&system[b][column].abs()))?;
+        if system[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        system.swap(column, pivot);
+        for row in column + 1..N {
+            let factor = system[row][column] / system[column][column];
+            let pivot_row = system[column];
+            for (value, pivot_value) in system[row].iter_mut().zip(pivot_row).skip(column) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+    let mut solution = [0.0; N];
+    for row in (0..N).rev() {
+        let known: f64 = (row + 1..N)
+            .map(|column| system[row][column] * solution[column])
+            .sum();
+        solution[row] = (system[row][N] - known) / system[row][row];
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE: [(f64, f64); 4] = [(0.0, 0.0), (9.0, 0.0), (9.0, 9.0), (0.0, 9.0)];
+
+    #[test]
+    fn homography_maps_corners() {
+        let target = [(2.0, 1.0), (30.0, 4.0), (25.0, 20.0), (1.0, 15.0)];
+
+        let transform = PerspectiveTransform::from_points(SQUARE, target);
+
+        for (source, (u, v)) in SQUARE.into_iter().zip(target) {
+            let (x, y) = project(&transform.homography(), source).unwrap();
+            assert!((x - u).abs() < 1e-9 && (y - v).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn shifted_points_give_translation() {
+        let target = SQUARE.map(|(x, y)| (x + 3.0, y - 2.0));
+        let mut image = RgbImage::from_fn(10, 10, |x, y| Rgb([x as u8 * 20, y as u8 * 20, 0]));
+        let original = image.clone();
+
+        PerspectiveTransform::from_points(SQUARE, target).apply(&mut image);
+
+        assert_eq!(original.get_pixel(4, 5), image.get_pixel(7, 3));
+        assert_eq!(&Rgb([0, 0, 0]), image.get_pixel(1, 1));
+    }
+
+    #[test]
+    fn negated_homography_gives_same_warp() {
+        let homography = [[1.2, 0.1, -2.0], [-0.2, 0.9, 3.0], [0.001, 0.002, 1.0]];
+        let negated = homography.map(|row| row.map(|value| -value));
+        let image = RgbImage::from_fn(20, 20, |x, y| Rgb([x as u8 * 12, y as u8 * 12, 50]));
+        let (mut expected, mut warped) = (image.clone(), image);
+
+        PerspectiveTransform::new(homography).apply(&mut expected);
+        PerspectiveTransform::new(negated).apply(&mut warped);
+
+        assert_eq!(expected, warped);
+        assert_ne!(&Rgb([0, 0, 0]), warped.get_pixel(10, 10));
+    }
+
+    #[test]
+    fn rectification_undoes_perspective() {
+        // a white page on a dark table, seen at an angle
+        let page = RgbImage::from_pixel(40, 30, Rgb([255; 3]));
+        let corners = [(12.0, 10.0), (50.0, 14.0), (56.0, 44.0), (6.0, 40.0)];
+        let mut photo = page;
+        PerspectiveTransform::from_points(
+            [(0.0, 0.0), (39.0, 0.0), (39.0, 29.0), (0.0, 29.0)],
+            corners,
+        )
+        .with_size(64, 56)
+        .with_background(Rgb([20; 3]))
+        .apply(&mut photo);
+        assert_eq!(&Rgb([20; 3]), photo.get_pixel(2, 2));
+
+        let mut rectified = photo;
+        PerspectiveTransform::rectify(corners).apply(&mut rectified);
+
+        let (width, height) = rectified.dimensions();
+        assert_eq!((50, 31), (width, height));
+        for (x, y, pixel) in rectified.enumerate_pixels() {
+            if (1..width - 1).contains(&x) && (1..height - 1).contains(&y) {
+                assert_eq!(&Rgb([255; 3]), pixel, "at {}, {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn solver_rejects_singular_system() {
+        assert_eq!(None, solve([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0]]));
+        assert_eq!(
+            Some([1.0, 2.0]),
+            solve([[1.0, 1.0, 3.0], [1.0, -1.0, -1.0]])
+        );
+    }
+}
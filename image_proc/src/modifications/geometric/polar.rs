@@ -0,0 +1,284 @@
+use super::interpolation::{sample, Interpolation};
+use crate::modifications::Transformation;
+use image::{imageops, Rgb, RgbImage};
+use std::f64::consts::PI;
+
+/// How the distance from the centre is laid out along the x axis of a polar image
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum RadialScale {
+    #[default]
+    Linear,
+    /// Distances growing exponentially from the minimum radius along the axis,
+    /// `r = r_min * (r_max / r_min)^fraction`, so scaling the image about the centre
+    /// shifts the polar image horizontally
+    Logarithmic,
+}
+
+impl RadialScale {
+    /// Distance from the centre at the given fraction of the radial axis
+    fn radius(&self, fraction: f64, (min_radius, max_radius): (f64, f64)) -> f64 {
+        match self {
+            RadialScale::Linear => fraction * max_radius,
+            RadialScale::Logarithmic => {
+                min_radius * (fraction * log_span(min_radius, max_radius)).exp()
+            }
+        }
+    }
+
+    /// Fraction of the radial axis at the given distance from the centre,
+    /// distances below the minimum radius are at the start of a logarithmic axis
+    fn fraction(&self, radius: f64, (min_radius, max_radius): (f64, f64)) -> f64 {
+        match self {
+            RadialScale::Linear => radius / max_radius,
+            RadialScale::Logarithmic => {
+                (radius / min_radius).ln().max(0.0) / log_span(min_radius, max_radius)
+            }
+        }
+    }
+}
+
+/// Logarithm of the ratio of the radii, kept positive for radii that are the same or swapped
+fn log_span(min_radius: f64, max_radius: f64) -> f64 {
+    (max_radius / min_radius).ln().max(f64::EPSILON)
+}
+
+/// Remaps the image to polar coordinates, with the distance from the centre along
+/// the x axis and the angle, clockwise from the right, along the y axis, so rotating the image
+/// about the centre shifts the polar image vertically. [`PolarTransform::inverse`] maps
+/// a polar image back.
+pub struct PolarTransform {
+    scale: RadialScale,
+    inverse: bool,
+    center: Option<(f64, f64)>,
+    max_radius: Option<f64>,
+    min_radius: f64,
+    size: Option<(u32, u32)>,
+    interpolation: Interpolation,
+    background: Rgb<u8>,
+}
+
+impl PolarTransform {
+    pub fn new(scale: RadialScale) -> Self {
+        Self {
+            scale,
+            inverse: false,
+            center: None,
+            max_radius: None,
+            min_radius: 1.0,
+            size: None,
+            interpolation: Interpolation::Bilinear,
+            background: Rgb([0, 0, 0]),
+        }
+    }
+
+    /// Maps a polar image back to cartesian coordinates
+    pub fn inverse(self) -> Self {
+        Self {
+            inverse: !self.inverse,
+            ..self
+        }
+    }
+
+    /// Centre of the cartesian image, its middle by default
+    pub fn with_center(self, x: f64, y: f64) -> Self {
+        Self {
+            center: Some((x, y)),
+            ..self
+        }
+    }
+
+    /// Distance from the centre at the end of the radial axis,
+    /// by default the distance to the farthest corner of the cartesian image
+    pub fn with_max_radius(self, max_radius: f64) -> Self {
+        assert!(max_radius > 0.0, "Radius must be positive");
+        Self {
+            max_radius: Some(max_radius),
+            ..self
+        }
+    }
+
+    /// Distance from the centre at the start of a logarithmic radial axis, one pixel by default
+    pub fn with_min_radius(self, min_radius: f64) -> Self {
+        assert!(min_radius > 0.0, "Radius must be positive");
+        Self { min_radius, ..self }
+    }
+
+    /// Size of the remapped image, the size of the source image by default
+    pub fn with_size(self, width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "Target size must not be zero");
+        Self {
+            size: Some((width, height)),
+            ..self
+        }
+    }
+
+    /// Bilinear by default
+    pub fn with_interpolation(self, interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            ..self
+        }
+    }
+
+    /// Black by default
+    pub fn with_background(self, background: Rgb<u8>) -> Self {
+        Self { background, ..self }
+    }
+
+    /// Centre and the minimum and maximum radius of the cartesian image of the given size
+    fn geometry(&self, width: u32, height: u32) -> ((f64, f64), (f64, f64)) {
+        let (cx, cy) = self
+            .center
+            .unwrap_or(((width as f64 - 1.0) / 2.0, (height as f64 - 1.0) / 2.0));
+        let max_radius = self.max_radius.unwrap_or_else(|| {
+            let (dx, dy) = (
+                cx.max(width as f64 - 1.0 - cx),
+                cy.max(height as f64 - 1.0 - cy),
+            );
+            dx.hypot(dy).max(1.0)
+        });
+        ((cx, cy), (self.min_radius, max_radius))
+    }
+
+    fn to_polar(&self, image: &RgbImage, (width, height): (u32, u32)) -> RgbImage {
+        let ((cx, cy), radii) = self.geometry(image.width(), image.height());
+        let last_column = (width as f64 - 1.0).max(1.0);
+        RgbImage::from_fn(width, height, |x, y| {
+            let radius = self.scale.radius(x as f64 / last_column, radii);
+            let angle = 2.0 * PI * y as f64 / height as f64;
+            let source = (cx + radius * angle.cos(), cy + radius * angle.sin());
+            sample(image, source, self.interpolation, self.background)
+        })
+    }
+
+    fn to_cartesian(&self, image: &RgbImage, (width, height): (u32, u32)) -> RgbImage {
+        let ((cx, cy), radii) = self.geometry(width, height);
+        let last_column = (image.width() as f64 - 1.0).max(1.0);
+        let rows = image.height() as f64;
+        // the first row repeated below the last, so angles just below a full turn interpolate
+        // between both ends
+        let mut wrapped = RgbImage::new(image.width(), image.height() + 1);
+        imageops::replace(&mut wrapped, image, 0, 0);
+        let first_row = imageops::crop_imm(image, 0, 0, image.width(), 1).to_image();
+        imageops::replace(&mut wrapped, &first_row, 0, image.height() as i64);
+
+        RgbImage::from_fn(width, height, |x, y| {
+            let (dx, dy) = (x as f64 - cx, y as f64 - cy);
+            let fraction = self.scale.fraction(dx.hypot(dy), radii);
+            let angle = dy.atan2(dx).rem_euclid(2.0 * PI);
+            let source = (fraction * last_column, angle / (2.0 * PI) * rows);
+            sample(&wrapped, source, self.interpolation, self.background)
+        })
+    }
+}
+
+impl Transformation for PolarTransform {
+    fn apply(&self, image: &mut RgbImage) {
+        let size = self.size.unwrap_or(image.dimensions());
+        *image = match self.inverse {
+            false => self.to_polar(image, size),
+            true => self.to_cartesian(image, size),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rings() -> RgbImage {
+        RgbImage::from_fn(65, 65, |x, y| {
+            let (dx, dy) = (x as f64 - 32.0, y as f64 - 32.0);
+            Rgb([(dx.hypot(dy) * 6.0) as u8, 0, 0])
+        })
+    }
+
+    #[test]
+    fn rings_become_columns() {
+        let mut image = rings();
+
+        PolarTransform::new(RadialScale::Linear)
+            .with_max_radius(32.0)
+            .with_size(33, 36)
+            .apply(&mut image);
+
+        for x in 0..33 {
+            let column: Vec<u8> = (0..36).map(|y| image.get_pixel(x, y)[0]).collect();
+            assert!(column.iter().all(|&c| c.abs_diff(column[0]) <= 6));
+            assert!(column[0].abs_diff((x * 6) as u8) <= 6);
+        }
+    }
+
+    #[test]
+    fn rotation_shifts_rows() {
+        let image = RgbImage::from_fn(41, 41, |x, y| Rgb([(x * 6) as u8, (y * 6) as u8, 0]));
+        let polar = |image: &RgbImage| {
+            let mut image = image.clone();
+            PolarTransform::new(RadialScale::Logarithmic)
+                .with_interpolation(Interpolation::Nearest)
+                .with_min_radius(1.3)
+                .with_max_radius(20.0)
+                .with_size(20, 36)
+                .apply(&mut image);
+            image
+        };
+        let (original, rotated) = (polar(&image), polar(&imageops::rotate90(&image)));
+
+        for y in 0..36 {
+            for x in 0..20 {
+                assert_eq!(original.get_pixel(x, y), rotated.get_pixel(x, (y + 9) % 36));
+            }
+        }
+    }
+
+    #[test]
+    fn scaling_shifts_columns() {
+        // rings twice as far apart, with a radial axis of five doublings over 50 columns
+        let rings = |spacing: f64| {
+            RgbImage::from_fn(65, 65, |x, y| {
+                let (dx, dy) = (x as f64 - 32.0, y as f64 - 32.0);
+                Rgb([(dx.hypot(dy) * 6.0 / spacing) as u8, 0, 0])
+            })
+        };
+        let polar = |mut image: RgbImage| {
+            PolarTransform::new(RadialScale::Logarithmic)
+                .with_min_radius(1.0)
+                .with_max_radius(32.0)
+                .with_size(51, 36)
+                .apply(&mut image);
+            image
+        };
+        let (original, scaled) = (polar(rings(1.0)), polar(rings(2.0)));
+
+        for y in 0..36 {
+            for x in 10..51 {
+                let (a, b) = (original.get_pixel(x - 10, y)[0], scaled.get_pixel(x, y)[0]);
+                assert!(a.abs_diff(b) <= 2, "{} and {} at {}, {}", a, b, x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_restores_image() {
+        for scale in [RadialScale::Linear, RadialScale::Logarithmic] {
+            let original = RgbImage::from_fn(48, 32, |x, y| Rgb([(x * 5) as u8, (y * 7) as u8, 0]));
+            let mut image = original.clone();
+
+            PolarTransform::new(scale)
+                .with_size(256, 512)
+                .apply(&mut image);
+            PolarTransform::new(scale)
+                .inverse()
+                .with_size(48, 32)
+                .apply(&mut image);
+
+            for (a, b) in original.pixels().zip(image.pixels()) {
+                assert!(
+                    a.0.iter().zip(b.0).all(|(&a, b)| a.abs_diff(b) <= 4),
+                    "{:?}",
+                    scale
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::modifications::Transformation;
+use image::{imageops, RgbImage};
+
+/// Lossless clockwise rotation by 90 degrees
+pub struct Rotate90;
+
+impl Transformation for Rotate90 {
+    fn apply(&self, image: &mut RgbImage) {
+        *image = imageops::rotate90(image);
+    }
+}
+
+/// Lossless rotation by 180 degrees
+pub struct Rotate180;
+
+impl Transformation for Rotate180 {
+    fn apply(&self, image: &mut RgbImage) {
+        imageops::rotate180_in_place(image);
+    }
+}
+
+/// Lossless clockwise rotation by 270 degrees
+pub struct Rotate270;
+
+impl Transformation for Rotate270 {
+    fn apply(&self, image: &mut RgbImage) {
+        *image = imageops::rotate270(image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn rotations_compose() {
+        let original = RgbImage::from_fn(5, 3, |x, y| Rgb([x as u8, y as u8, 0]));
+        let mut image = original.clone();
+
+        Rotate90.apply(&mut image);
+        assert_eq!((3, 5), image.dimensions());
+        assert_eq!(&Rgb([0, 0, 0]), image.get_pixel(2, 0));
+        Rotate90.apply(&mut image);
+        let mut half_turn = original.clone();
+        Rotate180.apply(&mut half_turn);
+        assert_eq!(half_turn, image);
+        Rotate270.apply(&mut image);
+        Rotate90.apply(&mut image);
+        Rotate180.apply(&mut image);
+
+        assert_eq!(original, image);
+    }
+}
@@ -1,21 +1,66 @@
+use super::interpolation::{kernel, kernel_support, Interpolation};
 use crate::modifications::Transformation;
-use image::{ImageBuffer, RgbImage};
+use image::{imageops, ImageBuffer, Rgb, RgbImage};
+
+/// How a target size with a different aspect ratio than the image is reached
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum AspectMode {
+    /// Exactly the target size, distorting the image
+    #[default]
+    Stretch,
+    /// The largest size with the aspect ratio of the image fitting into the target size
+    Fit,
+    /// The target size filled with the image scaled keeping its aspect ratio, with the overflow cropped
+    Fill,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Size {
+    Factors(f64, f64),
+    Target(u32, u32, AspectMode),
+    Width(u32),
+    Height(u32),
+}
 
 pub struct Scale {
-    factor_x: f64,
-    factor_y: f64,
+    size: Size,
+    interpolation: Interpolation,
 }
 
 //(G4) Image shrinking (--shrink) & (G5) Image enlargement (--enlarge)
 impl Transformation for Scale {
     fn apply(&self, image: &mut RgbImage) {
-        let mut new_image: RgbImage = ImageBuffer::new(
-            (image.width() as f64 * self.factor_x) as u32,
-            (image.height() as f64 * self.factor_y) as u32,
-        );
-        for (x, y, pixel) in new_image.enumerate_pixels_mut() {
-            let (src_x, src_y) = self.src_pixel_from_target(x, y);
-            *pixel = *image.get_pixel(src_x, src_y);
+        let (width, height, factor_x, factor_y) =
+            self.scaled_dimensions(image.width(), image.height());
+        let mut new_image: RgbImage = match self.interpolation {
+            Interpolation::Nearest => {
+                let mut new_image: RgbImage = ImageBuffer::new(width, height);
+                for (x, y, pixel) in new_image.enumerate_pixels_mut() {
+                    let (src_x, src_y) = src_pixel_from_target(x, y, factor_x, factor_y);
+                    *pixel = *image
+                        .get_pixel(src_x.min(image.width() - 1), src_y.min(image.height() - 1));
+                }
+                new_image
+            }
+            _ => resample(
+                image,
+                (width, height),
+                (factor_x, factor_y),
+                self.interpolation,
+            ),
+        };
+        if let Size::Target(target_width, target_height, AspectMode::Fill) = self.size {
+            // the overflow is split evenly between both sides
+            let (target_width, target_height) =
+                (target_width.min(width), target_height.min(height));
+            new_image = imageops::crop_imm(
+                &new_image,
+                (width - target_width) / 2,
+                (height - target_height) / 2,
+                target_width,
+                target_height,
+            )
+            .to_image();
         }
         *image = new_image;
     }
@@ -23,15 +68,251 @@ impl Transformation for Scale {
 
 impl Scale {
     pub fn new(factor_x: f64, factor_y: f64) -> Self {
-        Self { factor_x, factor_y }
+        Self {
+            size: Size::Factors(factor_x, factor_y),
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    /// Scales the image to the given size, keeping its aspect ratio as chosen by `aspect`
+    pub fn to_size(width: u32, height: u32, aspect: AspectMode) -> Self {
+        assert!(width > 0 && height > 0, "Target size must not be zero");
+        Self {
+            size: Size::Target(width, height, aspect),
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    /// Scales the image to the given width, keeping its aspect ratio
+    pub fn to_width(width: u32) -> Self {
+        assert!(width > 0, "Target size must not be zero");
+        Self {
+            size: Size::Width(width),
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    /// Scales the image to the given height, keeping its aspect ratio
+    pub fn to_height(height: u32) -> Self {
+        assert!(height > 0, "Target size must not be zero");
+        Self {
+            size: Size::Height(height),
+            interpolation: Interpolation::default(),
+        }
     }
 
-    /// Returns a pair of `x,y` coordinates in the source image,
-    /// corresponding to the specified `x,y` coordinates in the target image
-    fn src_pixel_from_target(&self, target_x: u32, target_y: u32) -> (u32, u32) {
-        (
-            (target_x as f64 / self.factor_x) as u32,
-            (target_y as f64 / self.factor_y) as u32,
-        )
+    pub fn with_interpolation(self, interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            ..self
+        }
+    }
+
+    /// Size of the image scaled from the given size, before the cropping of [`AspectMode::Fill`],
+    /// with the factors of both axes
+    fn scaled_dimensions(&self, width: u32, height: u32) -> (u32, u32, f64, f64) {
+        let (source_width, source_height) = (width as f64, height as f64);
+        let keep_aspect = |factor: f64| {
+            let scaled = |size: f64| ((size * factor).round() as u32).max(1);
+            (scaled(source_width), scaled(source_height), factor, factor)
+        };
+        match self.size {
+            Size::Factors(factor_x, factor_y) => (
+                (source_width * factor_x) as u32,
+                (source_height * factor_y) as u32,
+                factor_x,
+                factor_y,
+            ),
+            Size::Target(target_width, target_height, aspect) => {
+                let factor_x = target_width as f64 / source_width;
+                let factor_y = target_height as f64 / source_height;
+                match aspect {
+                    AspectMode::Stretch => (target_width, target_height, factor_x, factor_y),
+                    AspectMode::Fit => keep_aspect(factor_x.min(factor_y)),
+                    AspectMode::Fill => keep_aspect(factor_x.max(factor_y)),
+                }
+            }
+            Size::Width(target_width) => keep_aspect(target_width as f64 / source_width),
+            Size::Height(target_height) => keep_aspect(target_height as f64 / source_height),
+        }
+    }
+}
+
+/// Returns a pair of `x,y` coordinates in the source image,
+/// corresponding to the specified `x,y` coordinates in the target image
+fn src_pixel_from_target(target_x: u32, target_y: u32, factor_x: f64, factor_y: f64) -> (u32, u32) {
+    (
+        (target_x as f64 / factor_x) as u32,
+        (target_y as f64 / factor_y) as u32,
+    )
+}
+
+/// Source pixels contributing to every target pixel along one axis, with normalised weights.
+/// When shrinking, the kernel is stretched over all source pixels covered by the target pixel.
+fn contributions(
+    source: u32,
+    target: u32,
+    factor: f64,
+    interpolation: Interpolation,
+) -> Vec<Vec<(usize, f64)>> {
+    let last = source as i64 - 1;
+    (0..target)
+        .map(|index| {
+            let mut weights: Vec<(usize, f64)> = match interpolation {
+                Interpolation::Area => {
+                    let (start, end) = (index as f64 / factor, (index + 1) as f64 / factor);
+                    (start.floor() as i64..end.ceil() as i64)
+                        .map(|i| {
+                            let covered = end.min((i + 1) as f64) - start.max(i as f64);
+                            (i.clamp(0, last) as usize, covered.max(0.0))
+                        })
+                        .collect()
+                }
+                _ => {
+                    // centres of pixels lie at half-integer coordinates
+                    let centre = (index as f64 + 0.5) / factor;
+                    let stretch = (1.0 / factor).max(1.0);
+                    let support = kernel_support(interpolation) * stretch;
+                    ((centre - support).floor() as i64..=(centre + support).ceil() as i64)
+                        .map(|i| {
+                            let distance = (i as f64 + 0.5 - centre) / stretch;
+                            (i.clamp(0, last) as usize, kernel(interpolation, distance))
+                        })
+                        .collect()
+                }
+            };
+            let total: f64 = weights.iter().map(|(_, weight)| weight).sum();
+            for (_, weight) in weights.iter_mut() {
+                *weight /= total;
+            }
+            weights
+        })
+        .collect()
+}
+
+/// Separable resampling, first along rows and then along columns
+fn resample(
+    image: &RgbImage,
+    (width, height): (u32, u32),
+    (factor_x, factor_y): (f64, f64),
+    interpolation: Interpolation,
+) -> RgbImage {
+    let columns = contributions(image.width(), width, factor_x, interpolation);
+    let rows = contributions(image.height(), height, factor_y, interpolation);
+
+    let mut horizontal = Vec::with_capacity((width * image.height()) as usize);
+    for y in 0..image.height() {
+        for weights in &columns {
+            let mut value = [0.0; 3];
+            for &(x, weight) in weights {
+                let Rgb(pixel) = image.get_pixel(x as u32, y);
+                for (channel, c) in value.iter_mut().zip(pixel) {
+                    *channel += weight * *c as f64;
+                }
+            }
+            horizontal.push(value);
+        }
+    }
+
+    RgbImage::from_fn(width, height, |x, y| {
+        let mut value = [0.0; 3];
+        for &(row, weight) in &rows[y as usize] {
+            let pixel = horizontal[row * width as usize + x as usize];
+            for (channel, c) in value.iter_mut().zip(pixel) {
+                *channel += weight * c;
+            }
+        }
+        Rgb(value.map(|c| c.round().clamp(0.0, u8::MAX as f64) as u8))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERPOLATIONS: [Interpolation; 5] = [
+        Interpolation::Nearest,
+        Interpolation::Bilinear,
+        Interpolation::Bicubic,
+        Interpolation::Lanczos3,
+        Interpolation::Area,
+    ];
+
+    fn gradient() -> RgbImage {
+        RgbImage::from_fn(16, 8, |x, y| Rgb([x as u8 * 16, y as u8 * 32, 100]))
+    }
+
+    #[test]
+    fn constant_image_stays_constant() {
+        for interpolation in INTERPOLATIONS {
+            for factor in [0.3, 1.0, 2.5] {
+                let mut image = RgbImage::from_pixel(10, 7, Rgb([30, 60, 90]));
+
+                Scale::new(factor, factor)
+                    .with_interpolation(interpolation)
+                    .apply(&mut image);
+
+                assert!(
+                    image.pixels().all(|pixel| *pixel == Rgb([30, 60, 90])),
+                    "{:?} x{}",
+                    interpolation,
+                    factor
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nearest_repeats_pixels() {
+        let mut image = gradient();
+
+        Scale::new(2.0, 2.0).apply(&mut image);
+
+        assert_eq!((32, 16), image.dimensions());
+        assert_eq!(gradient().get_pixel(3, 2), image.get_pixel(7, 5));
+    }
+
+    #[test]
+    fn area_averages_covered_pixels() {
+        let mut image = RgbImage::from_fn(8, 8, |x, y| Rgb([((x + y) % 2 * 200) as u8; 3]));
+
+        Scale::new(0.5, 0.5)
+            .with_interpolation(Interpolation::Area)
+            .apply(&mut image);
+
+        assert!(image.pixels().all(|pixel| *pixel == Rgb([100; 3])));
+    }
+
+    #[test]
+    fn interpolation_enlarges_smoothly() {
+        for interpolation in [Interpolation::Bilinear, Interpolation::Bicubic] {
+            let mut image = gradient();
+
+            Scale::new(4.0, 1.0)
+                .with_interpolation(interpolation)
+                .apply(&mut image);
+
+            let row: Vec<u8> = (8..56).map(|x| image.get_pixel(x, 0)[0]).collect();
+            assert!(row.windows(2).all(|pair| pair[1] >= pair[0]));
+            assert!(row.windows(2).all(|pair| pair[1] - pair[0] <= 6));
+        }
+    }
+
+    #[test]
+    fn target_size_keeps_aspect_ratio() {
+        let scaled = |scale: Scale| {
+            let mut image = gradient();
+            scale.apply(&mut image);
+            image.dimensions()
+        };
+
+        assert_eq!(
+            (10, 20),
+            scaled(Scale::to_size(10, 20, AspectMode::Stretch))
+        );
+        assert_eq!((10, 5), scaled(Scale::to_size(10, 20, AspectMode::Fit)));
+        assert_eq!((10, 20), scaled(Scale::to_size(10, 20, AspectMode::Fill)));
+        assert_eq!((12, 6), scaled(Scale::to_width(12)));
+        assert_eq!((64, 32), scaled(Scale::to_height(32)));
     }
 }
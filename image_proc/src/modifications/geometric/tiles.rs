@@ -0,0 +1,58 @@
+use image::{imageops, RgbImage};
+
+/// A part of an image with the position of its top-left corner
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub image: RgbImage,
+}
+
+/// Splits the image into tiles of the given size, row by row, tiles at the right and bottom edges
+/// are smaller if the size of the image is not a multiple of the tile size
+pub fn split_into_tiles(image: &RgbImage, tile_width: u32, tile_height: u32) -> Vec<Tile> {
+    assert!(
+        tile_width > 0 && tile_height > 0,
+        "Tile size must not be zero"
+    );
+    let (width, height) = image.dimensions();
+    (0..height)
+        .step_by(tile_height as usize)
+        .flat_map(|y| (0..width).step_by(tile_width as usize).map(move |x| (x, y)))
+        .map(|(x, y)| Tile {
+            x,
+            y,
+            image: imageops::crop_imm(image, x, y, tile_width, tile_height).to_image(),
+        })
+        .collect()
+}
+
+/// Image just large enough for all the tiles, with parts covered by no tile left black
+/// and overlapping tiles drawn in order
+pub fn assemble_tiles(tiles: &[Tile]) -> RgbImage {
+    let width = tiles.iter().map(|tile| tile.x + tile.image.width()).max();
+    let height = tiles.iter().map(|tile| tile.y + tile.image.height()).max();
+    let mut image = RgbImage::new(width.unwrap_or(0), height.unwrap_or(0));
+    for tile in tiles {
+        imageops::replace(&mut image, &tile.image, tile.x as i64, tile.y as i64);
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn tiles_reassemble_image() {
+        let image = RgbImage::from_fn(10, 7, |x, y| Rgb([x as u8, y as u8, 0]));
+
+        let tiles = split_into_tiles(&image, 4, 3);
+
+        assert_eq!(9, tiles.len());
+        assert_eq!((2, 1), tiles[8].image.dimensions());
+        assert_eq!((8, 6), (tiles[8].x, tiles[8].y));
+        assert_eq!(image, assemble_tiles(&tiles));
+    }
+}
@@ -0,0 +1,163 @@
+use super::util::{cumulative, modify_planes, HistogramChannels};
+use crate::modifications::Transformation;
+use image::{GrayImage, RgbImage};
+
+/// Contrast-limited adaptive histogram equalisation.
+///
+/// The image is divided into a grid of tiles, each equalised with its own histogram clipped to limit
+/// the amplification of noise. Pixels are mapped by bilinear interpolation between the four nearest tiles.
+pub struct Clahe {
+    tiles_x: u32,
+    tiles_y: u32,
+    clip_limit: f64,
+    channels: HistogramChannels,
+}
+
+impl Clahe {
+    /// `clip_limit` is the highest allowed count of a histogram bin, relative to the average count,
+    /// the excess is redistributed evenly. 1 leaves the image almost unchanged, higher values add more contrast.
+    pub fn new(tiles_x: u32, tiles_y: u32, clip_limit: f64) -> Self {
+        assert!(
+            tiles_x > 0 && tiles_y > 0,
+            "Grid must have at least one tile"
+        );
+        assert!(clip_limit >= 1.0, "Clip limit must be at least 1");
+        Self {
+            tiles_x,
+            tiles_y,
+            clip_limit,
+            channels: HistogramChannels::default(),
+        }
+    }
+
+    pub fn with_channels(self, channels: HistogramChannels) -> Self {
+        Self { channels, ..self }
+    }
+
+    fn equalize(&self, plane: &mut GrayImage) {
+        let (width, height) = plane.dimensions();
+        let tiles_x = self.tiles_x.min(width);
+        let tiles_y = self.tiles_y.min(height);
+        let bounds =
+            |tile: u32, tiles: u32, size: u32| (tile * size / tiles, (tile + 1) * size / tiles);
+
+        let lookups: Vec<Vec<[u8; 256]>> = (0..tiles_y)
+            .map(|tile_y| {
+                (0..tiles_x)
+                    .map(|tile_x| {
+                        let (left, right) = bounds(tile_x, tiles_x, width);
+                        let (top, bottom) = bounds(tile_y, tiles_y, height);
+                        let mut histogram = [0u32; 256];
+                        for y in top..bottom {
+                            for x in left..right {
+                                histogram[plane.get_pixel(x, y)[0] as usize] += 1;
+                            }
+                        }
+                        self.tile_lookup(histogram)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let neighbours = |coordinate: u32, tiles: u32, size: u32| {
+            // position in units of tiles, relative to the centre of the first one
+            let position = (coordinate as f64 + 0.5) * tiles as f64 / size as f64 - 0.5;
+            let first = position.floor().clamp(0.0, (tiles - 1) as f64) as usize;
+            let second = (first + 1).min(tiles as usize - 1);
+            let weight = (position - first as f64).clamp(0.0, 1.0);
+            (first, second, weight)
+        };
+
+        for (x, y, pixel) in plane.enumerate_pixels_mut() {
+            let (left, right, weight_x) = neighbours(x, tiles_x, width);
+            let (top, bottom, weight_y) = neighbours(y, tiles_y, height);
+            let value = pixel[0] as usize;
+            let interpolate = |row: &[[u8; 256]]| {
+                row[left][value] as f64 * (1.0 - weight_x) + row[right][value] as f64 * weight_x
+            };
+            let mapped = interpolate(&lookups[top]) * (1.0 - weight_y)
+                + interpolate(&lookups[bottom]) * weight_y;
+            pixel[0] = mapped.round() as u8;
+        }
+    }
+
+    fn tile_lookup(&self, mut histogram: [u32; 256]) -> [u8; 256] {
+        let count: u32 = histogram.iter().sum();
+        let limit = ((self.clip_limit * count as f64 / 256.0).ceil() as u32).max(1);
+
+        let mut excess = 0;
+        for bin in histogram.iter_mut() {
+            excess += bin.saturating_sub(limit);
+            *bin = u32::min(*bin, limit);
+        }
+        let (share, remainder) = (excess / 256, excess % 256);
+        for (index, bin) in histogram.iter_mut().enumerate() {
+            *bin += share;
+            // the remainder is spread over the whole range rather than piled up at the dark end
+            if (index as u32 * remainder) % 256 + remainder > 255 {
+                *bin += 1;
+            }
+        }
+
+        let partial_sums = cumulative(&histogram);
+        let total = partial_sums[255].max(1) as f64;
+        partial_sums.map(|partial_sum| (partial_sum as f64 * u8::MAX as f64 / total).round() as u8)
+    }
+}
+
+impl Transformation for Clahe {
+    fn apply(&self, image: &mut RgbImage) {
+        modify_planes(image, self.channels, |plane| self.equalize(plane));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Luma, Rgb};
+
+    #[test]
+    fn clipped_histogram_keeps_pixel_count() {
+        let mut histogram = [0u32; 256];
+        histogram[10] = 900;
+        histogram[200] = 124;
+        let clahe = Clahe::new(1, 1, 2.0);
+
+        let lookup = clahe.tile_lookup(histogram);
+
+        assert_eq!(255, lookup[255]);
+        // clipping limits how far the single dominant value is pushed
+        assert!(lookup[10] < 255 && lookup[10] > 10);
+        assert!(lookup.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn tiles_adapt_to_local_contrast() {
+        // small dark textured region, which global equalisation leaves with a small share of the range
+        let image = RgbImage::from_fn(64, 32, |x, y| {
+            let base = if x < 16 { 20 } else { 200 };
+            Rgb([base + ((x + y) % 4) as u8; 3])
+        });
+        let mut global = image.clone();
+        let mut adaptive = image.clone();
+
+        super::super::HistogramEqualization::new().apply(&mut global);
+        Clahe::new(4, 2, 40.0).apply(&mut adaptive);
+
+        let spread = |image: &RgbImage| {
+            let values: Vec<u8> = (4..12).map(|x| image.get_pixel(x, 16)[0]).collect();
+            values.iter().max().unwrap() - values.iter().min().unwrap()
+        };
+        assert!(spread(&adaptive) > 2 * spread(&global));
+    }
+
+    #[test]
+    fn single_tile_of_uniform_histogram_is_identity() {
+        let mut plane = GrayImage::from_fn(256, 4, |x, _| Luma([x as u8]));
+        Clahe::new(1, 1, 1.0).equalize(&mut plane);
+
+        for (x, _, Luma([value])) in plane.enumerate_pixels() {
+            assert!(value.abs_diff(x as u8) <= 1);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::util::{cumulative, modify_planes, HistogramChannels};
+use crate::modifications::Transformation;
+use image::{GrayImage, RgbImage};
+
+/// Global histogram equalisation, spreads the luminosities so that their
+/// cumulative distribution becomes linear
+#[derive(Default)]
+pub struct HistogramEqualization {
+    channels: HistogramChannels,
+}
+
+impl HistogramEqualization {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_channels(self, channels: HistogramChannels) -> Self {
+        Self { channels }
+    }
+}
+
+impl Transformation for HistogramEqualization {
+    fn apply(&self, image: &mut RgbImage) {
+        modify_planes(image, self.channels, equalize);
+    }
+}
+
+fn equalize(plane: &mut GrayImage) {
+    let mut histogram = [0u32; 256];
+    for pixel in plane.pixels() {
+        histogram[pixel[0] as usize] += 1;
+    }
+    let lookup = equalization_lookup(&cumulative(&histogram), plane.width() * plane.height());
+    for pixel in plane.pixels_mut() {
+        pixel[0] = lookup[pixel[0] as usize];
+    }
+}
+
+/// Lookup table spreading the cumulative histogram of `count` values evenly over the whole range,
+/// starting with the darkest occupied value
+pub(super) fn equalization_lookup(partial_sums: &[u32; 256], count: u32) -> [u8; 256] {
+    let first = partial_sums.iter().copied().find(|&x| x > 0).unwrap_or(0);
+    if count == first {
+        // a single luminosity, there is nothing to spread
+        return std::array::from_fn(|i| i as u8);
+    }
+    let mut lookup = [0u8; 256];
+    for (value, &partial_sum) in lookup.iter_mut().zip(partial_sums) {
+        let spread = partial_sum.saturating_sub(first) as f64 / (count - first) as f64;
+        *value = (spread * u8::MAX as f64).round() as u8;
+    }
+    lookup
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn equalization_stretches_to_full_range() {
+        let mut image = RgbImage::from_fn(16, 16, |x, y| Rgb([100 + (x + y) as u8; 3]));
+
+        HistogramEqualization::new().apply(&mut image);
+
+        let values: Vec<u8> = image.pixels().map(|pixel| pixel[0]).collect();
+        assert_eq!(Some(&0), values.iter().min());
+        assert_eq!(Some(&255), values.iter().max());
+    }
+
+    #[test]
+    fn luminance_equalization_keeps_gray_gray() {
+        let mut image = RgbImage::from_fn(8, 8, |x, y| Rgb([(x * 8 + y) as u8; 3]));
+
+        HistogramEqualization::new()
+            .with_channels(HistogramChannels::Luminance)
+            .apply(&mut image);
+
+        for Rgb([r, g, b]) in image.pixels() {
+            assert!(r.abs_diff(*g) <= 1 && g.abs_diff(*b) <= 1);
+        }
+    }
+
+    #[test]
+    fn constant_image_is_unchanged() {
+        let mut image = RgbImage::from_pixel(4, 4, Rgb([90, 90, 90]));
+
+        HistogramEqualization::new().apply(&mut image);
+
+        assert_eq!(RgbImage::from_pixel(4, 4, Rgb([90, 90, 90])), image);
+    }
+}
@@ -0,0 +1,43 @@
+use super::util::{apply_final_pdf, HistogramChannels};
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+///(H2) Exponential final probability density function (--hexponent).
+pub struct ExponentialModification {
+    g_min: u8,
+    g_max: u8,
+    channels: HistogramChannels,
+}
+
+impl ExponentialModification {
+    pub fn new(g_min: u8, g_max: u8) -> Self {
+        assert!(g_max > g_min);
+        Self {
+            g_min,
+            g_max,
+            channels: HistogramChannels::default(),
+        }
+    }
+
+    pub fn with_channels(self, channels: HistogramChannels) -> Self {
+        Self { channels, ..self }
+    }
+}
+
+impl Transformation for ExponentialModification {
+    fn apply(&self, image: &mut RgbImage) {
+        let image_size = image.width() * image.height();
+        // chosen so that the brightest pixels get g_max, like alpha of the Rayleigh modification
+        let inverse_alpha = (self.g_max - self.g_min) as f64 / f64::ln(image_size as f64);
+
+        apply_final_pdf(image, self.channels, |partial_sum, image_size| {
+            let log_base = image_size as f64 / (image_size - partial_sum + 1) as f64;
+            self.g_min
+                + f64::clamp(
+                    inverse_alpha * f64::ln(log_base),
+                    0.0,
+                    (self.g_max - self.g_min) as f64,
+                ) as u8
+        });
+    }
+}
@@ -0,0 +1,42 @@
+use super::util::{apply_final_pdf, HistogramChannels};
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+///(H5) Hyperbolic final probability density function (--hhyper).
+pub struct HyperbolicModification {
+    g_min: u8,
+    g_max: u8,
+    channels: HistogramChannels,
+}
+
+impl HyperbolicModification {
+    pub fn new(g_min: u8, g_max: u8) -> Self {
+        assert!(
+            g_min > 0,
+            "g_min of the hyperbolic modification must be positive"
+        );
+        assert!(g_max > g_min);
+        Self {
+            g_min,
+            g_max,
+            channels: HistogramChannels::default(),
+        }
+    }
+
+    pub fn with_channels(self, channels: HistogramChannels) -> Self {
+        Self { channels, ..self }
+    }
+}
+
+impl Transformation for HyperbolicModification {
+    fn apply(&self, image: &mut RgbImage) {
+        let ratio = self.g_max as f64 / self.g_min as f64;
+
+        apply_final_pdf(image, self.channels, |partial_sum, image_size| {
+            let fraction = partial_sum as f64 / image_size as f64;
+            (self.g_min as f64 * ratio.powf(fraction))
+                .round()
+                .clamp(self.g_min as f64, self.g_max as f64) as u8
+        });
+    }
+}
@@ -0,0 +1,111 @@
+use super::util::{apply_lookup, cumulative_histograms};
+use crate::histogram::Histogram;
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+/// Histogram specification, maps luminosities of every channel so that
+/// its cumulative distribution follows the one of a target histogram
+pub struct HistogramMatching {
+    /// Cumulative distribution of every channel of the target, ending with 1
+    target: [[f64; 256]; 3],
+}
+
+impl HistogramMatching {
+    /// Matches every channel to the same channel of the reference image
+    pub fn from_image(reference: &RgbImage) -> Self {
+        let histogram = Histogram::new(reference);
+        Self::from_histograms([0, 1, 2].map(|channel| histogram[channel].map(|x| x as f64)))
+    }
+
+    /// Matches all channels to the same histogram, which does not need to be normalized
+    pub fn from_histogram(histogram: &[f64; 256]) -> Self {
+        Self::from_histograms([*histogram; 3])
+    }
+
+    pub fn from_histograms(histograms: [[f64; 256]; 3]) -> Self {
+        let target = histograms.map(|histogram| {
+            assert!(
+                histogram.iter().all(|&x| x >= 0.0),
+                "Histogram cannot contain negative values"
+            );
+            let total: f64 = histogram.iter().sum();
+            assert!(total > 0.0, "Histogram cannot be empty");
+
+            let mut sum = 0.0;
+            histogram.map(|x| {
+                sum += x;
+                sum / total
+            })
+        });
+        Self { target }
+    }
+}
+
+impl Transformation for HistogramMatching {
+    fn apply(&self, image: &mut RgbImage) {
+        let partial_sums = cumulative_histograms(image);
+        let image_size = (image.width() * image.height()) as f64;
+
+        let mut brightness_lookup = [[0u8; 256]; 3];
+        for channel in 0..3 {
+            let target = &self.target[channel];
+            for (value, &partial_sum) in brightness_lookup[channel]
+                .iter_mut()
+                .zip(&partial_sums[channel])
+            {
+                // the darkest target luminosity that has at least the same share of pixels below it,
+                // with a small tolerance for rounding of the target distribution
+                let fraction = partial_sum as f64 / image_size - 1e-12;
+                *value = target.partition_point(|&x| x < fraction).min(255) as u8;
+            }
+        }
+
+        apply_lookup(image, &brightness_lookup);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    fn gradient() -> RgbImage {
+        RgbImage::from_fn(32, 8, |x, y| Rgb([(x * 4) as u8, (y * 20) as u8, 100]))
+    }
+
+    #[test]
+    fn matching_to_itself_is_identity() {
+        let image = gradient();
+        let mut matched = image.clone();
+
+        HistogramMatching::from_image(&image).apply(&mut matched);
+
+        assert_eq!(image, matched);
+    }
+
+    #[test]
+    fn matching_reproduces_reference_histogram() {
+        let reference = RgbImage::from_fn(32, 8, |x, y| {
+            Rgb([255 - (x * 2) as u8, 50 + (y * 5) as u8, (x * y) as u8])
+        });
+        let mut image = gradient();
+
+        HistogramMatching::from_image(&reference).apply(&mut image);
+
+        let (expected, actual) = (Histogram::new(&reference), Histogram::new(&image));
+        for channel in 0..2 {
+            assert_eq!(expected[channel], actual[channel]);
+        }
+    }
+
+    #[test]
+    fn matching_to_flat_histogram_spreads_values() {
+        let mut image = RgbImage::from_fn(16, 16, |x, y| Rgb([(x * 16 + y) as u8 / 4; 3]));
+
+        HistogramMatching::from_histogram(&[1.0; 256]).apply(&mut image);
+
+        let values: Vec<u8> = image.pixels().map(|pixel| pixel[0]).collect();
+        assert!(*values.iter().min().unwrap() <= 3);
+        assert_eq!(Some(&255), values.iter().max());
+    }
+}
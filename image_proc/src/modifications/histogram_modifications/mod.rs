@@ -1,3 +1,17 @@
+mod clahe;
+mod equalization;
+mod exponential;
+mod hyperbolic;
+mod matching;
 mod rayleigh;
+mod uniform;
+mod util;
 
+pub use clahe::*;
+pub use equalization::*;
+pub use exponential::*;
+pub use hyperbolic::*;
+pub use matching::*;
 pub use rayleigh::*;
+pub use uniform::*;
+pub use util::HistogramChannels;
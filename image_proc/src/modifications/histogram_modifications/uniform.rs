@@ -0,0 +1,60 @@
+use super::util::{apply_final_pdf, HistogramChannels};
+use crate::modifications::Transformation;
+use image::RgbImage;
+
+///(H1) Uniform final probability density function (--huniform).
+pub struct UniformModification {
+    g_min: u8,
+    g_max: u8,
+    channels: HistogramChannels,
+}
+
+impl UniformModification {
+    pub fn new(g_min: u8, g_max: u8) -> Self {
+        assert!(g_max > g_min);
+        Self {
+            g_min,
+            g_max,
+            channels: HistogramChannels::default(),
+        }
+    }
+
+    pub fn with_channels(self, channels: HistogramChannels) -> Self {
+        Self { channels, ..self }
+    }
+}
+
+impl Transformation for UniformModification {
+    fn apply(&self, image: &mut RgbImage) {
+        apply_final_pdf(image, self.channels, |partial_sum, image_size| {
+            let fraction = partial_sum as f64 / image_size as f64;
+            self.g_min + ((self.g_max - self.g_min) as f64 * fraction).round() as u8
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn luminance_modification_keeps_colours() {
+        let image = RgbImage::from_fn(16, 16, |x, y| {
+            let v = (x + 16 * y) as u8 / 4;
+            Rgb([60 + v, 40 + v / 2, 20])
+        });
+
+        let mut per_channel = image.clone();
+        UniformModification::new(50, 200).apply(&mut per_channel);
+        let mut luminance = image;
+        UniformModification::new(50, 200)
+            .with_channels(HistogramChannels::Luminance)
+            .apply(&mut luminance);
+
+        assert!(per_channel.pixels().any(|Rgb([r, _, b])| b > r));
+        for Rgb([r, g, b]) in luminance.pixels() {
+            assert!(r > g && g > b);
+        }
+    }
+}
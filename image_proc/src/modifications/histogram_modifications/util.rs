@@ -0,0 +1,98 @@
+use crate::color_space::{rgb_to_ycbcr, ycbcr_to_rgb};
+use crate::histogram::Histogram;
+use image::{GrayImage, Luma, RgbImage};
+
+/// Which values of the image a histogram modification works with
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum HistogramChannels {
+    /// Each of the RGB channels is modified separately, which can shift colours
+    #[default]
+    PerChannel,
+    /// Only the luma (Y) channel of YCbCr is modified, preserving chroma
+    Luminance,
+}
+
+/// Number of pixels with luminosity lower or equal to the index, per channel
+pub(super) fn cumulative_histograms(image: &RgbImage) -> [[u32; 256]; 3] {
+    let histogram = Histogram::new(image);
+    [0, 1, 2].map(|channel| cumulative(&histogram[channel]))
+}
+
+pub(super) fn cumulative(histogram: &[u32; 256]) -> [u32; 256] {
+    let mut partial_sums = [0u32; 256];
+    let mut sum = 0;
+    for (partial_sum, value) in partial_sums.iter_mut().zip(histogram) {
+        sum += value;
+        *partial_sum = sum;
+    }
+    partial_sums
+}
+
+pub(super) fn apply_lookup(image: &mut RgbImage, brightness_lookup: &[[u8; 256]; 3]) {
+    for pixel in image.pixels_mut() {
+        for channel in 0..3 {
+            pixel[channel] = brightness_lookup[channel][pixel[channel] as usize];
+        }
+    }
+}
+
+/// Applies `modify` to each RGB channel, or to the luma channel only, as a separate grayscale image
+pub(super) fn modify_planes<F>(image: &mut RgbImage, channels: HistogramChannels, modify: F)
+where
+    F: Fn(&mut GrayImage),
+{
+    let (width, height) = image.dimensions();
+    match channels {
+        HistogramChannels::PerChannel => {
+            for channel in 0..3 {
+                let mut plane = GrayImage::from_fn(width, height, |x, y| {
+                    Luma([image.get_pixel(x, y)[channel]])
+                });
+                modify(&mut plane);
+                for (pixel, Luma([value])) in image.pixels_mut().zip(plane.pixels()) {
+                    pixel[channel] = *value;
+                }
+            }
+        }
+        HistogramChannels::Luminance => {
+            let ycbcr: Vec<[f64; 3]> = image.pixels().map(rgb_to_ycbcr).collect();
+            let mut luma = GrayImage::from_fn(width, height, |x, y| {
+                let [y, _, _] = ycbcr[(y * width + x) as usize];
+                Luma([y.round().clamp(0.0, 255.0) as u8])
+            });
+            modify(&mut luma);
+            for ((pixel, Luma([y])), [_, cb, cr]) in
+                image.pixels_mut().zip(luma.pixels()).zip(ycbcr)
+            {
+                *pixel = ycbcr_to_rgb([*y as f64, cb, cr]);
+            }
+        }
+    }
+}
+
+/// Modifies the image with a final probability density function, mapping every occupied luminosity
+/// through `brightness`, which gets the number of pixels not brighter than it and the number of all pixels
+pub(super) fn apply_final_pdf<F>(image: &mut RgbImage, channels: HistogramChannels, brightness: F)
+where
+    F: Fn(u32, u32) -> u8,
+{
+    modify_planes(image, channels, |plane| {
+        let mut histogram = [0u32; 256];
+        for pixel in plane.pixels() {
+            histogram[pixel[0] as usize] += 1;
+        }
+        let image_size = plane.width() * plane.height();
+
+        let mut brightness_lookup = [0u8; 256];
+        for (value, partial_sum) in brightness_lookup.iter_mut().zip(cumulative(&histogram)) {
+            if partial_sum == 0 {
+                // no pixels of such luminosity, so no reason to calculate
+                continue;
+            }
+            *value = brightness(partial_sum, image_size);
+        }
+        for pixel in plane.pixels_mut() {
+            pixel[0] = brightness_lookup[pixel[0] as usize];
+        }
+    });
+}
@@ -1,5 +1,6 @@
 use image::{Pixel, RgbImage};
 use std::ops::Deref;
+pub mod channel;
 pub mod elementary;
 pub mod filters;
 pub mod frequency_domain;
@@ -7,6 +8,7 @@ pub mod geometric;
 pub mod histogram_modifications;
 pub mod morphological;
 pub mod segmentation;
+pub mod thresholding;
 
 pub mod prelude {
     pub use super::elementary::*;
@@ -21,6 +23,12 @@ pub trait Transformation {
     fn apply(&self, image: &mut RgbImage);
 }
 
+impl<T: Transformation + ?Sized> Transformation for Box<T> {
+    fn apply(&self, image: &mut RgbImage) {
+        (**self).apply(image)
+    }
+}
+
 /// Does nothing with the image
 pub struct IdTransform;
 
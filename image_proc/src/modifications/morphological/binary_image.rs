@@ -0,0 +1,461 @@
+use super::{is_foreground, StructuringElement, BACKGROUND_PIXEL, FOREGROUND_PIXEL};
+use image::GrayImage;
+use std::collections::BTreeMap;
+
+const WORD_BITS: u32 = u64::BITS;
+
+/// Binary image packed into rows of 64-bit words, pixel `x` of a row being bit `x % 64`
+/// of word `x / 64`, so morphology shifts and combines 64 pixels at once.
+/// Bits past the width of the image are always zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinaryImage {
+    width: u32,
+    height: u32,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BinaryImage {
+    /// Image of the background only
+    pub fn new(width: u32, height: u32) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS) as usize;
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height as usize],
+        }
+    }
+
+    /// Foreground of the image as decided by the morphological threshold
+    pub fn from_gray(image: &GrayImage) -> Self {
+        let mut binary = Self::new(image.width(), image.height());
+        for (x, y, pixel) in image.enumerate_pixels() {
+            if is_foreground(pixel) {
+                binary.set(x, y, true);
+            }
+        }
+        binary
+    }
+
+    pub fn to_gray(&self) -> GrayImage {
+        GrayImage::from_fn(self.width, self.height, |x, y| match self.get(x, y) {
+            true => FOREGROUND_PIXEL,
+            false => BACKGROUND_PIXEL,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        let word = self.words[self.word_index(x, y)];
+        word >> (x % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, foreground: bool) {
+        let index = self.word_index(x, y);
+        let bit = 1 << (x % WORD_BITS);
+        match foreground {
+            true => self.words[index] |= bit,
+            false => self.words[index] &= !bit,
+        }
+    }
+
+    /// Whether the pixel lies inside the image and is foreground
+    pub fn get_checked(&self, x: i64, y: i64) -> bool {
+        (0..self.width as i64).contains(&x)
+            && (0..self.height as i64).contains(&y)
+            && self.get(x as u32, y as u32)
+    }
+
+    /// Positions of the foreground pixels, row by row
+    pub fn foreground(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, &word)| {
+                let y = (index / self.words_per_row) as u32;
+                let first_x = (index % self.words_per_row) as u32 * WORD_BITS;
+                let mut word = word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros();
+                    word &= word - 1;
+                    Some((first_x + bit, y))
+                })
+            })
+    }
+
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Swaps the foreground and the background
+    pub fn invert(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = !*word;
+        }
+        self.clear_padding();
+    }
+
+    pub fn and(&mut self, other: &BinaryImage) {
+        self.combine(other, |a, b| a & b);
+    }
+
+    pub fn or(&mut self, other: &BinaryImage) {
+        self.combine(other, |a, b| a | b);
+    }
+
+    /// Dilation, the union of the element placed at every foreground pixel
+    pub fn dilate(&self, element: &StructuringElement) -> BinaryImage {
+        let offsets: Vec<(i32, i32)> = element.offsets().collect();
+        self.with_margin(&offsets, false, |image| {
+            let or = |a: u64, b: u64| a | b;
+            match Decomposition::of(&offsets) {
+                Decomposition::Rectangle {
+                    left,
+                    top,
+                    width,
+                    height,
+                } => image
+                    .reduce_line(width, (-1, 0), false, or)
+                    .reduce_line(height, (0, -1), false, or)
+                    .shifted(-left, -top, false),
+                Decomposition::Runs(runs) => {
+                    let mut result = BinaryImage::new(image.width, image.height);
+                    for (length, runs) in runs {
+                        let spread = image.reduce_line(length, (-1, 0), false, or);
+                        for (left, y) in runs {
+                            result.or(&spread.shifted(-left, -y, false));
+                        }
+                    }
+                    result
+                }
+            }
+        })
+    }
+
+    /// Erosion, the foreground pixels at which the element fits into the foreground,
+    /// with pixels outside the image counted as background.
+    /// A pixel is kept only if it is foreground itself, even if the element misses its origin.
+    pub fn erode(&self, element: &StructuringElement) -> BinaryImage {
+        let mut offsets: Vec<(i32, i32)> = element.offsets().collect();
+        if !offsets.contains(&(0, 0)) {
+            offsets.push((0, 0));
+        }
+        self.erode_offsets(&offsets, false)
+    }
+
+    /// Erosion by the given offsets, with pixels outside the image counted as foreground
+    /// if `outside` is set. No offsets fit everywhere.
+    pub(crate) fn erode_offsets(&self, offsets: &[(i32, i32)], outside: bool) -> BinaryImage {
+        self.with_margin(offsets, outside, |image| {
+            let and = |a: u64, b: u64| a & b;
+            match Decomposition::of(offsets) {
+                Decomposition::Rectangle {
+                    left,
+                    top,
+                    width,
+                    height,
+                } => image
+                    .reduce_line(width, (1, 0), outside, and)
+                    .reduce_line(height, (0, 1), outside, and)
+                    .shifted(left, top, outside),
+                Decomposition::Runs(runs) => {
+                    let mut result = BinaryImage::new(image.width, image.height);
+                    result.invert();
+                    for (length, runs) in runs {
+                        let fitting = image.reduce_line(length, (1, 0), outside, and);
+                        for (left, y) in runs {
+                            result.and(&fitting.shifted(left, y, outside));
+                        }
+                    }
+                    result
+                }
+            }
+        })
+    }
+
+    /// Applies the operation to the image extended on every side by the reach of the offsets,
+    /// so that the partial results of a decomposed element are not cut off at the border
+    fn with_margin(
+        &self,
+        offsets: &[(i32, i32)],
+        outside: bool,
+        operation: impl FnOnce(&BinaryImage) -> BinaryImage,
+    ) -> BinaryImage {
+        let margin_x = offsets
+            .iter()
+            .map(|&(x, _)| x.unsigned_abs())
+            .max()
+            .unwrap_or(0);
+        let margin_y = offsets
+            .iter()
+            .map(|&(_, y)| y.unsigned_abs())
+            .max()
+            .unwrap_or(0);
+        let extended = self.window(
+            -(margin_x as i64),
+            -(margin_y as i64),
+            self.width + 2 * margin_x,
+            self.height + 2 * margin_y,
+            outside,
+        );
+        operation(&extended).window(
+            margin_x as i64,
+            margin_y as i64,
+            self.width,
+            self.height,
+            false,
+        )
+    }
+
+    /// Image combining every pixel with the `length - 1` pixels following it in the direction,
+    /// in a logarithmic number of shifts by combining already combined runs
+    fn reduce_line(
+        &self,
+        length: u32,
+        (step_x, step_y): (i32, i32),
+        outside: bool,
+        op: impl Fn(u64, u64) -> u64 + Copy,
+    ) -> BinaryImage {
+        let mut result = self.clone();
+        let mut covered = 1;
+        while covered < length {
+            let step = covered.min(length - covered) as i32;
+            let shifted = result.shifted(step_x * step, step_y * step, outside);
+            result.combine(&shifted, op);
+            covered += step as u32;
+        }
+        result
+    }
+
+    /// Image with pixel `x, y` taken from `x + dx, y + dy`, pixels from outside the image
+    /// being foreground if `outside` is set
+    fn shifted(&self, dx: i32, dy: i32, outside: bool) -> BinaryImage {
+        self.window(dx as i64, dy as i64, self.width, self.height, outside)
+    }
+
+    /// Part of the image of the given size with its top-left corner at `x, y`,
+    /// pixels from outside the image being foreground if `outside` is set
+    fn window(&self, x: i64, y: i64, width: u32, height: u32, outside: bool) -> BinaryImage {
+        let fill = match outside {
+            true => u64::MAX,
+            false => 0,
+        };
+        let mut result = BinaryImage::new(width, height);
+        let words_per_row = result.words_per_row;
+        for (row, target) in result.words.chunks_mut(words_per_row.max(1)).enumerate() {
+            let source_y = y + row as i64;
+            if !(0..self.height as i64).contains(&source_y) {
+                target.fill(fill);
+                continue;
+            }
+            let start = source_y as usize * self.words_per_row;
+            let source = &self.words[start..start + self.words_per_row];
+            shift_row(source, target, x, self.width, fill);
+        }
+        result.clear_padding();
+        result
+    }
+
+    fn combine(&mut self, other: &BinaryImage, op: impl Fn(u64, u64) -> u64) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "Images must have the same dimensions"
+        );
+        for (word, &other) in self.words.iter_mut().zip(&other.words) {
+            *word = op(*word, other);
+        }
+    }
+
+    fn word_index(&self, x: u32, y: u32) -> usize {
+        debug_assert!(x < self.width && y < self.height);
+        y as usize * self.words_per_row + (x / WORD_BITS) as usize
+    }
+
+    fn clear_padding(&mut self) {
+        let used = self.width % WORD_BITS;
+        if used == 0 {
+            return;
+        }
+        let mask = (1u64 << used) - 1;
+        for row in self.words.chunks_mut(self.words_per_row) {
+            *row.last_mut().unwrap() &= mask;
+        }
+    }
+}
+
+/// Sets bit `x` of the target row to bit `x + shift` of the source row,
+/// with bits from outside the row of `width` pixels set to `fill`
+fn shift_row(source: &[u64], target: &mut [u64], shift: i64, width: u32, fill: u64) {
+    let word_of = |index: i64| -> u64 {
+        let first_bit = index * WORD_BITS as i64;
+        if index < 0 || first_bit >= width as i64 {
+            return fill;
+        }
+        let word = source[index as usize];
+        let used = width as i64 - first_bit;
+        match used < WORD_BITS as i64 {
+            // the padding past the width reads as outside the image
+            true => word | (fill << used),
+            false => word,
+        }
+    };
+    let (words, bits) = (shift.div_euclid(64), shift.rem_euclid(64) as u32);
+    for (index, target) in target.iter_mut().enumerate() {
+        let low = word_of(index as i64 + words);
+        *target = match bits {
+            0 => low,
+            bits => (low >> bits) | (word_of(index as i64 + words + 1) << (WORD_BITS - bits)),
+        };
+    }
+}
+
+/// Structuring element split into parts that are cheap to apply to packed rows
+enum Decomposition {
+    /// A filled rectangle, applied as a horizontal and then a vertical line
+    Rectangle {
+        left: i32,
+        top: i32,
+        width: u32,
+        height: u32,
+    },
+    /// Horizontal runs of the element grouped by their length, as the starting offset
+    /// of every run, so each length is reduced only once
+    Runs(BTreeMap<u32, Vec<(i32, i32)>>),
+}
+
+impl Decomposition {
+    fn of(offsets: &[(i32, i32)]) -> Self {
+        if offsets.is_empty() {
+            return Decomposition::Runs(BTreeMap::new());
+        }
+        let left = offsets.iter().map(|&(x, _)| x).min().unwrap();
+        let right = offsets.iter().map(|&(x, _)| x).max().unwrap();
+        let top = offsets.iter().map(|&(_, y)| y).min().unwrap();
+        let bottom = offsets.iter().map(|&(_, y)| y).max().unwrap();
+        let (width, height) = ((right - left + 1) as u32, (bottom - top + 1) as u32);
+
+        let mut sorted: Vec<(i32, i32)> = offsets.iter().map(|&(x, y)| (y, x)).collect();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() == (width * height) as usize {
+            return Decomposition::Rectangle {
+                left,
+                top,
+                width,
+                height,
+            };
+        }
+
+        let mut runs: BTreeMap<u32, Vec<(i32, i32)>> = BTreeMap::new();
+        let mut start = 0;
+        for index in 1..=sorted.len() {
+            let continues = index < sorted.len()
+                && sorted[index].0 == sorted[index - 1].0
+                && sorted[index].1 == sorted[index - 1].1 + 1;
+            if !continues {
+                let (y, x) = sorted[start];
+                runs.entry((index - start) as u32).or_default().push((x, y));
+                start = index;
+            }
+        }
+        Decomposition::Runs(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(width: u32, height: u32) -> BinaryImage {
+        let mut image = BinaryImage::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                image.set(x, y, (x / 3 + y / 2 + x * y % 7) % 3 != 0);
+            }
+        }
+        image
+    }
+
+    /// Dilation and erosion straight from their definitions
+    fn reference(image: &BinaryImage, element: &StructuringElement) -> (BinaryImage, BinaryImage) {
+        let offsets: Vec<(i32, i32)> = element.offsets().collect();
+        let (mut dilated, mut eroded) = (image.clone(), image.clone());
+        for y in 0..image.height() {
+            for x in 0..image.width() {
+                let at = |(dx, dy): (i32, i32), sign: i64| {
+                    image.get_checked(x as i64 + sign * dx as i64, y as i64 + sign * dy as i64)
+                };
+                dilated.set(x, y, offsets.iter().any(|&offset| at(offset, -1)));
+                let fits = offsets.iter().all(|&offset| at(offset, 1));
+                eroded.set(x, y, image.get(x, y) && fits);
+            }
+        }
+        (dilated, eroded)
+    }
+
+    #[test]
+    fn packed_operations_match_definition() {
+        let elements = [
+            StructuringElement::rectangle(3, 3),
+            StructuringElement::rectangle(7, 2).with_origin(0, 1),
+            StructuringElement::disk(4),
+            StructuringElement::diamond(3).with_origin(1, 5),
+            StructuringElement::line(9, 30.0),
+            StructuringElement::cross(5, 3),
+            StructuringElement::rectangle(70, 1),
+            StructuringElement::new(
+                3,
+                3,
+                vec![true, true, true, false, false, false, false, false, false],
+            ),
+        ];
+        for (width, height) in [(150, 40), (64, 9), (5, 5)] {
+            let image = pattern(width, height);
+            for element in &elements {
+                let (dilated, eroded) = reference(&image, element);
+
+                assert_eq!(dilated, image.dilate(element), "{:?}", element);
+                assert_eq!(eroded, image.erode(element), "{:?}", element);
+            }
+        }
+    }
+
+    #[test]
+    fn shifts_cross_word_boundaries() {
+        let mut image = BinaryImage::new(130, 2);
+        image.set(63, 0, true);
+        image.set(129, 1, true);
+
+        let shifted = image.shifted(-1, 0, false);
+        assert_eq!(vec![(64, 0)], shifted.foreground().collect::<Vec<_>>());
+        let shifted = image.shifted(65, 1, false);
+        assert_eq!(vec![(64, 0)], shifted.foreground().collect::<Vec<_>>());
+        // the two shifted pixels and the last column from outside
+        assert_eq!(4, image.shifted(1, 0, true).count());
+    }
+
+    #[test]
+    fn gray_round_trip() {
+        let image = pattern(70, 3);
+
+        assert_eq!(image, BinaryImage::from_gray(&image.to_gray()));
+        let mut inverted = image.clone();
+        inverted.invert();
+        assert_eq!(70 * 3, image.count() + inverted.count());
+    }
+}
@@ -1,15 +1,19 @@
 use crate::modifications::morphological::dilation::Dilation;
 use crate::modifications::morphological::erosion::Erosion;
-use crate::modifications::morphological::{Mask, MorphologicalTransform};
+use crate::modifications::morphological::{Mask, MorphologicalTransform, StructuringElement};
 use image::GrayImage;
 
 pub struct Closing {
-    mask: Mask,
+    element: StructuringElement,
 }
 
 impl Closing {
     pub fn new(mask: Mask) -> Self {
-        Self { mask }
+        Self::from_element(StructuringElement::from(&mask))
+    }
+
+    pub fn from_element(element: StructuringElement) -> Self {
+        Self { element }
     }
 }
 
@@ -17,7 +21,7 @@ impl_transform!(Closing);
 
 impl MorphologicalTransform for Closing {
     fn apply_morph_operation(&self, image: &mut GrayImage) {
-        Dilation::apply(&self.mask, image);
-        Erosion::apply(&self.mask, image);
+        Dilation::apply(&self.element, image);
+        Erosion::apply(&self.element, image);
     }
 }
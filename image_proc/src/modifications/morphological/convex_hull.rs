@@ -1,4 +1,4 @@
-use super::{hmt::HitOrMissTransform, Mask, MorphologicalTransform, FOREGROUND_PIXEL};
+use super::{hmt::HitOrMissTransform, BinaryImage, Mask, MorphologicalTransform};
 use image::GrayImage;
 
 pub struct ConvexHull;
@@ -24,32 +24,44 @@ static STRUCTURAL_ELEMENTS: [(Mask, Mask); 4] = [
 
 impl_transform!(ConvexHull);
 
-fn saturate_with_transform(image: &mut GrayImage, transform: &HitOrMissTransform) {
-    loop {
-        let transform_result: Vec<_> = transform.get_matching_pixels(image).collect();
-        if transform_result.len() == 0 {
-            return;
+/// Adds the pixels matched by the transform until none is left. Only the neighbourhood
+/// of an added pixel can start to match, so just those pixels are checked again.
+///
+/// The miss mask must be the centre pixel alone, as in all of [`STRUCTURAL_ELEMENTS`]. Then adding
+/// a pixel cannot stop any other from matching, and the order of additions does not matter.
+fn saturate_with_transform(image: &mut BinaryImage, transform: &HitOrMissTransform) {
+    let mut worklist: Vec<(u32, u32)> = transform.matching_pixels(image).foreground().collect();
+    while let Some((x, y)) = worklist.pop() {
+        if image.get(x, y) || !transform.matches(image, x, y) {
+            continue;
         }
-        for (x, y) in transform_result {
-            image.put_pixel(x, y, FOREGROUND_PIXEL);
+        image.set(x, y, true);
+        // pixels having the added one in their hit mask
+        for &(dx, dy) in transform.hit_offsets() {
+            let (x, y) = (x as i64 - dx as i64, y as i64 - dy as i64);
+            if (0..image.width() as i64).contains(&x) && (0..image.height() as i64).contains(&y) {
+                worklist.push((x as u32, y as u32));
+            }
         }
     }
 }
 
 impl MorphologicalTransform for ConvexHull {
     fn apply_morph_operation(&self, image: &mut GrayImage) {
+        let mut binary = BinaryImage::from_gray(image);
         for (hit, miss) in &STRUCTURAL_ELEMENTS {
             let transform = HitOrMissTransform::new(hit.clone(), miss.clone());
-            saturate_with_transform(image, &transform);
+            saturate_with_transform(&mut binary, &transform);
         }
+        *image = binary.to_gray();
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::saturate_with_transform;
+    use super::{saturate_with_transform, STRUCTURAL_ELEMENTS};
     use crate::modifications::morphological::hmt::HitOrMissTransform;
-    use crate::modifications::morphological::{is_foreground, Mask, FOREGROUND_PIXEL};
+    use crate::modifications::morphological::{is_foreground, BinaryImage, Mask, FOREGROUND_PIXEL};
     use image::{GrayImage, ImageBuffer};
 
     #[test]
@@ -66,9 +78,68 @@ mod tests {
             Mask::from_raw_data(0b000010000),
         );
 
-        saturate_with_transform(&mut image, &transformation);
+        let mut binary = BinaryImage::from_gray(&image);
+        saturate_with_transform(&mut binary, &transformation);
+        let image = binary.to_gray();
 
         assert!(is_foreground(image.get_pixel(1, 1)));
         assert!(is_foreground(image.get_pixel(1, 2)));
     }
+
+    fn scattered(width: u32, height: u32) -> BinaryImage {
+        let mut image = BinaryImage::new(width, height);
+        for (x, y) in [
+            (3, 4),
+            (20, 2),
+            (35, 10),
+            (28, 26),
+            (9, 21),
+            (17, 13),
+            (0, 15),
+        ] {
+            image.set(x, y, true);
+        }
+        image
+    }
+
+    #[test]
+    fn worklist_saturates_like_rescanning() {
+        let mut image = scattered(40, 30);
+        let mut rescanned = image.clone();
+        for (hit, miss) in &STRUCTURAL_ELEMENTS {
+            let transform = HitOrMissTransform::new(hit.clone(), miss.clone());
+            loop {
+                let matched: Vec<(u32, u32)> =
+                    transform.matching_pixels(&rescanned).foreground().collect();
+                if matched.is_empty() {
+                    break;
+                }
+                for (x, y) in matched {
+                    rescanned.set(x, y, true);
+                }
+            }
+
+            saturate_with_transform(&mut image, &transform);
+
+            assert_eq!(rescanned, image);
+        }
+    }
+
+    #[test]
+    fn packed_matching_agrees_with_single_pixels() {
+        let image = scattered(40, 30);
+        let transform = HitOrMissTransform::new(
+            Mask::from_raw_data(0b000010000),
+            Mask::from_raw_data(0b101000101),
+        );
+
+        let matched = transform.matching_pixels(&image);
+
+        for y in 0..30 {
+            for x in 0..40 {
+                assert_eq!(transform.matches(&image, x, y), matched.get(x, y));
+            }
+        }
+        assert_eq!(7, matched.count());
+    }
 }
@@ -1,28 +1,22 @@
 use super::mask::Mask;
-use super::MorphologicalTransform;
-use crate::modifications::morphological::is_foreground;
-use image::{GrayImage, ImageBuffer};
+use super::{BinaryImage, MorphologicalTransform, StructuringElement};
+use image::GrayImage;
 
 pub struct Dilation {
-    mask: Mask,
+    element: StructuringElement,
 }
 
 impl Dilation {
     pub fn new(mask: Mask) -> Self {
-        Self { mask }
+        Self::from_element(StructuringElement::from(&mask))
     }
 
-    pub(crate) fn apply(mask: &Mask, image: &mut GrayImage) {
-        let mut new_image: GrayImage = ImageBuffer::new(image.width(), image.height());
-        for (x, y, pixel) in image.enumerate_pixels() {
-            if !is_foreground(pixel) {
-                continue;
-            }
-            let new_image_mask = &Mask::from_image(&new_image, x, y);
-            let mask = mask | new_image_mask;
-            mask.write_to_image(&mut new_image, x, y);
-        }
-        *image = new_image;
+    pub fn from_element(element: StructuringElement) -> Self {
+        Self { element }
+    }
+
+    pub(crate) fn apply(element: &StructuringElement, image: &mut GrayImage) {
+        *image = BinaryImage::from_gray(image).dilate(element).to_gray();
     }
 }
 
@@ -30,6 +24,6 @@ impl_transform!(Dilation);
 
 impl MorphologicalTransform for Dilation {
     fn apply_morph_operation(&self, image: &mut GrayImage) {
-        Self::apply(&self.mask, image);
+        Self::apply(&self.element, image);
     }
 }
@@ -1,27 +1,22 @@
-use super::{is_foreground, Mask, MorphologicalTransform, FOREGROUND_PIXEL};
-use image::{GrayImage, ImageBuffer};
+use super::{BinaryImage, Mask, MorphologicalTransform, StructuringElement};
+use image::GrayImage;
 
 pub struct Erosion {
-    mask: Mask,
+    element: StructuringElement,
 }
 
 impl Erosion {
     pub fn new(mask: Mask) -> Self {
-        Self { mask }
+        Self::from_element(StructuringElement::from(&mask))
     }
 
-    pub(crate) fn apply(mask: &Mask, image: &mut GrayImage) {
-        let mut new_image: GrayImage = ImageBuffer::new(image.width(), image.height());
-        for (x, y, pixel) in image.enumerate_pixels() {
-            if !is_foreground(pixel) {
-                continue;
-            }
-            let new_mask = mask & &Mask::from_image(&image, x, y);
-            if &new_mask == mask {
-                new_image.put_pixel(x, y, FOREGROUND_PIXEL);
-            }
-        }
-        *image = new_image;
+    pub fn from_element(element: StructuringElement) -> Self {
+        Self { element }
+    }
+
+    /// Pixels outside the image count as the background
+    pub(crate) fn apply(element: &StructuringElement, image: &mut GrayImage) {
+        *image = BinaryImage::from_gray(image).erode(element).to_gray();
     }
 }
 
@@ -29,6 +24,6 @@ impl_transform!(Erosion);
 
 impl MorphologicalTransform for Erosion {
     fn apply_morph_operation(&self, image: &mut GrayImage) {
-        Self::apply(&self.mask, image);
+        Self::apply(&self.element, image);
     }
 }
@@ -0,0 +1,319 @@
+use super::{Mask, StructuringElement};
+use crate::modifications::Transformation;
+use image::imageops::grayscale;
+use image::{DynamicImage, GrayImage, Luma, RgbImage};
+
+/// Structuring element of grayscale morphology, offsets from the origin with heights added to
+/// the pixels by dilation and subtracted by erosion, all zero for a flat element.
+/// An element is never empty.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrayStructuringElement {
+    points: Vec<(i32, i32, i16)>,
+}
+
+impl GrayStructuringElement {
+    /// Flat rectangle with the origin in its middle
+    pub fn rectangle(width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "Element size must not be zero");
+        Self::non_flat(
+            width,
+            height,
+            &vec![Some(0); width as usize * height as usize],
+        )
+    }
+
+    /// Flat element of the foreground of a non-empty 3x3 mask, with the origin in its middle
+    pub fn from_mask(mask: &Mask) -> Self {
+        Self::flat(&StructuringElement::from(mask))
+    }
+
+    /// Flat element of the shape of a non-empty binary one, with the same origin
+    pub fn flat(element: &StructuringElement) -> Self {
+        Self::from_points(element.offsets().map(|(x, y)| (x, y, 0)).collect())
+    }
+
+    /// Element of the given heights row by row, with the origin in its middle,
+    /// `None` for positions outside the element
+    pub fn non_flat(width: u32, height: u32, heights: &[Option<i16>]) -> Self {
+        assert_eq!(
+            width as usize * height as usize,
+            heights.len(),
+            "Expected a height for every position of the element"
+        );
+        let (origin_x, origin_y) = ((width / 2) as i32, (height / 2) as i32);
+        let points: Vec<(i32, i32, i16)> = heights
+            .iter()
+            .enumerate()
+            .filter_map(|(index, height)| {
+                let (x, y) = (
+                    (index % width as usize) as i32,
+                    (index / width as usize) as i32,
+                );
+                height.map(|height| (x - origin_x, y - origin_y, height))
+            })
+            .collect();
+        Self::from_points(points)
+    }
+
+    /// Non-flat hemisphere of the given radius, zero at the origin and sinking towards the rim,
+    /// as used by the rolling ball background subtraction
+    pub fn ball(radius: u32) -> Self {
+        let r = radius as i64;
+        let size = 2 * radius + 1;
+        let heights: Vec<Option<i16>> = (-r..=r)
+            .flat_map(|y| (-r..=r).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let left = (r * r - x * x - y * y) as f64;
+                (left >= 0.0).then(|| (left.sqrt() - r as f64).round() as i16)
+            })
+            .collect();
+        Self::non_flat(size, size, &heights)
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.points.iter().all(|&(_, _, height)| height == 0)
+    }
+
+    fn from_points(points: Vec<(i32, i32, i16)>) -> Self {
+        assert!(!points.is_empty(), "Structuring element must not be empty");
+        Self { points }
+    }
+}
+
+/// Operation of [`GrayMorphology`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GrayOperation {
+    /// Local maximum of the pixels plus the heights of the element
+    Dilation,
+    /// Local minimum of the pixels minus the heights of the element
+    Erosion,
+    /// Erosion followed by dilation, removes bright details smaller than the element
+    Opening,
+    /// Dilation followed by erosion, removes dark details smaller than the element
+    Closing,
+    /// Dilation minus erosion, bright at edges
+    Gradient,
+    /// Image minus its opening, the bright details smaller than the element
+    TopHat,
+    /// Closing minus the image, the dark details smaller than the element
+    BlackHat,
+}
+
+/// Morphology of grayscale images, on the luma of the image or with
+/// [`GrayMorphology::per_channel`] on each of the RGB channels separately
+pub struct GrayMorphology {
+    operation: GrayOperation,
+    element: GrayStructuringElement,
+    per_channel: bool,
+}
+
+impl GrayMorphology {
+    pub fn new(operation: GrayOperation, element: GrayStructuringElement) -> Self {
+        Self {
+            operation,
+            element,
+            per_channel: false,
+        }
+    }
+
+    pub fn per_channel(self) -> Self {
+        Self {
+            per_channel: true,
+            ..self
+        }
+    }
+
+    pub fn apply_to_gray(&self, image: &GrayImage) -> GrayImage {
+        let element = &self.element;
+        match self.operation {
+            GrayOperation::Dilation => dilate(image, element),
+            GrayOperation::Erosion => erode(image, element),
+            GrayOperation::Opening => dilate(&erode(image, element), element),
+            GrayOperation::Closing => erode(&dilate(image, element), element),
+            GrayOperation::Gradient => difference(&dilate(image, element), &erode(image, element)),
+            GrayOperation::TopHat => difference(image, &dilate(&erode(image, element), element)),
+            GrayOperation::BlackHat => difference(&erode(&dilate(image, element), element), image),
+        }
+    }
+}
+
+impl Transformation for GrayMorphology {
+    fn apply(&self, image: &mut RgbImage) {
+        if !self.per_channel {
+            let result = self.apply_to_gray(&grayscale(image));
+            *image = DynamicImage::from(result).to_rgb8();
+            return;
+        }
+        let channels: Vec<GrayImage> = (0..3)
+            .map(|channel| {
+                let plane = GrayImage::from_fn(image.width(), image.height(), |x, y| {
+                    Luma([image.get_pixel(x, y)[channel]])
+                });
+                self.apply_to_gray(&plane)
+            })
+            .collect();
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            for (channel, plane) in channels.iter().enumerate() {
+                pixel[channel] = plane.get_pixel(x, y)[0];
+            }
+        }
+    }
+}
+
+/// Value of the pixel at the offset from `x, y`, none outside the image
+fn neighbour(image: &GrayImage, x: u32, y: u32, dx: i32, dy: i32) -> Option<i32> {
+    let (x, y) = (x as i64 + dx as i64, y as i64 + dy as i64);
+    match x >= 0 && y >= 0 && x < image.width() as i64 && y < image.height() as i64 {
+        true => Some(image.get_pixel(x as u32, y as u32)[0] as i32),
+        false => None,
+    }
+}
+
+/// Pixels outside the image are ignored, so they neither brighten nor darken the border
+fn dilate(image: &GrayImage, element: &GrayStructuringElement) -> GrayImage {
+    GrayImage::from_fn(image.width(), image.height(), |x, y| {
+        let value = element
+            .points
+            .iter()
+            .filter_map(|&(dx, dy, height)| {
+                neighbour(image, x, y, -dx, -dy).map(|value| value + height as i32)
+            })
+            .max()
+            .unwrap_or(0);
+        Luma([value.clamp(0, u8::MAX as i32) as u8])
+    })
+}
+
+fn erode(image: &GrayImage, element: &GrayStructuringElement) -> GrayImage {
+    GrayImage::from_fn(image.width(), image.height(), |x, y| {
+        let value = element
+            .points
+            .iter()
+            .filter_map(|&(dx, dy, height)| {
+                neighbour(image, x, y, dx, dy).map(|value| value - height as i32)
+            })
+            .min()
+            .unwrap_or(u8::MAX as i32);
+        Luma([value.clamp(0, u8::MAX as i32) as u8])
+    })
+}
+
+fn difference(minuend: &GrayImage, subtrahend: &GrayImage) -> GrayImage {
+    GrayImage::from_fn(minuend.width(), minuend.height(), |x, y| {
+        Luma([minuend.get_pixel(x, y)[0].saturating_sub(subtrahend.get_pixel(x, y)[0])])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    fn row(values: &[u8]) -> GrayImage {
+        GrayImage::from_raw(values.len() as u32, 1, values.to_vec()).unwrap()
+    }
+
+    fn apply(operation: GrayOperation, element: &GrayStructuringElement, values: &[u8]) -> Vec<u8> {
+        GrayMorphology::new(operation, element.clone())
+            .apply_to_gray(&row(values))
+            .into_raw()
+    }
+
+    #[test]
+    fn flat_operations_take_local_extremes() {
+        let element = GrayStructuringElement::rectangle(3, 1);
+        let values = [10, 50, 20, 20, 90, 30, 30];
+
+        assert_eq!(
+            vec![50, 50, 50, 90, 90, 90, 30],
+            apply(GrayOperation::Dilation, &element, &values)
+        );
+        assert_eq!(
+            vec![10, 10, 20, 20, 20, 30, 30],
+            apply(GrayOperation::Erosion, &element, &values)
+        );
+        assert_eq!(
+            vec![10, 20, 20, 20, 30, 30, 30],
+            apply(GrayOperation::Opening, &element, &values)
+        );
+        assert_eq!(
+            vec![0, 30, 0, 0, 60, 0, 0],
+            apply(GrayOperation::TopHat, &element, &values)
+        );
+        assert_eq!(
+            vec![40, 40, 30, 70, 70, 60, 0],
+            apply(GrayOperation::Gradient, &element, &values)
+        );
+    }
+
+    #[test]
+    fn black_hat_finds_dark_details() {
+        let element = GrayStructuringElement::rectangle(3, 1);
+
+        assert_eq!(
+            vec![0, 0, 70, 0, 0],
+            apply(GrayOperation::BlackHat, &element, &[100, 100, 30, 100, 100])
+        );
+    }
+
+    #[test]
+    fn non_flat_element_adds_heights() {
+        let element = GrayStructuringElement::non_flat(3, 1, &[Some(-10), Some(0), Some(5)]);
+
+        // the height 5 lies right of the origin, so it carries each pixel to the right
+        assert_eq!(
+            vec![100, 105, 5],
+            apply(GrayOperation::Dilation, &element, &[100, 0, 0])
+        );
+        assert_eq!(
+            vec![95, 95, 100],
+            apply(GrayOperation::Erosion, &element, &[100, 100, 100])
+        );
+        assert!(!element.is_flat());
+    }
+
+    #[test]
+    fn ball_sinks_towards_rim() {
+        let ball = GrayStructuringElement::ball(3);
+
+        assert!(ball.points.contains(&(0, 0, 0)));
+        assert!(ball.points.contains(&(3, 0, -3)));
+        assert!(!ball.points.iter().any(|&(x, y, _)| (x, y) == (3, 3)));
+    }
+
+    #[test]
+    fn flat_element_keeps_origin() {
+        let element = StructuringElement::rectangle(2, 1).with_origin(0, 0);
+
+        assert_eq!(
+            vec![20, 30, 30],
+            apply(
+                GrayOperation::Erosion,
+                &GrayStructuringElement::flat(&element),
+                &[20, 40, 30]
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Structuring element must not be empty")]
+    fn flat_element_must_not_be_empty() {
+        GrayStructuringElement::from_mask(&Mask::from_raw_data(0));
+    }
+
+    #[test]
+    fn per_channel_keeps_colours() {
+        let mut image = RgbImage::from_pixel(5, 5, Rgb([10, 20, 30]));
+        image.put_pixel(2, 2, Rgb([200, 20, 0]));
+
+        GrayMorphology::new(
+            GrayOperation::Dilation,
+            GrayStructuringElement::rectangle(3, 3),
+        )
+        .per_channel()
+        .apply(&mut image);
+
+        assert_eq!(&Rgb([200, 20, 30]), image.get_pixel(1, 1));
+        assert_eq!(&Rgb([10, 20, 30]), image.get_pixel(0, 0));
+    }
+}
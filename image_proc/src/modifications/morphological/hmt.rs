@@ -1,37 +1,38 @@
-use super::{Mask, MorphologicalTransform, FOREGROUND_PIXEL};
-use image::{GrayImage, ImageBuffer};
+use super::{BinaryImage, Mask, MorphologicalTransform};
+use image::GrayImage;
 
 pub struct HitOrMissTransform {
-    hit_mask: Mask,
-    miss_mask: Mask,
+    hit_offsets: Vec<(i32, i32)>,
+    miss_offsets: Vec<(i32, i32)>,
 }
 
 impl HitOrMissTransform {
     pub fn new(hit_mask: Mask, miss_mask: Mask) -> Self {
         Self {
-            hit_mask,
-            miss_mask,
+            hit_offsets: hit_mask.offsets(),
+            miss_offsets: miss_mask.offsets(),
         }
     }
 
-    pub(crate) fn get_matching_pixels<'img, 's, 'out>(
-        &'s self,
-        image: &'img GrayImage,
-    ) -> impl Iterator<Item = (u32, u32)> + 'out
-    where
-        'img: 'out,
-        's: 'out,
-    {
-        image
-            .enumerate_pixels()
-            .map(|(x, y, _)| (x, y))
-            .filter(|(x, y)| {
-                let img_mask = &Mask::from_image(image, *x, *y);
-                let is_hit = self.hit_mask == (&self.hit_mask & img_mask);
-                let is_miss = self.miss_mask == (&self.miss_mask & &(!img_mask));
+    /// Pixels whose hit mask lies in the foreground and whose miss mask lies in the background,
+    /// with pixels outside the image counted as the background
+    pub(crate) fn matching_pixels(&self, image: &BinaryImage) -> BinaryImage {
+        let mut hits = image.erode_offsets(&self.hit_offsets, false);
+        let mut background = image.clone();
+        background.invert();
+        hits.and(&background.erode_offsets(&self.miss_offsets, true));
+        hits
+    }
+
+    /// Whether the pixel matches, as found by [`HitOrMissTransform::matching_pixels`]
+    pub(crate) fn matches(&self, image: &BinaryImage, x: u32, y: u32) -> bool {
+        let at =
+            |&(dx, dy): &(i32, i32)| image.get_checked(x as i64 + dx as i64, y as i64 + dy as i64);
+        self.hit_offsets.iter().all(at) && !self.miss_offsets.iter().any(at)
+    }
 
-                is_hit && is_miss
-            })
+    pub(crate) fn hit_offsets(&self) -> &[(i32, i32)] {
+        &self.hit_offsets
     }
 }
 
@@ -39,10 +40,8 @@ impl_transform!(HitOrMissTransform);
 
 impl MorphologicalTransform for HitOrMissTransform {
     fn apply_morph_operation(&self, image: &mut GrayImage) {
-        let mut new_image: GrayImage = ImageBuffer::new(image.width(), image.height());
-        for (x, y) in self.get_matching_pixels(image) {
-            new_image.put_pixel(x, y, FOREGROUND_PIXEL);
-        }
-        *image = new_image;
+        *image = self
+            .matching_pixels(&BinaryImage::from_gray(image))
+            .to_gray();
     }
 }
@@ -2,7 +2,7 @@ use super::{is_foreground, BACKGROUND_PIXEL, FOREGROUND_PIXEL};
 use image::{GrayImage, ImageBuffer, Luma, Pixel};
 use std::ops::Deref;
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Mask {
     data: u16, // 9 bits needed, so 2 bytes
 }
@@ -98,6 +98,14 @@ impl Mask {
     pub fn is_empty(&self) -> bool {
         self.data == 0
     }
+
+    /// Offsets of the set bits from the middle of the mask
+    pub fn offsets(&self) -> Vec<(i32, i32)> {
+        (0..9)
+            .filter(|index| self.data & (1 << index) != 0)
+            .map(|index| (index % 3 - 1, index / 3 - 1))
+            .collect()
+    }
 }
 
 impl std::ops::BitAnd<&Mask> for &Mask {
@@ -1,3 +1,4 @@
+use crate::modifications::thresholding::{binarize, GlobalThreshold};
 use crate::modifications::Transformation;
 use image::imageops::grayscale;
 use image::{DynamicImage, GrayImage, Luma, RgbImage};
@@ -15,6 +16,34 @@ pub trait MorphologicalTransform: Transformation {
         self.apply_morph_operation(&mut grayscale);
         *image = DynamicImage::from(grayscale).to_rgb8();
     }
+
+    /// Binarises the image with `threshold` instead of the fixed `LUMA_THRESHOLD` before the operation.
+    /// `GlobalThreshold::Fixed(0)` takes every non-zero pixel of an already binarised image as the foreground.
+    fn with_threshold(self, threshold: GlobalThreshold) -> Thresholded<Self>
+    where
+        Self: Sized,
+    {
+        Thresholded {
+            transform: self,
+            threshold,
+        }
+    }
+}
+
+/// Morphological operation on an image binarised with a chosen threshold
+pub struct Thresholded<T> {
+    transform: T,
+    threshold: GlobalThreshold,
+}
+
+impl<T: MorphologicalTransform> Transformation for Thresholded<T> {
+    fn apply(&self, image: &mut RgbImage) {
+        let mut grayscale = grayscale(image);
+        let level = self.threshold.level_of(&grayscale);
+        binarize(&mut grayscale, level);
+        self.transform.apply_morph_operation(&mut grayscale);
+        *image = DynamicImage::from(grayscale).to_rgb8();
+    }
 }
 
 macro_rules! impl_transform {
@@ -32,12 +61,142 @@ fn is_foreground(pixel: &Luma<u8>) -> bool {
     luma > LUMA_THRESHOLD
 }
 
+pub mod binary_image;
+pub use binary_image::BinaryImage;
+
 pub mod mask;
 pub use mask::Mask;
 
+pub mod structuring_element;
+pub use structuring_element::StructuringElement;
+
 pub mod closing;
 pub mod convex_hull;
 pub mod dilation;
 pub mod erosion;
+pub mod gray_morphology;
 pub mod hmt;
 pub mod opening;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn threshold_decides_foreground() {
+        // dim square that the fixed threshold treats as background
+        let image = RgbImage::from_fn(5, 5, |x, y| {
+            match (1..4).contains(&x) && (1..4).contains(&y) {
+                true => Rgb([100; 3]),
+                false => Rgb([20; 3]),
+            }
+        });
+        let mask = Mask::from_raw_data(0b000010000);
+
+        let mut fixed = image.clone();
+        Transformation::apply(&dilation::Dilation::new(mask.clone()), &mut fixed);
+        let mut otsu = image.clone();
+        dilation::Dilation::new(mask)
+            .with_threshold(GlobalThreshold::Otsu)
+            .apply(&mut otsu);
+
+        assert_eq!(&Rgb([0; 3]), fixed.get_pixel(2, 2));
+        assert_eq!(&Rgb([255; 3]), otsu.get_pixel(2, 2));
+        assert_eq!(&Rgb([0; 3]), otsu.get_pixel(0, 0));
+    }
+
+    fn blocks(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, y| {
+            Luma([u8::from((x / 3 + y / 2 + x * y % 4) % 3 != 0) * 255])
+        })
+    }
+
+    #[test]
+    fn element_path_matches_mask_path() {
+        let mask = Mask::from_raw_data(0b011110010);
+        // the same element with an empty column on the left
+        let shifted = StructuringElement::new(
+            4,
+            3,
+            vec![
+                false, false, true, false, false, false, true, true, false, true, true, false,
+            ],
+        )
+        .with_origin(2, 1);
+        let mut sparse = blocks(20, 15);
+        image::imageops::invert(&mut sparse);
+        for (fast, slow, image) in [
+            (
+                &dilation::Dilation::new(mask.clone()) as &dyn MorphologicalTransform,
+                &dilation::Dilation::from_element(shifted.clone()) as &dyn MorphologicalTransform,
+                sparse,
+            ),
+            (
+                &erosion::Erosion::new(mask.clone()),
+                &erosion::Erosion::from_element(shifted.clone()),
+                blocks(20, 15),
+            ),
+        ] {
+            let (mut expected, mut result) = (image.clone(), image.clone());
+
+            fast.apply_morph_operation(&mut expected);
+            slow.apply_morph_operation(&mut result);
+
+            assert_eq!(expected, result);
+            assert!(result.pixels().any(is_foreground) && expected != image);
+        }
+    }
+
+    /// Erosion as the 3x3 masks were first applied, keeping the foreground pixels
+    /// whose neighbourhood contains the mask
+    fn mask_erosion(mask: &Mask, image: &GrayImage) -> GrayImage {
+        GrayImage::from_fn(image.width(), image.height(), |x, y| {
+            let fits = &(mask & &Mask::from_image(image, x, y)) == mask;
+            match is_foreground(image.get_pixel(x, y)) && fits {
+                true => FOREGROUND_PIXEL,
+                false => BACKGROUND_PIXEL,
+            }
+        })
+    }
+
+    #[test]
+    fn erosion_matches_mask_erosion() {
+        let image = blocks(70, 9);
+        // the top row only, which leaves out the pixel itself
+        let top_row = Mask::from_raw_data(0b000000111);
+        for mask in (0..512).map(Mask::from_raw_data).chain([top_row]) {
+            let mut eroded = image.clone();
+
+            erosion::Erosion::new(mask.clone()).apply_morph_operation(&mut eroded);
+
+            assert!(mask_erosion(&mask, &image) == eroded);
+        }
+    }
+
+    #[test]
+    fn empty_mask_dilates_to_background_and_keeps_eroded_image() {
+        let empty = Mask::from_raw_data(0);
+        let (mut dilated, mut eroded) = (blocks(20, 15), blocks(20, 15));
+
+        dilation::Dilation::new(empty.clone()).apply_morph_operation(&mut dilated);
+        erosion::Erosion::new(empty).apply_morph_operation(&mut eroded);
+
+        assert!(!dilated.pixels().any(is_foreground));
+        assert_eq!(blocks(20, 15), eroded);
+    }
+
+    #[test]
+    fn large_element_dilates_point_into_its_shape() {
+        let mut image = GrayImage::new(9, 9);
+        image.put_pixel(4, 4, FOREGROUND_PIXEL);
+
+        dilation::Dilation::from_element(StructuringElement::disk(3))
+            .apply_morph_operation(&mut image);
+
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let (dx, dy) = (x as i32 - 4, y as i32 - 4);
+            assert_eq!(dx * dx + dy * dy <= 9, is_foreground(pixel), "{} {}", x, y);
+        }
+    }
+}
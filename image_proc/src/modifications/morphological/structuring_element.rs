@@ -0,0 +1,202 @@
+use super::{is_foreground, Mask};
+use image::GrayImage;
+
+/// Binary structuring element of any size and shape, with its origin anywhere inside its bounds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuringElement {
+    width: u32,
+    height: u32,
+    bits: Vec<bool>,
+    origin: (u32, u32),
+}
+
+impl StructuringElement {
+    /// Element of the given bits row by row, with the origin in its middle.
+    /// An element without any bit set dilates everything to the background
+    /// while erosion keeps the image.
+    pub fn new(width: u32, height: u32, bits: Vec<bool>) -> Self {
+        assert_eq!(
+            width as usize * height as usize,
+            bits.len(),
+            "Expected a bit for every position of the element"
+        );
+        Self {
+            width,
+            height,
+            bits,
+            origin: (width / 2, height / 2),
+        }
+    }
+
+    pub fn rectangle(width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "Element size must not be zero");
+        Self::new(width, height, vec![true; width as usize * height as usize])
+    }
+
+    /// Pixels within `radius` of the origin
+    pub fn disk(radius: u32) -> Self {
+        let r = radius as i64;
+        Self::from_predicate(radius, |x, y| x * x + y * y <= r * r)
+    }
+
+    /// Pixels within `radius` of the origin in the city block distance
+    pub fn diamond(radius: u32) -> Self {
+        let r = radius as i64;
+        Self::from_predicate(radius, |x, y| x.abs() + y.abs() <= r)
+    }
+
+    /// Middle row and middle column of a rectangle
+    pub fn cross(width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "Element size must not be zero");
+        let bits = (0..height)
+            .flat_map(|y| (0..width).map(move |x| x == width / 2 || y == height / 2))
+            .collect();
+        Self::new(width, height, bits)
+    }
+
+    /// Digital line of `length` pixels through the origin in its middle,
+    /// at `angle` degrees counter-clockwise from the x axis
+    pub fn line(length: u32, angle: f64) -> Self {
+        assert!(length > 0, "Element size must not be zero");
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let half = (length as f64 - 1.0) / 2.0;
+        let offsets: Vec<(i32, i32)> = (0..length)
+            .map(|step| {
+                let t = step as f64 - half;
+                // the y axis of the image points down
+                ((t * cos).round() as i32, (-t * sin).round() as i32)
+            })
+            .collect();
+        Self::from_offsets(&offsets)
+    }
+
+    /// Foreground pixels of the image, with the origin in its middle
+    pub fn from_image(image: &GrayImage) -> Self {
+        let bits = image.pixels().map(is_foreground).collect();
+        Self::new(image.width(), image.height(), bits)
+    }
+
+    /// Moves the origin to the given position of the element, its middle by default
+    pub fn with_origin(self, x: u32, y: u32) -> Self {
+        assert!(
+            x < self.width && y < self.height,
+            "Origin must lie inside the element"
+        );
+        Self {
+            origin: (x, y),
+            ..self
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn origin(&self) -> (u32, u32) {
+        self.origin
+    }
+
+    /// Whether the element has no pixels
+    pub fn is_empty(&self) -> bool {
+        !self.bits.contains(&true)
+    }
+
+    /// Offsets of the pixels of the element from its origin, row by row
+    pub fn offsets(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let (origin_x, origin_y) = (self.origin.0 as i32, self.origin.1 as i32);
+        self.bits
+            .iter()
+            .enumerate()
+            .filter(|(_, &bit)| bit)
+            .map(move |(index, _)| {
+                let width = self.width as usize;
+                let (x, y) = ((index % width) as i32, (index / width) as i32);
+                (x - origin_x, y - origin_y)
+            })
+    }
+
+    /// Square element of side `2 * radius + 1` of the offsets satisfying the predicate
+    fn from_predicate(radius: u32, predicate: impl Fn(i64, i64) -> bool) -> Self {
+        let size = radius
+            .checked_mul(2)
+            .and_then(|size| size.checked_add(1))
+            .filter(|&size| size <= i32::MAX as u32)
+            .expect("Element radius is too large");
+        let r = radius as i64;
+        let bits = (-r..=r)
+            .flat_map(|y| (-r..=r).map(move |x| (x, y)))
+            .map(|(x, y)| predicate(x, y))
+            .collect();
+        Self::new(size, size, bits)
+    }
+
+    /// Smallest element containing the offsets, with the origin at offset zero
+    fn from_offsets(offsets: &[(i32, i32)]) -> Self {
+        let min_x = offsets.iter().map(|&(x, _)| x).min().unwrap().min(0);
+        let min_y = offsets.iter().map(|&(_, y)| y).min().unwrap().min(0);
+        let max_x = offsets.iter().map(|&(x, _)| x).max().unwrap().max(0);
+        let max_y = offsets.iter().map(|&(_, y)| y).max().unwrap().max(0);
+        let (width, height) = ((max_x - min_x + 1) as u32, (max_y - min_y + 1) as u32);
+        let mut bits = vec![false; width as usize * height as usize];
+        for &(x, y) in offsets {
+            bits[(y - min_y) as usize * width as usize + (x - min_x) as usize] = true;
+        }
+        Self::new(width, height, bits).with_origin((-min_x) as u32, (-min_y) as u32)
+    }
+}
+
+/// An empty mask gives an empty element
+impl From<&Mask> for StructuringElement {
+    fn from(mask: &Mask) -> Self {
+        let bits = (0..9)
+            .map(|index| is_foreground(&mask.get_pixel(index % 3, index / 3)))
+            .collect();
+        Self::new(3, 3, bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offsets(element: &StructuringElement) -> Vec<(i32, i32)> {
+        element.offsets().collect()
+    }
+
+    #[test]
+    fn shapes() {
+        assert_eq!(
+            vec![(0, -1), (-1, 0), (0, 0), (1, 0), (0, 1)],
+            offsets(&StructuringElement::diamond(1))
+        );
+        assert_eq!(13, StructuringElement::disk(2).offsets().count());
+        assert_eq!(9, StructuringElement::cross(5, 5).offsets().count());
+        assert_eq!(
+            vec![(-2, 0), (-1, 0), (0, 0), (1, 0), (2, 0)],
+            offsets(&StructuringElement::line(5, 0.0))
+        );
+        assert_eq!(
+            vec![(1, -1), (0, 0), (-1, 1)],
+            offsets(&StructuringElement::line(3, 45.0))
+        );
+    }
+
+    #[test]
+    fn origin_shifts_offsets() {
+        let element = StructuringElement::rectangle(2, 1).with_origin(0, 0);
+
+        assert_eq!(vec![(0, 0), (1, 0)], offsets(&element));
+    }
+
+    #[test]
+    fn mask_conversion() {
+        let element = StructuringElement::from(&Mask::from_raw_data(0b010111010));
+
+        assert_eq!(offsets(&StructuringElement::diamond(1)), offsets(&element));
+        assert!(StructuringElement::from(&Mask::from_raw_data(0)).is_empty());
+    }
+}
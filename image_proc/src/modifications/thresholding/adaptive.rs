@@ -0,0 +1,205 @@
+use super::{gray_to_rgb, BACKGROUND, FOREGROUND};
+use crate::modifications::Transformation;
+use image::imageops::grayscale;
+use image::{GrayImage, RgbImage};
+
+/// How the threshold of a pixel is computed from the window around it
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AdaptiveMethod {
+    /// Mean of the window lowered by `offset`
+    Mean { offset: f64 },
+    /// Mean of the window weighted by a gaussian, lowered by `offset`
+    Gaussian { offset: f64 },
+    /// `mean + k * deviation`, usually with `k` around -0.2
+    Niblack { k: f64 },
+    /// `mean * (1 + k * (deviation / r - 1))`, usually with `k` around 0.2 to 0.5 and `r` of 128,
+    /// which copes better than Niblack with uneven background of documents
+    Sauvola { k: f64, r: f64 },
+}
+
+/// Binarises the grayscale version of the image with a threshold computed separately for every pixel
+/// from a square window around it, for uneven lighting
+pub struct AdaptiveThreshold {
+    method: AdaptiveMethod,
+    window: u32,
+}
+
+impl AdaptiveThreshold {
+    /// `window` is the side of the square window, it must be odd
+    pub fn new(method: AdaptiveMethod, window: u32) -> Self {
+        assert!(!window.is_multiple_of(2), "Window size must be odd");
+        Self { method, window }
+    }
+
+    fn thresholds(&self, image: &GrayImage) -> Vec<f64> {
+        match self.method {
+            AdaptiveMethod::Mean { offset } => {
+                let (means, _) = local_statistics(image, self.window / 2);
+                means.into_iter().map(|mean| mean - offset).collect()
+            }
+            AdaptiveMethod::Gaussian { offset } => gaussian_means(image, self.window)
+                .into_iter()
+                .map(|mean| mean - offset)
+                .collect(),
+            AdaptiveMethod::Niblack { k } => {
+                let (means, deviations) = local_statistics(image, self.window / 2);
+                means
+                    .into_iter()
+                    .zip(deviations)
+                    .map(|(mean, deviation)| mean + k * deviation)
+                    .collect()
+            }
+            AdaptiveMethod::Sauvola { k, r } => {
+                let (means, deviations) = local_statistics(image, self.window / 2);
+                means
+                    .into_iter()
+                    .zip(deviations)
+                    .map(|(mean, deviation)| mean * (1.0 + k * (deviation / r - 1.0)))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl Transformation for AdaptiveThreshold {
+    fn apply(&self, image: &mut RgbImage) {
+        let mut grayscale = grayscale(image);
+        let thresholds = self.thresholds(&grayscale);
+        for (pixel, threshold) in grayscale.pixels_mut().zip(thresholds) {
+            *pixel = match pixel[0] as f64 > threshold {
+                true => FOREGROUND,
+                false => BACKGROUND,
+            };
+        }
+        *image = gray_to_rgb(grayscale);
+    }
+}
+
+/// Mean and standard deviation of the window of every pixel, with windows cut off at the image borders.
+/// Uses summed-area tables, so the cost does not depend on the window size.
+fn local_statistics(image: &GrayImage, radius: u32) -> (Vec<f64>, Vec<f64>) {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let stride = width + 1;
+    let mut sums = vec![0.0; stride * (height + 1)];
+    let mut squares = vec![0.0; stride * (height + 1)];
+    for y in 0..height {
+        for x in 0..width {
+            let value = image.get_pixel(x as u32, y as u32)[0] as f64;
+            let index = (y + 1) * stride + x + 1;
+            sums[index] = value + sums[index - 1] + sums[index - stride] - sums[index - stride - 1];
+            squares[index] = value * value + squares[index - 1] + squares[index - stride]
+                - squares[index - stride - 1];
+        }
+    }
+
+    let radius = radius as usize;
+    let mut means = Vec::with_capacity(width * height);
+    let mut deviations = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let (top, bottom) = (y.saturating_sub(radius), (y + radius + 1).min(height));
+        for x in 0..width {
+            let (left, right) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+            let area = |table: &[f64]| {
+                table[bottom * stride + right]
+                    - table[top * stride + right]
+                    - table[bottom * stride + left]
+                    + table[top * stride + left]
+            };
+            let count = ((bottom - top) * (right - left)) as f64;
+            let mean = area(&sums) / count;
+            let variance = (area(&squares) / count - mean * mean).max(0.0);
+            means.push(mean);
+            deviations.push(variance.sqrt());
+        }
+    }
+    (means, deviations)
+}
+
+/// Gaussian weighted means with the same standard deviation for a window size as OpenCV,
+/// with the borders extended by repeating the edge pixels
+fn gaussian_means(image: &GrayImage, window: u32) -> Vec<f64> {
+    let radius = (window / 2) as i64;
+    let sigma = 0.3 * ((window as f64 - 1.0) * 0.5 - 1.0) + 0.8;
+    let weights: Vec<f64> = (-radius..=radius)
+        .map(|offset| (-(offset * offset) as f64 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f64 = weights.iter().sum();
+
+    let (width, height) = (image.width() as i64, image.height() as i64);
+    let blur = |values: &[f64], step: (i64, i64)| -> Vec<f64> {
+        let mut result = Vec::with_capacity(values.len());
+        for y in 0..height {
+            for x in 0..width {
+                let sum: f64 = (-radius..=radius)
+                    .zip(&weights)
+                    .map(|(offset, weight)| {
+                        let sample_x = (x + offset * step.0).clamp(0, width - 1);
+                        let sample_y = (y + offset * step.1).clamp(0, height - 1);
+                        values[(sample_y * width + sample_x) as usize] * weight
+                    })
+                    .sum();
+                result.push(sum / total);
+            }
+        }
+        result
+    };
+
+    let values: Vec<f64> = image.pixels().map(|pixel| pixel[0] as f64).collect();
+    blur(&blur(&values, (1, 0)), (0, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Luma, Rgb};
+
+    /// Dark text-like stripes on a background getting brighter from left to right
+    fn unevenly_lit() -> RgbImage {
+        RgbImage::from_fn(64, 16, |x, y| {
+            let background = 60 + x as u8 * 3;
+            let value = match x % 8 == 3 && (4..12).contains(&y) {
+                true => background / 2,
+                false => background,
+            };
+            Rgb([value; 3])
+        })
+    }
+
+    #[test]
+    fn local_statistics_of_constant_image() {
+        let image = GrayImage::from_pixel(7, 5, Luma([90]));
+
+        let (means, deviations) = local_statistics(&image, 2);
+
+        assert!(means.iter().all(|&mean| (mean - 90.0).abs() < 1e-9));
+        assert!(deviations.iter().all(|&deviation| deviation < 1e-6));
+    }
+
+    #[test]
+    fn adaptive_methods_find_stripes_under_uneven_light() {
+        for method in [
+            AdaptiveMethod::Mean { offset: 5.0 },
+            AdaptiveMethod::Gaussian { offset: 5.0 },
+            AdaptiveMethod::Niblack { k: -0.2 },
+            AdaptiveMethod::Sauvola { k: 0.2, r: 128.0 },
+        ] {
+            let mut image = unevenly_lit();
+
+            AdaptiveThreshold::new(method, 7).apply(&mut image);
+
+            for x in 0..64 {
+                let expected = match x % 8 == 3 {
+                    true => 0,
+                    false => 255,
+                };
+                assert_eq!(
+                    expected,
+                    image.get_pixel(x, 8)[0],
+                    "{:?} at column {}",
+                    method,
+                    x
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use super::{binarize, gray_to_rgb};
+use crate::histogram::ChannelHistogram;
+use crate::modifications::Transformation;
+use image::imageops::grayscale;
+use image::{GrayImage, RgbImage};
+
+/// Method of choosing a single threshold for the whole image, pixels brighter than it are the foreground
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GlobalThreshold {
+    /// The given level, 0 treats any non-zero pixel of an already binarised image as the foreground
+    Fixed(u8),
+    /// Maximises the variance between the two classes
+    Otsu,
+    /// Maximises the sum of the entropies of the two classes (Kapur, Sahoo and Wong)
+    Kapur,
+    /// Furthest point of the histogram from the line joining its peak with the end of its longer tail,
+    /// suited to images with a dominant background and a small foreground
+    Triangle,
+}
+
+impl GlobalThreshold {
+    /// Threshold of a histogram with a bin for every 8-bit value
+    pub fn level(&self, histogram: &ChannelHistogram) -> u8 {
+        assert_eq!(
+            256,
+            histogram.bins().len(),
+            "Thresholds require a bin for every value"
+        );
+        let bins = histogram.bins();
+        match self {
+            GlobalThreshold::Fixed(level) => *level,
+            GlobalThreshold::Otsu => multi_otsu_levels(histogram, 2)[0],
+            GlobalThreshold::Kapur => kapur_level(bins),
+            GlobalThreshold::Triangle => triangle_level(bins),
+        }
+    }
+
+    /// Threshold of the grayscale image
+    pub fn level_of(&self, image: &GrayImage) -> u8 {
+        match self {
+            GlobalThreshold::Fixed(level) => *level,
+            _ => self.level(&ChannelHistogram::from_channel(image, 0, 256, None)),
+        }
+    }
+}
+
+/// Thresholds splitting the histogram into `classes` classes with the highest variance between them,
+/// a value belongs to the class of the first threshold it does not exceed
+pub fn multi_otsu_levels(histogram: &ChannelHistogram, classes: usize) -> Vec<u8> {
+    assert!(
+        (2..=256).contains(&classes),
+        "Number of classes must be between 2 and 256"
+    );
+    let bins = histogram.bins();
+    // prefix sums of counts and of values weighted by counts, so that any range is O(1)
+    let mut counts = vec![0.0; bins.len() + 1];
+    let mut sums = vec![0.0; bins.len() + 1];
+    for (value, &count) in bins.iter().enumerate() {
+        counts[value + 1] = counts[value] + count as f64;
+        sums[value + 1] = sums[value] + (value * count as usize) as f64;
+    }
+    // with a fixed total mean, maximising the variance between classes maximises sum of weight * mean^2
+    let score = |first: usize, last: usize| {
+        let count = counts[last + 1] - counts[first];
+        let sum = sums[last + 1] - sums[first];
+        match count > 0.0 {
+            true => sum * sum / count,
+            false => 0.0,
+        }
+    };
+
+    // best[class][last]: best score of values 0..=last split into class + 1 classes,
+    // split[class][last]: last value of the previous class in that split
+    let levels = bins.len();
+    let mut best = vec![vec![f64::MIN; levels]; classes];
+    let mut split = vec![vec![0; levels]; classes];
+    for (last, best) in best[0].iter_mut().enumerate() {
+        *best = score(0, last);
+    }
+    for class in 1..classes {
+        for last in class..levels {
+            for previous in class - 1..last {
+                let candidate = best[class - 1][previous] + score(previous + 1, last);
+                if candidate > best[class][last] {
+                    best[class][last] = candidate;
+                    split[class][last] = previous;
+                }
+            }
+        }
+    }
+
+    let mut thresholds = vec![0u8; classes - 1];
+    let mut last = levels - 1;
+    for class in (1..classes).rev() {
+        last = split[class][last];
+        thresholds[class - 1] = last as u8;
+    }
+    thresholds
+}
+
+fn kapur_level(bins: &[u32]) -> u8 {
+    let total: f64 = bins.iter().map(|&x| x as f64).sum();
+    let probabilities: Vec<f64> = bins.iter().map(|&x| x as f64 / total).collect();
+    let entropy = |range: &[f64]| {
+        let weight: f64 = range.iter().sum();
+        if weight <= 0.0 {
+            return None;
+        }
+        let entropy = range
+            .iter()
+            .filter(|&&p| p > 0.0)
+            .map(|&p| -(p / weight) * (p / weight).ln())
+            .sum::<f64>();
+        Some(entropy)
+    };
+
+    let mut best = (0, f64::MIN);
+    for level in 0..255 {
+        let (background, foreground) = probabilities.split_at(level + 1);
+        if let (Some(low), Some(high)) = (entropy(background), entropy(foreground)) {
+            if low + high > best.1 {
+                best = (level, low + high);
+            }
+        }
+    }
+    best.0 as u8
+}
+
+fn triangle_level(bins: &[u32]) -> u8 {
+    let (Some(first), Some(last)) = (
+        bins.iter().position(|&x| x > 0),
+        bins.iter().rposition(|&x| x > 0),
+    ) else {
+        return 0;
+    };
+    let peak = (first..=last)
+        .max_by_key(|&i| (bins[i], usize::MAX - i))
+        .unwrap();
+
+    // the longer tail is made to lie right of the peak
+    let flipped = peak - first > last - peak;
+    let count = |i: usize| match flipped {
+        true => bins[255 - i] as f64,
+        false => bins[i] as f64,
+    };
+    let (peak, end) = match flipped {
+        true => (255 - peak, 255 - first + 1),
+        false => (peak, last + 1),
+    };
+
+    // the line goes from the top of the peak to zero just past the end of the tail,
+    // all points share the same slope, so the vertical distance is proportional to the perpendicular one
+    let line = |i: usize| count(peak) * (end - i) as f64 / (end - peak) as f64;
+    let level = (peak..end.min(256))
+        .max_by(|&a, &b| (line(a) - count(a)).total_cmp(&(line(b) - count(b))))
+        .unwrap_or(peak);
+
+    match flipped {
+        true => (255 - level).saturating_sub(1) as u8,
+        false => level as u8,
+    }
+}
+
+/// Binarises the grayscale version of the image, with white foreground
+pub struct Binarization {
+    threshold: GlobalThreshold,
+}
+
+impl Binarization {
+    pub fn new(threshold: GlobalThreshold) -> Self {
+        Self { threshold }
+    }
+}
+
+impl Transformation for Binarization {
+    fn apply(&self, image: &mut RgbImage) {
+        let mut grayscale = grayscale(image);
+        let level = self.threshold.level_of(&grayscale);
+        binarize(&mut grayscale, level);
+        *image = gray_to_rgb(grayscale);
+    }
+}
+
+/// Splits the grayscale version of the image into classes with multi-level Otsu's method,
+/// shown as evenly spaced levels of gray
+pub struct MultiOtsu {
+    classes: usize,
+}
+
+impl MultiOtsu {
+    pub fn new(classes: usize) -> Self {
+        assert!(
+            (2..=256).contains(&classes),
+            "Number of classes must be between 2 and 256"
+        );
+        Self { classes }
+    }
+}
+
+impl Transformation for MultiOtsu {
+    fn apply(&self, image: &mut RgbImage) {
+        let mut grayscale = grayscale(image);
+        let histogram = ChannelHistogram::from_channel(&grayscale, 0, 256, None);
+        let thresholds = multi_otsu_levels(&histogram, self.classes);
+        let step = u8::MAX as f64 / (self.classes - 1) as f64;
+        for pixel in grayscale.pixels_mut() {
+            let class = thresholds.partition_point(|&level| level < pixel[0]);
+            pixel[0] = (class as f64 * step).round() as u8;
+        }
+        *image = gray_to_rgb(grayscale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two normal-like peaks of different size
+    fn bimodal() -> ChannelHistogram {
+        let peak = |value: usize, centre: f64, height: f64| {
+            (height * (-((value as f64 - centre) / 12.0).powi(2)).exp()) as u32
+        };
+        let bins = (0..256)
+            .map(|value| peak(value, 60.0, 1000.0) + peak(value, 180.0, 400.0))
+            .collect();
+        ChannelHistogram::from_bins(bins, 256)
+    }
+
+    #[test]
+    fn otsu_separates_peaks() {
+        let histogram = bimodal();
+
+        let level = GlobalThreshold::Otsu.level(&histogram);
+
+        assert!((85..=155).contains(&level), "Otsu gave {}", level);
+        assert_eq!(42, GlobalThreshold::Fixed(42).level(&histogram));
+    }
+
+    #[test]
+    fn kapur_splits_equal_blocks_between_them() {
+        // the sum of entropies does not look for valleys, but is largest for two equally wide classes
+        let bins = (0..256)
+            .map(|value| match value {
+                20..=60 | 150..=190 => 30,
+                _ => 0,
+            })
+            .collect();
+        let histogram = ChannelHistogram::from_bins(bins, 256);
+
+        assert_eq!(60, GlobalThreshold::Kapur.level(&histogram));
+    }
+
+    #[test]
+    fn otsu_splits_two_values_between_them() {
+        let mut bins = vec![0; 256];
+        bins[40] = 10;
+        bins[200] = 30;
+        let histogram = ChannelHistogram::from_bins(bins, 256);
+
+        let level = GlobalThreshold::Otsu.level(&histogram);
+
+        assert!((40..200).contains(&level));
+    }
+
+    #[test]
+    fn multi_otsu_finds_every_gap() {
+        let mut bins = vec![0; 256];
+        for (value, count) in [(20, 50), (25, 40), (100, 30), (110, 30), (230, 60)] {
+            bins[value] = count;
+        }
+        let histogram = ChannelHistogram::from_bins(bins, 256);
+
+        let thresholds = multi_otsu_levels(&histogram, 3);
+
+        assert_eq!(2, thresholds.len());
+        assert!((25..100).contains(&thresholds[0]));
+        assert!((110..230).contains(&thresholds[1]));
+    }
+
+    #[test]
+    fn triangle_follows_longer_tail() {
+        // peak at the bright end, with a long tail towards the dark values
+        let bins = (0..256u32)
+            .map(|value| match value {
+                240.. => 1000,
+                40..=239 => (value - 39) * 2,
+                _ => 0,
+            })
+            .collect();
+        let histogram = ChannelHistogram::from_bins(bins, 256);
+
+        let level = GlobalThreshold::Triangle.level(&histogram);
+
+        assert!((40..240).contains(&level));
+    }
+}
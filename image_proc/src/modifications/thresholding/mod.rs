@@ -0,0 +1,26 @@
+//! Binarisation of images, the foreground is white and the background black
+
+use image::{DynamicImage, GrayImage, Luma, RgbImage};
+
+mod adaptive;
+mod global;
+
+pub use adaptive::*;
+pub use global::*;
+
+const FOREGROUND: Luma<u8> = Luma([255]);
+const BACKGROUND: Luma<u8> = Luma([0]);
+
+/// Pixels brighter than `level` become the foreground, the rest the background
+pub(crate) fn binarize(image: &mut GrayImage, level: u8) {
+    for pixel in image.pixels_mut() {
+        *pixel = match pixel[0] > level {
+            true => FOREGROUND,
+            false => BACKGROUND,
+        };
+    }
+}
+
+fn gray_to_rgb(image: GrayImage) -> RgbImage {
+    DynamicImage::from(image).to_rgb8()
+}
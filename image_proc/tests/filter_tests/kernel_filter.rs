@@ -0,0 +1,48 @@
+use crate::*;
+use image_proc::modifications::filters::linear::{KernelFilter, LinearFilter};
+use image_proc::modifications::frequency_domain::convolution::ConvolutionMethod;
+
+fn test_kernel_filter_matches_linear_filter(
+    mask: [[f64; 3]; 3],
+    mask_scale: Option<f64>,
+    method: ConvolutionMethod,
+) {
+    let mut expected = sample_image();
+    let mut image = sample_image();
+    let kernel = mask.iter().map(|row| row.to_vec()).collect();
+
+    LinearFilter::new(mask, mask_scale).apply(&mut expected);
+    KernelFilter::new(kernel, mask_scale)
+        .with_method(method)
+        .apply(&mut image);
+
+    assert_eq!(expected, image);
+}
+
+invoke_test! { test_kernel_filter_matches_linear_filter {
+    kernel_lowpass_spatial ([[1.0, 2.0, 1.0], [2.0, 4.0, 2.0], [1.0, 2.0, 1.0]], Some(1.0 / 16.0), ConvolutionMethod::Spatial),
+    kernel_lowpass_fourier ([[1.0, 2.0, 1.0], [2.0, 4.0, 2.0], [1.0, 2.0, 1.0]], Some(1.0 / 16.0), ConvolutionMethod::Fourier),
+    kernel_sharpen_spatial ([[0.0, -1.0, 0.0], [-1.0, 5.0, -1.0], [0.0, -1.0, 0.0]], None, ConvolutionMethod::Spatial),
+    kernel_sharpen_fourier ([[0.0, -1.0, 0.0], [-1.0, 5.0, -1.0], [0.0, -1.0, 0.0]], None, ConvolutionMethod::Fourier),
+    kernel_asymmetric_fourier ([[1.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 2.0]], Some(0.5), ConvolutionMethod::Fourier),
+}}
+
+#[test]
+fn kernel_filter_methods_agree_for_large_kernel() {
+    let kernel: Vec<Vec<f64>> = (0..11)
+        .map(|y| (0..11).map(|x| ((x + y) % 3) as f64).collect())
+        .collect();
+    let mut spatial = sample_image();
+    let mut fourier = sample_image();
+    let mut large = RgbImage::from_fn(40, 30, |x, y| Rgb([(x * y) as u8, x as u8, y as u8]));
+    let mut large_fourier = large.clone();
+
+    let filter = |method| KernelFilter::new(kernel.clone(), Some(1.0 / 121.0)).with_method(method);
+    filter(ConvolutionMethod::Spatial).apply(&mut spatial);
+    filter(ConvolutionMethod::Fourier).apply(&mut fourier);
+    filter(ConvolutionMethod::Spatial).apply(&mut large);
+    filter(ConvolutionMethod::Fourier).apply(&mut large_fourier);
+
+    assert_eq!(spatial, fourier);
+    assert_eq!(large, large_fourier);
+}
@@ -1,5 +1,6 @@
 mod gmean_filter_cpu;
 mod gmean_filter_gpu;
+mod kernel_filter;
 mod max_filter_cpu;
 mod max_filter_gpu;
 mod median_filter_cpu;
@@ -0,0 +1,69 @@
+use super::ACCURACY;
+use image_proc::modifications::frequency_domain::fourier_transform::FTDirection;
+use image_proc::modifications::frequency_domain::real_transforms::{dct_2d, dct_2d_blocks};
+
+fn slice_to_vec_2d(input: &[&[f64]]) -> Vec<Vec<f64>> {
+    input.iter().map(|row| row.to_vec()).collect()
+}
+
+fn test_dct_2d(input: &[&[f64]], expected: &[&[f64]]) {
+    let result = dct_2d(&slice_to_vec_2d(input), FTDirection::Forward);
+
+    for (expected, result) in expected
+        .iter()
+        .flat_map(|&x| x)
+        .zip(result.iter().flatten())
+    {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+fn test_inverse_dct_2d(input: &[&[f64]], expected: &[&[f64]]) {
+    let result = dct_2d(&slice_to_vec_2d(input), FTDirection::Inverse);
+
+    for (expected, result) in expected
+        .iter()
+        .flat_map(|&x| x)
+        .zip(result.iter().flatten())
+    {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+fn test_dct_2d_round_trip(width: usize, height: usize, block_size: Option<usize>) {
+    let input: Vec<Vec<f64>> = (0..height)
+        .map(|y| (0..width).map(|x| ((x * 7 + y * 13) % 17) as f64).collect())
+        .collect();
+
+    let transform = |data: &[Vec<f64>], direction| match block_size {
+        Some(block_size) => dct_2d_blocks(data, block_size, direction),
+        None => dct_2d(data, direction),
+    };
+    let result = transform(
+        &transform(&input, FTDirection::Forward),
+        FTDirection::Inverse,
+    );
+
+    for (expected, result) in input.iter().flatten().zip(result.iter().flatten()) {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+invoke_test! { test_dct_2d {
+    dct_forward_1 (&[&[1.0,2.0],&[3.0,4.0]], &[&[5.0,-1.0],&[-2.0,0.0]]),
+    dct_forward_2 (&[&[1.0,0.0,2.0,3.0],&[4.0,5.0,1.0,0.0],&[2.0,2.0,7.0,1.0],&[0.0,3.0,1.0,6.0]],
+        &[&[9.5,-1.1152,-1.0,-0.0793],&[-1.5772,2.1893,0.5412,1.0429],&[-1.5,-4.1110,3.0,-2.0855],&[0.1121,-2.4571,-1.3066,4.3107]]),
+    dct_forward_non_square (&[&[1.0,2.0,3.0],&[4.0,5.0,6.0]], &[&[8.5732,-2.0,0.0],&[-3.6742,0.0,0.0]]),
+}}
+
+invoke_test! { test_inverse_dct_2d {
+    dct_inverse_1 (&[&[5.0,-1.0],&[-2.0,0.0]], &[&[1.0,2.0],&[3.0,4.0]]),
+    dct_inverse_non_square (&[&[8.5732,-2.0,0.0],&[-3.6742,0.0,0.0]], &[&[1.0,2.0,3.0],&[4.0,5.0,6.0]]),
+}}
+
+invoke_test! { test_dct_2d_round_trip {
+    dct_round_trip_square (16, 16, None),
+    dct_round_trip_non_square (12, 7, None),
+    dct_round_trip_blocks (16, 16, Some(8)),
+    dct_round_trip_partial_blocks (21, 13, Some(8)),
+}}
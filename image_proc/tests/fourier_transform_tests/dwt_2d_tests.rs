@@ -0,0 +1,88 @@
+use super::ACCURACY;
+use image_proc::modifications::frequency_domain::fourier_transform::FTDirection;
+use image_proc::modifications::frequency_domain::wavelet::{dwt, dwt_2d, Wavelet};
+use std::f64::consts::FRAC_1_SQRT_2;
+
+fn slice_to_vec_2d(input: &[&[f64]]) -> Vec<Vec<f64>> {
+    input.iter().map(|row| row.to_vec()).collect()
+}
+
+fn sample_data(width: usize, height: usize) -> Vec<Vec<f64>> {
+    (0..height)
+        .map(|y| (0..width).map(|x| ((x * 7 + y * 13) % 17) as f64).collect())
+        .collect()
+}
+
+fn test_dwt(input: &[f64], expected: &[f64], wavelet: Wavelet) {
+    let result = dwt(input, wavelet, FTDirection::Forward);
+
+    for (expected, result) in expected.iter().zip(result.iter()) {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+fn test_dwt_2d(input: &[&[f64]], expected: &[&[f64]], wavelet: Wavelet, levels: u32) {
+    let result = dwt_2d(
+        &slice_to_vec_2d(input),
+        wavelet,
+        levels,
+        FTDirection::Forward,
+    );
+
+    for (expected, result) in expected
+        .iter()
+        .flat_map(|&x| x)
+        .zip(result.iter().flatten())
+    {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+fn test_dwt_2d_round_trip(width: usize, height: usize, wavelet: Wavelet, levels: u32) {
+    let input = sample_data(width, height);
+
+    let transformed = dwt_2d(&input, wavelet, levels, FTDirection::Forward);
+    let result = dwt_2d(&transformed, wavelet, levels, FTDirection::Inverse);
+
+    for (expected, result) in input.iter().flatten().zip(result.iter().flatten()) {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+fn test_dwt_2d_preserves_energy(wavelet: Wavelet) {
+    let input = sample_data(32, 16);
+    let energy = |data: &[Vec<f64>]| data.iter().flatten().map(|x| x * x).sum::<f64>();
+
+    let transformed = dwt_2d(&input, wavelet, 3, FTDirection::Forward);
+
+    assert_delta!(energy(&input), energy(&transformed), ACCURACY);
+}
+
+invoke_test! { test_dwt {
+    dwt_daubechies2 (&[1.0,0.0,2.0,3.0,4.0,5.0,1.0,0.0],
+        &[0.5430,3.7250,6.3386,FRAC_1_SQRT_2,0.0947,0.0,-0.8018,FRAC_1_SQRT_2], Wavelet::Daubechies2),
+}}
+
+invoke_test! { test_dwt_2d {
+    dwt_haar_1 (&[&[1.0,2.0],&[3.0,4.0]], &[&[5.0,-1.0],&[-2.0,0.0]], Wavelet::Haar, 1),
+    dwt_haar_one_level (&[&[1.0,0.0,2.0,3.0],&[4.0,5.0,1.0,0.0],&[2.0,2.0,7.0,1.0],&[0.0,3.0,1.0,6.0]],
+        &[&[5.0,3.0,0.0,0.0],&[3.5,7.5,-1.5,0.5],&[-4.0,2.0,1.0,-1.0],&[0.5,0.5,1.5,5.5]], Wavelet::Haar, 1),
+    dwt_haar_two_levels (&[&[1.0,0.0,2.0,3.0],&[4.0,5.0,1.0,0.0],&[2.0,2.0,7.0,1.0],&[0.0,3.0,1.0,6.0]],
+        &[&[9.5,-1.0,0.0,0.0],&[-1.5,3.0,-1.5,0.5],&[-4.0,2.0,1.0,-1.0],&[0.5,0.5,1.5,5.5]], Wavelet::Haar, 2),
+}}
+
+invoke_test! { test_dwt_2d_round_trip {
+    dwt_round_trip_haar (16, 16, Wavelet::Haar, 4),
+    dwt_round_trip_daubechies2 (32, 8, Wavelet::Daubechies2, 3),
+    dwt_round_trip_daubechies3 (24, 40, Wavelet::Daubechies3, 3),
+    dwt_round_trip_daubechies4 (16, 16, Wavelet::Daubechies4, 4),
+    dwt_round_trip_cdf97 (16, 16, Wavelet::Cdf97, 4),
+    dwt_round_trip_cdf97_non_square (48, 20, Wavelet::Cdf97, 2),
+}}
+
+invoke_test! { test_dwt_2d_preserves_energy {
+    dwt_energy_haar (Wavelet::Haar),
+    dwt_energy_daubechies2 (Wavelet::Daubechies2),
+    dwt_energy_daubechies3 (Wavelet::Daubechies3),
+    dwt_energy_daubechies4 (Wavelet::Daubechies4),
+}}
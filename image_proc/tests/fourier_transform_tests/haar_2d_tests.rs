@@ -0,0 +1,45 @@
+use super::ACCURACY;
+use image_proc::modifications::frequency_domain::fourier_transform::FTDirection;
+use image_proc::modifications::frequency_domain::real_transforms::haar_2d;
+use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+fn slice_to_vec_2d(input: &[&[f64]]) -> Vec<Vec<f64>> {
+    input.iter().map(|row| row.to_vec()).collect()
+}
+
+fn test_haar_2d(input: &[&[f64]], expected: &[&[f64]], direction: FTDirection) {
+    let result = haar_2d(&slice_to_vec_2d(input), direction);
+
+    for (expected, result) in expected
+        .iter()
+        .flat_map(|&x| x)
+        .zip(result.iter().flatten())
+    {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+fn test_haar_2d_round_trip(width: usize, height: usize) {
+    let input: Vec<Vec<f64>> = (0..height)
+        .map(|y| (0..width).map(|x| ((x * 7 + y * 13) % 17) as f64).collect())
+        .collect();
+
+    let result = haar_2d(&haar_2d(&input, FTDirection::Forward), FTDirection::Inverse);
+
+    for (expected, result) in input.iter().flatten().zip(result.iter().flatten()) {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+invoke_test! { test_haar_2d {
+    haar_forward_1 (&[&[1.0,2.0],&[3.0,4.0]], &[&[5.0,-1.0],&[-2.0,0.0]], FTDirection::Forward),
+    haar_forward_2 (&[&[1.0,0.0,2.0,3.0],&[4.0,5.0,1.0,0.0],&[2.0,2.0,7.0,1.0],&[0.0,3.0,1.0,6.0]],
+        &[&[9.5,-1.0,-0.75*SQRT_2,0.25*SQRT_2],&[-1.5,3.0,0.75*SQRT_2,-0.25*SQRT_2],
+          &[-SQRT_2,-3.0*SQRT_2,1.0,-1.0],&[FRAC_1_SQRT_2,0.0,1.5,5.5]], FTDirection::Forward),
+    haar_inverse_1 (&[&[5.0,-1.0],&[-2.0,0.0]], &[&[1.0,2.0],&[3.0,4.0]], FTDirection::Inverse),
+}}
+
+invoke_test! { test_haar_2d_round_trip {
+    haar_round_trip_square (16, 16),
+    haar_round_trip_non_square (8, 32),
+}}
@@ -16,7 +16,11 @@ macro_rules! assert_delta {
     };
 }
 
+mod dct_2d_tests;
 mod dft_1d_tests;
 mod dtt_2d_tests;
+mod dwt_2d_tests;
 mod fft_1d_tests;
 mod fft_2d_tests;
+mod haar_2d_tests;
+mod walsh_hadamard_2d_tests;
@@ -0,0 +1,46 @@
+use super::ACCURACY;
+use image_proc::modifications::frequency_domain::fourier_transform::FTDirection;
+use image_proc::modifications::frequency_domain::real_transforms::walsh_hadamard_2d;
+
+fn slice_to_vec_2d(input: &[&[f64]]) -> Vec<Vec<f64>> {
+    input.iter().map(|row| row.to_vec()).collect()
+}
+
+fn test_walsh_hadamard_2d(input: &[&[f64]], expected: &[&[f64]], direction: FTDirection) {
+    let result = walsh_hadamard_2d(&slice_to_vec_2d(input), direction);
+
+    for (expected, result) in expected
+        .iter()
+        .flat_map(|&x| x)
+        .zip(result.iter().flatten())
+    {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+fn test_walsh_hadamard_2d_round_trip(width: usize, height: usize) {
+    let input: Vec<Vec<f64>> = (0..height)
+        .map(|y| (0..width).map(|x| ((x * 7 + y * 13) % 17) as f64).collect())
+        .collect();
+
+    let result = walsh_hadamard_2d(
+        &walsh_hadamard_2d(&input, FTDirection::Forward),
+        FTDirection::Inverse,
+    );
+
+    for (expected, result) in input.iter().flatten().zip(result.iter().flatten()) {
+        assert_delta!(expected, result, ACCURACY);
+    }
+}
+
+invoke_test! { test_walsh_hadamard_2d {
+    walsh_hadamard_forward_1 (&[&[1.0,2.0],&[3.0,4.0]], &[&[5.0,-1.0],&[-2.0,0.0]], FTDirection::Forward),
+    walsh_hadamard_forward_2 (&[&[1.0,0.0,2.0,3.0],&[4.0,5.0,1.0,0.0],&[2.0,2.0,7.0,1.0],&[0.0,3.0,1.0,6.0]],
+        &[&[9.5,-0.5,-1.0,-1.0],&[-0.5,3.5,-3.0,-1.0],&[-1.5,0.5,3.0,1.0],&[-1.5,-3.5,-3.0,3.0]], FTDirection::Forward),
+    walsh_hadamard_inverse_1 (&[&[5.0,-1.0],&[-2.0,0.0]], &[&[1.0,2.0],&[3.0,4.0]], FTDirection::Inverse),
+}}
+
+invoke_test! { test_walsh_hadamard_2d_round_trip {
+    walsh_hadamard_round_trip_square (16, 16),
+    walsh_hadamard_round_trip_non_square (32, 8),
+}}
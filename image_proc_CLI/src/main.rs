@@ -8,7 +8,7 @@ mod transformations;
 
 use crate::analysis::get_characteristic;
 use analysis::get_comparers;
-use transformations::get_transformation;
+use transformations::{get_transformation, save_histogram, save_histogram_comparison};
 
 fn try_get_image(path: &String) -> Option<RgbImage> {
     match image::io::Reader::open(path) {
@@ -37,7 +37,8 @@ fn main() {
         }
     };
 
-    let transformation: Box<dyn Transformation> = match get_transformation(&args) {
+    let transformation: Box<dyn Transformation> = match get_transformation(&args, img.dimensions())
+    {
         Ok(t) => t,
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -79,6 +80,14 @@ fn main() {
         }
     }
 
+    let histogram_result = match args.command.as_str() {
+        "--histogram" => save_histogram(&args, &img),
+        _ => save_histogram_comparison(&args, &img, &altered_image),
+    };
+    if let Err(e) = histogram_result {
+        eprintln!("Error: {}", e);
+    }
+
     if let Some(output_path) = args.args.get("-o") {
         let save_result = altered_image.save(output_path);
         match save_result {
@@ -101,12 +101,86 @@ Commands:
 \t --brightness -amount=<AMOUNT> \t Image brightness modification
 \t --contrast -amount=<AMOUNT>   \t Image contrast modification
 \t --negative                    \t Image negative
+\t --gamma -gamma=<GAMMA>        \t Power-law transformation, below 1 brightens
+\t --log, --exp                  \t Logarithmic transformation and its inverse
+                                \t  -strength=<STRENGTH>: how strongly dark tones are expanded (default 255)
+\t --tone-curve -points=<POINTS> \t Tone curve through points given as in,out;in,out;...
+                                \t  -spline: smooth curve instead of straight segments
+\t --levels                      \t Stretch of the range between the black and white points
+                                \t  -black=<LEVEL> (default 0), -white=<LEVEL> (default 255)
+                                \t  -gamma=<GAMMA>: gamma of the midtones (default 1)
+\t --auto-levels                 \t Levels with the points from the histogram
+                                \t  -clip=<PERCENT>: ignored darkest and brightest pixels (default 0.5)
+                                \t  -luminance: the same points for all channels, keeping colours
+\t --lut -file=<FILE>            \t Lookup table of 256 values, or 768 for separate R, G, B tables
+
+\t --white-balance               \t Automatic white balance
+                                \t  -method=<METHOD>: gray-world (default) or white-patch
+                                \t  -percentile=<PERCENT>: white of white-patch (default 99)
+\t --temperature -kelvin=<K>     \t Light of the given colour temperature instead of daylight
+\t --saturation -amount=<FACTOR> \t Saturation, 0 gives grayscale and 1 keeps the image
+\t --vibrance -amount=<AMOUNT>   \t Saturation of dull colours, down to -1 (default 0.5)
+\t --hue -degrees=<DEGREES>      \t Hue rotation
+\t --mixer -matrix=<MATRIX>      \t Channel mixer, MATRIX: rr,rg,rb;gr,gg,gb;br,bg,bb
+\t --grayscale, --sepia          \t Grayscale and sepia toned images
+                                \t  -weights=<WEIGHTS>: 601 (default), 709, average or r,g,b
+
+\t --split -space=<SPACE>        \t Channels of a colour space side by side
+                                \t  -prefix=<PATH>: also save each channel to PATH_<CHANNEL>.png
+\t --merge -space=<SPACE> -prefix=<PATH>
+                                \t  Replace channels with the files saved by --split,
+                                \t  channels without a file are kept from the input
+                                \t  SPACE: rgb (default), hsv, hsl, ycbcr, xyz, lab or cmyk
+                                \t  Any command accepts -channel=<CHANNEL> with -space=<SPACE>
+                                \t  to transform only that channel, e.g. -space=lab -channel=L
 
 \t --hflip                       \t Horizontal flip
 \t --vflip                       \t Vertical flip
 \t --dflip                       \t Diagonal flip
 \t --shrink -factor=<FACTOR>     \t Shrink image
 \t --enlarge -factor=<FACTOR>    \t Enlarge image
+\t --resize -width=<W> -height=<H>
+                                \t  Scale to the given size, or to one of them keeping the aspect ratio
+                                \t  -aspect=<MODE>: stretch (default), fit or fill (cropping the overflow)
+                                \t  Scaling accepts -interpolation=<METHOD>:
+                                \t  nearest (default), bilinear, bicubic, lanczos or area
+\t --rotate -angle=<DEGREES>     \t Clockwise rotation around the centre, lossless for multiples of 90
+\t --shear -x=<FX> -y=<FY>       \t Shear around the centre, x' = x + FX * y, y' = y + FY * x
+\t --translate -x=<DX> -y=<DY>   \t Shift by the given number of pixels
+\t --affine -matrix=<MATRIX>     \t Affine warp, MATRIX: a,b,c;d,e,f maps x, y to ax+by+c, dx+ey+f
+                                \t  Warps accept -interpolation (default bilinear), -background=r,g,b
+                                \t  (default black) and -canvas=<CANVAS>: crop (default) or expand
+\t --perspective -matrix=<MATRIX> \t Perspective warp, MATRIX: 3 rows of a homography, as for --affine
+\t --perspective -from=<POINTS> -to=<POINTS>
+                                \t  Perspective warp mapping 4 points given as x,y;x,y;x,y;x,y
+\t --rectify -corners=<POINTS>   \t Straightens the quadrilateral with the corners, clockwise from
+                                \t  the top-left, e.g. a photographed document
+                                \t  Both accept -interpolation, -background and the output size
+                                \t  -width with -height, estimated from the corners by --rectify
+\t --lens -k1=<K1> -k2=<K2> -p1=<P1> -p2=<P2>
+                                \t  Removes Brown-Conrady lens distortion, k1 < 0 for barrel and k1 > 0
+                                \t  for pincushion, tangential p1 and p2; -inverse adds the distortion
+                                \t  -cx with -cy: optical centre (default the middle of the image)
+                                \t  -focal=<F>: focal length in pixels (default half the diagonal)
+\t --polar [-log] [-inverse]     \t Remaps to polar coordinates, radius along x and angle along y,
+                                \t  -log for log-polar, -inverse maps a polar image back
+                                \t  -cx with -cy: centre (default the middle of the image)
+                                \t  -radius=<R>: radius at the right edge (default farthest corner)
+                                \t  -min-radius=<R>: radius at the left edge with -log (default 1)
+                                \t  Both accept -interpolation, -background and the output size
+                                \t  -width with -height for --polar
+\t --crop -x=<X> -y=<Y> -width=<W> -height=<H>
+                                \t  Cuts out the rectangle with the top-left corner at X, Y
+\t --autocrop [-tolerance=<T>]   \t Removes borders of the colour of the top-left pixel, with channels
+                                \t  differing by at most T (default 0) counted as the same colour
+\t --pad -size=<N>               \t Adds N pixels on all sides, or -left, -top, -right and -bottom
+                                \t  pixels, or with -pow2 pads the right and bottom up to powers of two
+                                \t  -mode=<MODE>: constant (default, -background=r,g,b, default black),
+                                \t  replicate (edge pixels) or reflect (mirrored at the edges)
+\t --tiles -width=<W> -height=<H> -prefix=<PATH>
+                                \t  Saves tiles of the image to PATH_<ROW>_<COLUMN>.png
+\t --assemble -width=<W> -height=<H> -prefix=<PATH>
+                                \t  Replaces tiles of the image with the files saved by --tiles
 
 \t --median -w=<WIDTH> -h=<HEIGHT> Median filter on CPU
                                     \t  WIDTH: Width of sample region in pixels
@@ -124,16 +198,111 @@ Commands:
 \t --max-gpu                        \t Maximum filter
 \t --minimum                        \t Minimum filter
 
-\t --histogram -r, -g, -b, -all     \t Create histogram with: Red, Green, Blue or ALL
+\t --histogram -c=<CHANNEL>         \t Create histogram with: Red, Green, Blue, ALL or Luminance
+                                    \t  CHANNEL: r, g, b, all or l
+                                    \t  -plot=<FILE>: save the plot, -export=<FILE>: save the counts,
+                                    \t  as JSON for *.json, CSV otherwise
+                                    \t  -replace: replace the output image with the plot
+                                    \t  -plot-width=<W>, -plot-height=<H>: plot size, 256x100 by default
+                                    \t  -plot-log: logarithmic counts, -plot-cumulative: cumulative curve
+                                    \t  -plot-ticks: axis ticks every 32 values and every quarter of the height
 \t --lowpass                        \t Lowpass filter on CPU
 \t --lowpass-gpu                    \t Lowpass filter on GPU
+\t --kernel-filter -mask=<MASK>     \t Linear filter with a kernel of any odd square size
+                                    \t  MASK: values separated by ; row by row
+                                    \t  -mask-scale=<SCALE>: multiplier of the result
+                                    \t  -method=<METHOD>: auto (default), spatial or fft
+\t --match-template -template=<FILE> Normalised cross-correlation with the template
+                                    \t  -mark: outline the best match instead
 \t --hraleigh -gmin=<GMIN> -gmax=<GMAX> Rayleigh distribution
                                     \t  GMIN: User input
                                     \t  GMAX: User input
+\t --huniform -gmin=<GMIN> -gmax=<GMAX> Uniform distribution
+\t --hexponent -gmin=<GMIN> -gmax=<GMAX> Exponential distribution
+\t --hhyper -gmin=<GMIN> -gmax=<GMAX>   Hyperbolic distribution, GMIN must be positive
+\t --hmatch -reference=<FILE>       \t Histogram matching to a reference image, per channel
+\t --hmatch -histogram=<FILE>       \t Histogram matching to 256 counts from a text file
+\t --hequalize                      \t Histogram equalisation
+\t --clahe                          \t Contrast-limited adaptive histogram equalisation
+                                    \t  -tiles=<TILES>: tiles along each side (default 8)
+                                    \t  -clip=<LIMIT>: clip limit relative to the average bin (default 2)
+                                    \t  The distributions and both equalisations accept -luminance to keep colours
 \t --uolis                          \t Uolis filter
 
+\t --threshold -method=<METHOD>     \t Binarisation of the grayscale image with a global threshold
+                                    \t  METHOD: a level 0-255, otsu, kapur or triangle
+\t --multi-otsu -classes=<N>        \t Multi-level Otsu's method, classes shown as levels of gray
+\t --adaptive-threshold -method=<METHOD>
+                                    \t  Binarisation with a threshold from the window around each pixel
+                                    \t  METHOD: mean, gaussian (-offset, default 5), niblack (-k, default -0.2)
+                                    \t  or sauvola (-k, default 0.2, -r, default 128)
+                                    \t  -window=<SIZE>: odd side of the window (default 15)
+\t --dilation, --erosion, --opening, --closing, --hmt, --convexhull
+                                    \t  -threshold=<METHOD>: binarisation before the operation, as for
+                                    \t  --threshold, 0 for already binary images (default 128)
+\t --dilation, --erosion, --opening, --closing
+                                    \t  -kernel=<MASK>: 3x3 structuring element, 9 values separated by ;
+                                    \t  -element=<SPEC>: structuring element of any size instead, rect:WxH,
+                                    \t  cross:WxH, disk:R, diamond:R, line:LENGTH,ANGLE or a binary image
+                                    \t  -origin=x,y: origin of the element (default its middle)
+\t --gray-morph -op=<OP>            \t Grayscale morphology, OP: dilation, erosion, opening, closing,
+                                    \t  gradient, tophat (bright details) or blackhat (dark details)
+                                    \t  -size=<N>: flat N x N square (default 3), -kernel or -element as
+                                    \t  above for a flat element, or -ball=<RADIUS> for a non-flat hemisphere
+                                    \t  -rgb: each RGB channel separately instead of the luma
+
+\t --dft, --fft                     \t Spectrum of the image
+                                    \t  -component=<COMPONENT>: magnitude (default), power or phase
+                                    \t  -scale=<SCALE>: log (default) or linear
+                                    \t  -no-shift: zero frequency in the corner instead of the centre
+                                    \t  -axes, -grid=<SPACING>: draw frequency axes and grid lines
+                                    \t  -export=<FILE>: save the complex spectrum to the file
+\t --ifft -spectrum=<FILE>          \t Inverse transform of an exported spectrum
+                                    \t  the input image is not used
+\t --dct                            \t Discrete cosine transform coefficients
+                                    \t  -block=<SIZE>: transform SIZExSIZE blocks separately
+\t --walsh-hadamard                 \t Walsh-Hadamard transform coefficients
+\t --haar                           \t Haar transform coefficients
+                                    \t  Walsh-Hadamard and Haar require power of 2 sizes
+\t --dwt                            \t Subbands of the discrete wavelet transform
+\t --wavelet-denoise                \t Wavelet shrinkage denoising
+                                    \t  -rule=<RULE>: visu (default) or bayes
+                                    \t  -mode=<MODE>: soft (default) or hard
+                                    \t  Both accept -wavelet=<WAVELET>: haar (default), db2, db3,
+                                    \t  db4 or cdf97 and -levels=<LEVELS> (default 3)
+
+\t --freq-lowpass -radius=<RADIUS>  \t Low-pass filter in frequency domain
+\t --freq-highpass -radius=<RADIUS> \t High-pass filter in frequency domain
+\t --freq-bandpass -from=<FROM> -to=<TO> Band-pass filter in frequency domain
+\t --freq-bandcut -from=<FROM> -to=<TO>  Band-cut filter in frequency domain
+\t --edge-direction -mask=<FILE>    \t High-pass filter with detection of edge direction
+\t --phase-modify -k=<K> -l=<L>     \t Phase modifying filter
+\t --freq-emphasis -radius=<RADIUS> -offset=<OFFSET> -multiplier=<MULTIPLIER>
+                                    \t  High-frequency emphasis filter
+\t --homomorphic -gamma-low=<GL> -gamma-high=<GH> -cutoff=<CUTOFF>
+                                    \t  Homomorphic filter for illumination correction
+\t --notch-reject -notches=<NOTCHES> -radius=<RADIUS> Notch reject filter
+\t --notch-pass -notches=<NOTCHES> -radius=<RADIUS>   Notch pass filter
+                                    \t  NOTCHES: u,v;u,v... offsets from the centre of the spectrum,
+                                    \t  symmetric notches are added automatically, or auto
+                                    \t  to detect spikes, tuned with -threshold=<STDEVS> (default 4)
+                                    \t  and -min-distance=<DISTANCE> (default 8)
+                                    \t  All frequency domain filters accept -color=<MODE>:
+                                    \t  gray (default), rgb, ycbcr or lab
+                                    \t  Frequency filters accept -shape=<SHAPE>:
+                                    \t  ideal (default), butterworth (with -order=<ORDER>) or gaussian
+
+\t --deconv-inverse -psf=<PSF> -threshold=<THRESHOLD> Inverse filtering
+\t --deconv-wiener -psf=<PSF> -k=<K>  Wiener filtering, K: noise-to-signal ratio
+\t --deconv-cls -psf=<PSF> -gamma=<GAMMA> Constrained least squares filtering
+                                    \t  PSF: motion (-length, -angle), disk (-radius),
+                                    \t  gaussian (-sigma) or an image file with the kernel
+
 Options:
 \t -o=<FILE>            \t Save the image after transformation to the specified file
+\t -histogram-plot=<FILE>   Save histograms before and after the transformation side by side,
+                        \t  with -c and the -plot-* options of --histogram, all channels by default
+\t -histogram-export=<FILE> Save histogram counts before and after the transformation
 
 \t --mse                \t Display mean square error
 \t --pmse               \t Display peak mean square error
@@ -2,26 +2,80 @@ use crate::parsing::Args;
 use image_proc::modifications::{
     filters::{basic::gpu::*, RobertsOperator1, SobelOperator},
     frequency_domain::image_transformations::{
-        filtration::{BandCutFilter, BandPassFilter, HighPassFilter, LowPassFilter},
-        image_fourier_transforms::{DFT, FFT},
+        deconvolution::{ConstrainedLeastSquaresFilter, InverseFilter, WienerFilter},
+        filtration::{
+            BandCutFilter, BandPassFilter, HighFrequencyEmphasisFilter, HighPassFilter,
+            HomomorphicFilter, LowPassFilter,
+        },
+        image_fourier_transforms::{InverseFourierTransform, DFT, FFT},
+        image_real_transforms::{Haar, WalshHadamard},
+        notch::{NotchPassFilter, NotchRejectFilter},
+        wavelets::DWT,
     },
     morphological::{
         closing::Closing, convex_hull::ConvexHull, dilation::Dilation, erosion::Erosion,
         hmt::HitOrMissTransform, opening::Opening, Mask,
     },
     prelude::*,
+    thresholding::Binarization,
     IdTransform, Transformation,
 };
 
 use construction_helpers::{
-    try_new_raleigh, try_new_region_grow, try_parse_hmt_kernel, try_parse_kernel,
+    try_new_raleigh, try_new_region_grow, try_parse_color_mode, try_parse_filter_shape,
+    try_parse_global_threshold, try_parse_hmt_kernel, try_parse_notch_centers, try_parse_psf,
+    try_parse_spectrum_options, try_parse_structuring_element, try_parse_wavelet,
+    try_read_spectrum, with_optional_threshold,
 };
+use histogram::HistogramChannelOptions;
+use image::RgbImage;
+use spectrum_export::SpectrumExport;
 use image_proc::modifications::frequency_domain::image_transformations::filtration::{HighPassFilterWithEdgeDetection, PhaseFilter};
 
+mod channels;
 mod construction_helpers;
 mod histogram;
+mod spectrum_export;
+mod tiles;
 
-pub fn get_transformation(args: &Args) -> Result<Box<dyn Transformation>, String> {
+/// The transformation of the command for an image of `input_size`,
+/// limited to a single channel if `-channel` is given
+pub fn get_transformation(
+    args: &Args,
+    input_size: (u32, u32),
+) -> Result<Box<dyn Transformation>, String> {
+    let transformation = get_rgb_transformation(args, input_size)?;
+    match args.args.contains_key("-channel") {
+        true if changes_size(args) => Err(format!(
+            "{} changes the size of the image and cannot be limited to a channel",
+            args.command
+        )),
+        true => construction_helpers::try_restrict_to_channel(args, transformation),
+        false => Ok(transformation),
+    }
+}
+
+/// Whether the result of the command can differ in size from the image
+fn changes_size(args: &Args) -> bool {
+    let expands = args
+        .args
+        .get("-canvas")
+        .is_some_and(|canvas| canvas == "expand");
+    let sized = args.args.contains_key("-width") || args.args.contains_key("-height");
+    match args.command.as_str() {
+        "--split" | "--merge" | "--shrink" | "--enlarge" | "--resize" | "--rectify" | "--crop"
+        | "--autocrop" | "--pad" | "--tiles" | "--assemble" | "--ifft" => true,
+        "--histogram" => args.args.contains_key("-replace"),
+        "--rotate" | "--shear" | "--translate" | "--affine" => expands,
+        "--perspective" | "--polar" => sized,
+        _ => false,
+    }
+}
+
+fn get_rgb_transformation(
+    args: &Args,
+    input_size: (u32, u32),
+) -> Result<Box<dyn Transformation>, String> {
     match args.command.as_str() {
         "--id" => Ok(Box::new(IdTransform {})),
         "--negative" => Ok(Box::new(Negative {})),
@@ -29,11 +83,58 @@ pub fn get_transformation(args: &Args) -> Result<Box<dyn Transformation>, String
             args.try_get_num_arg::<i32>("amount")?,
         ))),
         "--contrast" => Ok(Box::new(Contrast::new(args.try_get_num_arg::<f64>("amount")?))),
+        "--gamma" => Ok(Box::new(construction_helpers::try_new_gamma(args)?)),
+        "--log" => Ok(Box::new(Logarithmic::new(
+            construction_helpers::try_get_strength(args)?,
+        ))),
+        "--exp" => Ok(Box::new(Exponential::new(
+            construction_helpers::try_get_strength(args)?,
+        ))),
+        "--tone-curve" => Ok(Box::new(construction_helpers::try_new_tone_curve(args)?)),
+        "--levels" => Ok(Box::new(construction_helpers::try_new_levels(args)?)),
+        "--auto-levels" => Ok(Box::new(construction_helpers::try_new_auto_levels(args)?)),
+        "--lut" => Ok(Box::new(construction_helpers::try_new_lookup_table(args)?)),
+        "--white-balance" => Ok(Box::new(construction_helpers::try_new_white_balance(args)?)),
+        "--temperature" => Ok(Box::new(construction_helpers::try_new_color_temperature(
+            args,
+        )?)),
+        "--saturation" => Ok(Box::new(Saturation::new(
+            args.try_get_num_arg::<f64>("amount")?,
+        ))),
+        "--vibrance" => Ok(Box::new(construction_helpers::try_new_vibrance(args)?)),
+        "--hue" => Ok(Box::new(construction_helpers::try_new_hue_rotation(args)?)),
+        "--mixer" => Ok(Box::new(construction_helpers::try_new_channel_mixer(args)?)),
+        "--grayscale" => Ok(Box::new(ChannelMixer::grayscale(
+            construction_helpers::try_parse_luminance_weights(args)?,
+        ))),
+        "--sepia" => Ok(Box::new(ChannelMixer::sepia(
+            construction_helpers::try_parse_luminance_weights(args)?,
+        ))),
+        "--split" => Ok(Box::new(construction_helpers::try_new_channel_split(args)?)),
+        "--merge" => Ok(Box::new(construction_helpers::try_new_channel_merge(args)?)),
         "--hflip" => Ok(Box::new(HorizontalFlip {})),
         "--vflip" => Ok(Box::new(VerticalFlip {})),
         "--dflip" => Ok(Box::new(DiagonalFlip {})),
         "--shrink" => Ok(Box::new(construction_helpers::try_new_shrink(args)?)),
         "--enlarge" => Ok(Box::new(construction_helpers::try_new_enlarge(args)?)),
+        "--resize" => Ok(Box::new(construction_helpers::try_new_resize(args)?)),
+        "--rotate" => construction_helpers::try_new_rotation(args),
+        "--shear" => Ok(Box::new(construction_helpers::try_new_shear(args)?)),
+        "--translate" => Ok(Box::new(construction_helpers::try_new_translation(args)?)),
+        "--affine" => Ok(Box::new(construction_helpers::try_new_affine(args)?)),
+        "--perspective" => Ok(Box::new(construction_helpers::try_new_perspective(args)?)),
+        "--rectify" => Ok(Box::new(construction_helpers::try_new_rectification(args)?)),
+        "--lens" => Ok(Box::new(construction_helpers::try_new_lens_correction(
+            args,
+        )?)),
+        "--polar" => Ok(Box::new(construction_helpers::try_new_polar(args)?)),
+        "--crop" => Ok(Box::new(construction_helpers::try_new_crop(
+            args, input_size,
+        )?)),
+        "--autocrop" => Ok(Box::new(construction_helpers::try_new_auto_crop(args)?)),
+        "--pad" => Ok(Box::new(construction_helpers::try_new_pad(args)?)),
+        "--tiles" => Ok(Box::new(construction_helpers::try_new_tile_split(args)?)),
+        "--assemble" => Ok(Box::new(construction_helpers::try_new_tile_assemble(args)?)),
         "--median" => {
             let (width, height) = construction_helpers::get_width_and_height(args)?;
             Ok(Box::new(MedianFilter::new(width, height)))
@@ -78,83 +179,263 @@ pub fn get_transformation(args: &Args) -> Result<Box<dyn Transformation>, String
             let (width, height) = construction_helpers::get_width_and_height(args)?;
             Ok(Box::new(MinFilter::new(width, height)))
         }
-        "--histogram" => Ok(Box::new(construction_helpers::get_histogram_modifier(
-            args,
-        )?)),
+        "--histogram" => match args.args.contains_key("-replace") {
+            true => Ok(Box::new(construction_helpers::get_histogram_modifier(
+                args,
+            )?)),
+            // the histogram is only saved, see save_histogram
+            false => Ok(Box::new(IdTransform {})),
+        },
         "--lowpass" => Ok(Box::new(construction_helpers::try_new_linear(args)?)),
         "--lowpass-gpu" => Ok(Box::new(construction_helpers::try_new_linear_gpu(args)?)),
+        "--kernel-filter" => Ok(Box::new(construction_helpers::try_new_kernel_filter(args)?)),
+        "--match-template" => Ok(Box::new(construction_helpers::try_new_template_matching(
+            args, input_size,
+        )?)),
         "--hraleigh" => Ok(Box::new(try_new_raleigh(args)?)),
+        "--huniform" => Ok(Box::new(construction_helpers::try_new_uniform(args)?)),
+        "--hexponent" => Ok(Box::new(construction_helpers::try_new_exponential(args)?)),
+        "--hhyper" => Ok(Box::new(construction_helpers::try_new_hyperbolic(args)?)),
+        "--hmatch" => Ok(Box::new(construction_helpers::try_new_histogram_matching(
+            args,
+        )?)),
+        "--hequalize" => Ok(Box::new(construction_helpers::new_equalization(args))),
+        "--clahe" => Ok(Box::new(construction_helpers::try_new_clahe(args)?)),
         "--uolis" => Ok(Box::new(UolisOperator {})),
         "--orobertsi" => Ok(Box::new(RobertsOperator1 {})),
         "--osobel" => Ok(Box::new(SobelOperator {})),
         "--region" => Ok(Box::new(try_new_region_grow(args)?)),
         "--dilation" => {
-            let kernel: Vec<u8> = try_parse_kernel(args)?
-                .into_iter()
-                .map(|x| if x > 0 { 1 } else { 0 })
-                .collect();
-            Ok(Box::new(Dilation::new(Mask::from_raw_bits(&kernel))))
+            let element = try_parse_structuring_element(args)?;
+            with_optional_threshold(args, Dilation::from_element(element))
         }
         "--erosion" => {
-            let kernel: Vec<u8> = try_parse_kernel(args)?
-                .into_iter()
-                .map(|x| if x > 0 { 1 } else { 0 })
-                .collect();
-            Ok(Box::new(Erosion::new(Mask::from_raw_bits(&kernel))))
+            let element = try_parse_structuring_element(args)?;
+            with_optional_threshold(args, Erosion::from_element(element))
         }
         "--hmt" => {
             let (hit, miss) = try_parse_hmt_kernel(args)?;
-            Ok(Box::new(HitOrMissTransform::new(
-                Mask::from_raw_bits(&hit),
-                Mask::from_raw_bits(&miss),
-            )))
+            let transform =
+                HitOrMissTransform::new(Mask::from_raw_bits(&hit), Mask::from_raw_bits(&miss));
+            with_optional_threshold(args, transform)
         }
-        "--convexhull" => Ok(Box::new(ConvexHull {})),
+        "--convexhull" => with_optional_threshold(args, ConvexHull {}),
+        "--gray-morph" => Ok(Box::new(construction_helpers::try_new_gray_morphology(
+            args,
+        )?)),
+        "--threshold" => Ok(Box::new(Binarization::new(try_parse_global_threshold(
+            args, "method",
+        )?))),
+        "--multi-otsu" => Ok(Box::new(construction_helpers::try_new_multi_otsu(args)?)),
+        "--adaptive-threshold" => Ok(Box::new(construction_helpers::try_new_adaptive_threshold(
+            args,
+        )?)),
         "--opening" => {
-            let kernel: Vec<u8> = try_parse_kernel(args)?
-                .into_iter()
-                .map(|x| if x > 0 { 1 } else { 0 })
-                .collect();
-            Ok(Box::new(Opening::new(Mask::from_raw_bits(&kernel))))
+            let element = try_parse_structuring_element(args)?;
+            with_optional_threshold(args, Opening::from_element(element))
         }
         "--closing" => {
-            let kernel: Vec<u8> = try_parse_kernel(args)?
-                .into_iter()
-                .map(|x| if x > 0 { 1 } else { 0 })
-                .collect();
-            Ok(Box::new(Closing::new(Mask::from_raw_bits(&kernel))))
-        }
-        "--dft" => Ok(Box::new(DFT {})),
-        "--fft" => Ok(Box::new(FFT {})),
+            let element = try_parse_structuring_element(args)?;
+            with_optional_threshold(args, Closing::from_element(element))
+        }
+        "--dft" => {
+            let options = try_parse_spectrum_options(args)?;
+            match args.args.get("-export") {
+                Some(path) => Ok(Box::new(SpectrumExport::<DFT>::new(options, path))),
+                None => Ok(Box::new(DFT.with_options(options))),
+            }
+        }
+        "--fft" => {
+            let options = try_parse_spectrum_options(args)?;
+            match args.args.get("-export") {
+                Some(path) => Ok(Box::new(SpectrumExport::<FFT>::new(options, path))),
+                None => Ok(Box::new(FFT.with_options(options))),
+            }
+        }
+        "--ifft" => Ok(Box::new(InverseFourierTransform::new(try_read_spectrum(
+            args,
+        )?))),
+        "--dct" => Ok(Box::new(construction_helpers::try_new_dct(args)?)),
+        "--walsh-hadamard" => Ok(Box::new(WalshHadamard {})),
+        "--haar" => Ok(Box::new(Haar {})),
+        "--dwt" => {
+            let (wavelet, levels) = try_parse_wavelet(args, input_size)?;
+            Ok(Box::new(DWT::new(wavelet, levels)))
+        }
+        "--wavelet-denoise" => Ok(Box::new(construction_helpers::try_new_wavelet_denoise(
+            args, input_size,
+        )?)),
         "--freq-lowpass" => {
             let radius: u32 = args.try_get_num_arg("radius")?;
-            Ok(Box::new(LowPassFilter::new(radius)))
+            let shape = try_parse_filter_shape(args)?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                LowPassFilter::new(radius)
+                    .with_shape(shape)
+                    .with_color_mode(color_mode),
+            ))
         }
         "--freq-highpass" => {
             let radius: u32 = args.try_get_num_arg("radius")?;
-            Ok(Box::new(HighPassFilter::new(radius)))
+            let shape = try_parse_filter_shape(args)?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                HighPassFilter::new(radius)
+                    .with_shape(shape)
+                    .with_color_mode(color_mode),
+            ))
         }
         "--freq-bandpass" => {
             let from: u32 = args.try_get_num_arg("from")?;
             let to: u32 = args.try_get_num_arg("to")?;
-            Ok(Box::new(BandPassFilter::new(from, to)))
+            let shape = try_parse_filter_shape(args)?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                BandPassFilter::new(from, to)
+                    .with_shape(shape)
+                    .with_color_mode(color_mode),
+            ))
         }
         "--freq-bandcut" => {
             let from: u32 = args.try_get_num_arg("from")?;
             let to: u32 = args.try_get_num_arg("to")?;
-            Ok(Box::new(BandCutFilter::new(from, to)))
+            let shape = try_parse_filter_shape(args)?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                BandCutFilter::new(from, to)
+                    .with_shape(shape)
+                    .with_color_mode(color_mode),
+            ))
+        }
+        "--freq-emphasis" => {
+            let radius: u32 = args.try_get_num_arg("radius")?;
+            let offset: f64 = args.try_get_num_arg("offset")?;
+            let multiplier: f64 = args.try_get_num_arg("multiplier")?;
+            let shape = try_parse_filter_shape(args)?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                HighFrequencyEmphasisFilter::new(radius, offset, multiplier)
+                    .with_shape(shape)
+                    .with_color_mode(color_mode),
+            ))
+        }
+        "--homomorphic" => {
+            let gamma_low: f64 = args.try_get_num_arg("gamma-low")?;
+            let gamma_high: f64 = args.try_get_num_arg("gamma-high")?;
+            let cutoff: u32 = args.try_get_num_arg("cutoff")?;
+            let color_mode = try_parse_color_mode(args)?;
+            let filter = HomomorphicFilter::new(gamma_low, gamma_high, cutoff);
+            let filter = match args.args.get("-shape") {
+                Some(_) => filter.with_shape(try_parse_filter_shape(args)?),
+                None => filter,
+            };
+            Ok(Box::new(filter.with_color_mode(color_mode)))
+        }
+        "--notch-reject" => {
+            let centers = try_parse_notch_centers(args)?;
+            let radius: u32 = args.try_get_num_arg("radius")?;
+            let shape = try_parse_filter_shape(args)?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                NotchRejectFilter::new(centers, radius)
+                    .with_shape(shape)
+                    .with_color_mode(color_mode),
+            ))
+        }
+        "--notch-pass" => {
+            let centers = try_parse_notch_centers(args)?;
+            let radius: u32 = args.try_get_num_arg("radius")?;
+            let shape = try_parse_filter_shape(args)?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                NotchPassFilter::new(centers, radius)
+                    .with_shape(shape)
+                    .with_color_mode(color_mode),
+            ))
+        }
+        "--deconv-inverse" => {
+            let psf = try_parse_psf(args)?;
+            let threshold: f64 = args.try_get_num_arg("threshold")?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                InverseFilter::new(psf, threshold).with_color_mode(color_mode),
+            ))
+        }
+        "--deconv-wiener" => {
+            let psf = try_parse_psf(args)?;
+            let noise_to_signal: f64 = args.try_get_num_arg("k")?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                WienerFilter::new(psf, noise_to_signal).with_color_mode(color_mode),
+            ))
+        }
+        "--deconv-cls" => {
+            let psf = try_parse_psf(args)?;
+            let gamma: f64 = args.try_get_num_arg("gamma")?;
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                ConstrainedLeastSquaresFilter::new(psf, gamma).with_color_mode(color_mode),
+            ))
         }
         "--edge-direction" => {
             let path: String = args.try_get_arg("mask")?;
             let mask = construction_helpers::try_open_grayscale_image(&path)?;
-            Ok(Box::new(HighPassFilterWithEdgeDetection::new(mask)))
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(
+                HighPassFilterWithEdgeDetection::new(mask).with_color_mode(color_mode),
+            ))
         }
         "--phase-modify" => {
             let k: f64 = args.try_get_num_arg("k")?;
             let l: f64 = args.try_get_num_arg("l")?;
-            Ok(Box::new(PhaseFilter::new(k, l)))
+            let color_mode = try_parse_color_mode(args)?;
+            Ok(Box::new(PhaseFilter::new(k, l).with_color_mode(color_mode)))
         }
         _ => Err(format!("Command {} undefined", args.command)),
     }
 }
 
+/// Saves the plot of the histogram of the image with `-plot=<FILE>` and its counts with `-export=<FILE>`
+pub fn save_histogram(args: &Args, image: &RgbImage) -> Result<(), String> {
+    let channel = construction_helpers::try_parse_histogram_channel(args)?;
+    if let Some(path) = args.args.get("-plot") {
+        let plot = construction_helpers::try_parse_histogram_plot(args, channel)?;
+        plot.render(image)
+            .save(path)
+            .map_err(|e| format!("Could not save histogram: {}", e))?;
+        println!("Saved histogram to {}", path);
+    }
+    if let Some(path) = args.args.get("-export") {
+        histogram::export_histograms(path, &histogram::histogram_columns(channel, image, ""))?;
+        println!("Saved histogram to {}", path);
+    }
+    Ok(())
+}
+
+/// Saves histograms of the image before and after the transformation, when requested with
+/// `-histogram-plot=<FILE>` or `-histogram-export=<FILE>`, without changing the output image
+pub fn save_histogram_comparison(
+    args: &Args,
+    before: &RgbImage,
+    after: &RgbImage,
+) -> Result<(), String> {
+    let channel = match args.args.contains_key("-c") {
+        true => construction_helpers::try_parse_histogram_channel(args)?,
+        false => HistogramChannelOptions::All,
+    };
+    if let Some(path) = args.args.get("-histogram-plot") {
+        let plot = construction_helpers::try_parse_histogram_plot(args, channel)?;
+        plot.render_comparison(before, after)
+            .save(path)
+            .map_err(|e| format!("Could not save histograms: {}", e))?;
+        println!("Saved histograms to {}", path);
+    }
+    if let Some(path) = args.args.get("-histogram-export") {
+        let mut columns = histogram::histogram_columns(channel, before, "before_");
+        columns.extend(histogram::histogram_columns(channel, after, "after_"));
+        histogram::export_histograms(path, &columns)?;
+        println!("Saved histograms to {}", path);
+    }
+    Ok(())
+}
+
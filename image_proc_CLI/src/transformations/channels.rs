@@ -0,0 +1,91 @@
+use image::{imageops, DynamicImage, GrayImage, RgbImage};
+use image_proc::color_space::ColorSpace;
+use image_proc::modifications::Transformation;
+
+/// Path of the file with a single channel, `<prefix>_<channel>.png`
+pub fn channel_path(prefix: &str, space: ColorSpace, channel: usize) -> String {
+    format!("{}_{}.png", prefix, space.channel_names()[channel])
+}
+
+/// Shows the channels of a colour space side by side, optionally saving each of them to a file
+pub struct ChannelSplit {
+    space: ColorSpace,
+    prefix: Option<String>,
+}
+
+impl ChannelSplit {
+    pub fn new(space: ColorSpace) -> Self {
+        Self {
+            space,
+            prefix: None,
+        }
+    }
+
+    pub fn with_prefix(self, prefix: &str) -> Self {
+        Self {
+            prefix: Some(prefix.to_string()),
+            ..self
+        }
+    }
+}
+
+impl Transformation for ChannelSplit {
+    fn apply(&self, image: &mut RgbImage) {
+        let channels: Vec<GrayImage> = self
+            .space
+            .split(image)
+            .iter()
+            .enumerate()
+            .map(|(channel, plane)| self.space.encode(plane, channel))
+            .collect();
+        if let Some(prefix) = &self.prefix {
+            for (channel, plane) in channels.iter().enumerate() {
+                let path = channel_path(prefix, self.space, channel);
+                match plane.save(&path) {
+                    Ok(_) => println!("Saved channel to {}", path),
+                    Err(error) => eprintln!("Error while saving channel: {}", error),
+                }
+            }
+        }
+
+        let (width, height) = image.dimensions();
+        let mut strip = GrayImage::new(width * channels.len() as u32, height);
+        for (index, plane) in channels.iter().enumerate() {
+            imageops::replace(&mut strip, plane, (index as u32 * width) as i64, 0);
+        }
+        *image = DynamicImage::from(strip).to_rgb8();
+    }
+}
+
+/// Replaces channels of a colour space with the given 8-bit images, as saved by [`ChannelSplit`]
+pub struct ChannelMerge {
+    space: ColorSpace,
+    /// Channels missing here are kept from the image
+    channels: Vec<Option<GrayImage>>,
+}
+
+impl ChannelMerge {
+    pub fn new(space: ColorSpace, channels: Vec<Option<GrayImage>>) -> Self {
+        assert_eq!(space.channel_count(), channels.len());
+        Self { space, channels }
+    }
+}
+
+impl Transformation for ChannelMerge {
+    fn apply(&self, image: &mut RgbImage) {
+        let mut planes = self.space.split(image);
+        for (channel, replacement) in self.channels.iter().enumerate() {
+            match replacement {
+                Some(replacement) if replacement.dimensions() != image.dimensions() => {
+                    eprintln!(
+                        "Error: channel {} has a different size than the image, keeping it",
+                        self.space.channel_names()[channel]
+                    )
+                }
+                Some(replacement) => planes[channel] = self.space.decode(replacement, channel),
+                None => {}
+            }
+        }
+        *image = self.space.merge(&planes);
+    }
+}
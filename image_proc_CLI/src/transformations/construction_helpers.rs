@@ -1,13 +1,52 @@
 use crate::parsing::Args;
-use crate::transformations::histogram;
-use crate::transformations::histogram::HistogramConverter;
+use crate::transformations::channels::{channel_path, ChannelMerge, ChannelSplit};
+use crate::transformations::tiles::{TileAssemble, TileSplit};
+use crate::transformations::histogram::{
+    HistogramChannelOptions, HistogramConverter, HistogramPlot,
+};
+use image_proc::color_space::ColorSpace;
+use image_proc::modifications::channel::ChannelTransformation;
 use image_proc::modifications::filters::linear::optimized::LinearFilterGPU;
-use image_proc::modifications::geometric::Scale;
+use image_proc::modifications::filters::linear::KernelFilter;
+use image_proc::modifications::frequency_domain::convolution::ConvolutionMethod;
+use image_proc::modifications::frequency_domain::image_transformations::color_mode::ColorMode;
+use image_proc::modifications::frequency_domain::image_transformations::deconvolution::PointSpreadFunction;
+use image_proc::modifications::frequency_domain::image_transformations::filter_shape::FilterShape;
+use image_proc::modifications::frequency_domain::image_transformations::image_fourier_transforms::{
+    SpectrumComponent, SpectrumOptions, SpectrumOverlay, SpectrumScale,
+};
+use image_proc::modifications::frequency_domain::image_transformations::image_real_transforms::DCT;
+use image_proc::modifications::frequency_domain::image_transformations::notch::NotchCenters;
+use image_proc::modifications::frequency_domain::image_transformations::spectrum_file::read_spectrum;
+use image_proc::modifications::frequency_domain::image_transformations::template_matching::{
+    MatchOutput, TemplateMatching,
+};
+use image_proc::modifications::frequency_domain::image_transformations::wavelets::{
+    ThresholdRule, Thresholding, WaveletDenoise,
+};
+use image_proc::modifications::frequency_domain::wavelet::Wavelet;
+use image_proc::modifications::geometric::{
+    AffineTransform, AspectMode, AutoCrop, BrownConrady, Canvas, Crop, Interpolation,
+    LensCorrection, Pad, PaddingMode, PerspectiveTransform, PolarTransform, RadialScale, Rotate180,
+    Rotate270, Rotate90, Scale,
+};
 use image_proc::modifications::prelude::*;
+use image_proc::modifications::morphological::gray_morphology::{
+    GrayMorphology, GrayOperation, GrayStructuringElement,
+};
+use image_proc::modifications::morphological::{
+    Mask, MorphologicalTransform, StructuringElement,
+};
 use image_proc::modifications::segmentation::RegionGrowing;
-use num::Integer;
+use image_proc::modifications::thresholding::{
+    AdaptiveMethod, AdaptiveThreshold, GlobalThreshold, MultiOtsu,
+};
+use image_proc::modifications::{IdTransform, Transformation};
+use num::{Complex, Integer};
+use std::fs::File;
+use std::io::BufReader;
 use std::num::{ParseFloatError, ParseIntError};
-use image::GrayImage;
+use image::{GrayImage, Luma, Rgb};
 
 pub fn try_open_grayscale_image(path: &str) -> Result<GrayImage, String> {
     match image::open(path) {
@@ -18,15 +57,321 @@ pub fn try_open_grayscale_image(path: &str) -> Result<GrayImage, String> {
     }
 }
 
+pub fn try_parse_color_mode(args: &Args) -> Result<ColorMode, String> {
+    match args.args.get("-color") {
+        Some(mode) => match mode.as_str() {
+            "gray" => Ok(ColorMode::Grayscale),
+            "rgb" => Ok(ColorMode::Rgb),
+            "ycbcr" => Ok(ColorMode::YCbCr),
+            "lab" => Ok(ColorMode::Lab),
+            _ => Err(format!("Unknown color mode {}", mode)),
+        },
+        None => Ok(ColorMode::default()),
+    }
+}
+
+pub fn try_parse_color_space(args: &Args) -> Result<ColorSpace, String> {
+    match args.args.get("-space") {
+        Some(space) => match space.as_str() {
+            "rgb" => Ok(ColorSpace::Rgb),
+            "hsv" => Ok(ColorSpace::Hsv),
+            "hsl" => Ok(ColorSpace::Hsl),
+            "ycbcr" => Ok(ColorSpace::YCbCr),
+            "xyz" => Ok(ColorSpace::Xyz),
+            "lab" => Ok(ColorSpace::Lab),
+            "cmyk" => Ok(ColorSpace::Cmyk),
+            _ => Err(format!("Unknown colour space {}", space)),
+        },
+        None => Ok(ColorSpace::default()),
+    }
+}
+
+/// Restricts the transformation to the channel given by `-channel`, in the space given by `-space`
+pub fn try_restrict_to_channel(
+    args: &Args,
+    transformation: Box<dyn Transformation>,
+) -> Result<Box<dyn Transformation>, String> {
+    let space = try_parse_color_space(args)?;
+    let name = args.try_get_arg("channel")?;
+    let channel = space.channel_index(&name).ok_or(format!(
+        "Unknown channel {}, expected one of {}",
+        name,
+        space.channel_names().join(", ")
+    ))?;
+    Ok(Box::new(ChannelTransformation::new(
+        transformation,
+        space,
+        channel,
+    )))
+}
+
+pub fn try_new_channel_split(args: &Args) -> Result<ChannelSplit, String> {
+    let split = ChannelSplit::new(try_parse_color_space(args)?);
+    match args.args.get("-prefix") {
+        Some(prefix) => Ok(split.with_prefix(prefix)),
+        None => Ok(split),
+    }
+}
+
+/// Reads the channels saved by `--split` with the same prefix, channels without a file are kept
+pub fn try_new_channel_merge(args: &Args) -> Result<ChannelMerge, String> {
+    let space = try_parse_color_space(args)?;
+    let prefix = args.try_get_arg("prefix")?;
+    let channels = (0..space.channel_count())
+        .map(|channel| {
+            let path = channel_path(&prefix, space, channel);
+            match std::path::Path::new(&path).exists() {
+                true => try_open_grayscale_image(&path).map(Some),
+                false => Ok(None),
+            }
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if channels.iter().all(Option::is_none) {
+        return Err(format!("No channel files found with prefix {}", prefix));
+    }
+    Ok(ChannelMerge::new(space, channels))
+}
+
+pub fn try_parse_filter_shape(args: &Args) -> Result<FilterShape, String> {
+    match args.args.get("-shape") {
+        Some(shape) => match shape.as_str() {
+            "ideal" => Ok(FilterShape::Ideal),
+            "butterworth" => {
+                let order: u32 = match args.args.get("-order") {
+                    Some(_) => args.try_get_num_arg("order")?,
+                    None => 2,
+                };
+                Ok(FilterShape::Butterworth(order))
+            }
+            "gaussian" => Ok(FilterShape::Gaussian),
+            _ => Err(format!("Unknown filter shape {}", shape)),
+        },
+        None => Ok(FilterShape::default()),
+    }
+}
+
+/// Largest extent of a point-spread function in pixels, more than any practical blur
+const MAX_PSF_EXTENT: f64 = 1024.0;
+
+pub fn try_parse_psf(args: &Args) -> Result<PointSpreadFunction, String> {
+    let get_extent = |name: &str| -> Result<f64, String> {
+        let value: f64 = args.try_get_num_arg(name)?;
+        match value <= MAX_PSF_EXTENT {
+            true => Ok(value),
+            false => Err(format!("{} must be at most {}", name, MAX_PSF_EXTENT)),
+        }
+    };
+    let psf = args.try_get_arg("psf")?;
+    match psf.as_str() {
+        "motion" => Ok(PointSpreadFunction::MotionBlur {
+            length: get_extent("length")?,
+            angle: try_get_required_signed_arg(args, "angle")?,
+        }),
+        "disk" => Ok(PointSpreadFunction::Disk {
+            radius: get_extent("radius")?,
+        }),
+        "gaussian" => match get_extent("sigma")? {
+            sigma if sigma > 0.0 => Ok(PointSpreadFunction::Gaussian { sigma }),
+            _ => Err("sigma must be positive".to_string()),
+        },
+        path => {
+            let image = try_open_grayscale_image(path)?;
+            match image.pixels().any(|&Luma([luma])| luma > 0) {
+                true => Ok(PointSpreadFunction::from_image(&image)),
+                false => Err(format!("Point-spread function {} is empty", path)),
+            }
+        }
+    }
+}
+
+pub fn try_parse_notch_centers(args: &Args) -> Result<NotchCenters, String> {
+    let notches = args.try_get_arg("notches")?;
+    if notches == "auto" {
+        let threshold: f64 = match args.args.get("-threshold") {
+            Some(_) => args.try_get_num_arg("threshold")?,
+            None => 4.0,
+        };
+        let min_distance: u32 = match args.args.get("-min-distance") {
+            Some(_) => args.try_get_num_arg("min-distance")?,
+            None => 8,
+        };
+        return Ok(NotchCenters::Automatic {
+            threshold,
+            min_distance,
+        });
+    }
+
+    let mut centers = Vec::new();
+    for notch in notches.split(';') {
+        let coordinates: Vec<&str> = notch.split(',').collect();
+        if coordinates.len() != 2 {
+            return Err(format!("Expected notch in format u,v, got {}", notch));
+        }
+        let u: i32 = coordinates[0]
+            .parse()
+            .map_err(|e: ParseIntError| e.to_string())?;
+        let v: i32 = coordinates[1]
+            .parse()
+            .map_err(|e: ParseIntError| e.to_string())?;
+        centers.push((u, v));
+    }
+    Ok(NotchCenters::Manual(centers))
+}
+
+pub fn try_parse_spectrum_options(args: &Args) -> Result<SpectrumOptions, String> {
+    let component = match args.args.get("-component").map(|x| x.as_str()) {
+        Some("magnitude") | None => SpectrumComponent::Magnitude,
+        Some("power") => SpectrumComponent::Power,
+        Some("phase") => SpectrumComponent::Phase,
+        Some(component) => return Err(format!("Unknown spectrum component {}", component)),
+    };
+    let scale = match args.args.get("-scale").map(|x| x.as_str()) {
+        Some("log") | None => SpectrumScale::Logarithmic,
+        Some("linear") => SpectrumScale::Linear,
+        Some(scale) => return Err(format!("Unknown spectrum scale {}", scale)),
+    };
+    let overlay = match (args.args.get("-grid"), args.args.get("-axes")) {
+        (Some(_), _) => {
+            let spacing: u32 = args.try_get_num_arg("grid")?;
+            if spacing == 0 {
+                return Err("Grid spacing must be positive".to_string());
+            }
+            SpectrumOverlay::Grid { spacing }
+        }
+        (None, Some(_)) => SpectrumOverlay::Axes,
+        (None, None) => SpectrumOverlay::None,
+    };
+    Ok(SpectrumOptions {
+        component,
+        scale,
+        centered: !args.args.contains_key("-no-shift"),
+        overlay,
+    })
+}
+
+pub fn try_read_spectrum(args: &Args) -> Result<Vec<Vec<Complex<f64>>>, String> {
+    let path = args.try_get_arg("spectrum")?;
+    let file = File::open(&path).map_err(|e| format!("Could not open {}: {}", path, e))?;
+    let spectrum = read_spectrum(&mut BufReader::new(file)).map_err(|e| e.to_string())?;
+    if !spectrum.len().is_power_of_two() || !spectrum[0].len().is_power_of_two() {
+        return Err("Spectrum dimensions must be powers of 2".to_string());
+    }
+    Ok(spectrum)
+}
+
+/// Parses `-wavelet` and the number of decomposition `-levels` of an image of the given size
+pub fn try_parse_wavelet(
+    args: &Args,
+    (width, height): (u32, u32),
+) -> Result<(Wavelet, u32), String> {
+    let wavelet = match args.args.get("-wavelet").map(|x| x.as_str()) {
+        Some("haar") | None => Wavelet::Haar,
+        Some("db2") => Wavelet::Daubechies2,
+        Some("db3") => Wavelet::Daubechies3,
+        Some("db4") => Wavelet::Daubechies4,
+        Some("cdf97") => Wavelet::Cdf97,
+        Some(wavelet) => return Err(format!("Unknown wavelet {}", wavelet)),
+    };
+    let levels: u32 = match args.args.get("-levels") {
+        Some(_) => args.try_get_num_arg("levels")?,
+        None => 3,
+    };
+    if levels == 0 {
+        return Err("Number of levels must be positive".to_string());
+    }
+    // images are mirrored up to a multiple of `2^levels`, which must not exceed the image
+    if levels > width.min(height).checked_ilog2().unwrap_or(0) {
+        return Err(format!(
+            "{}x{} image cannot be decomposed into {} levels",
+            width, height, levels
+        ));
+    }
+    Ok((wavelet, levels))
+}
+
+/// The whole image, or blocks of `-block=<SIZE>` transformed separately
+pub fn try_new_dct(args: &Args) -> Result<DCT, String> {
+    if !args.args.contains_key("-block") {
+        return Ok(DCT::new());
+    }
+    match args.try_get_num_arg("block")? {
+        0 => Err("Block size must be positive".to_string()),
+        block_size => Ok(DCT::new().with_blocks(block_size)),
+    }
+}
+
+pub fn try_new_wavelet_denoise(
+    args: &Args,
+    input_size: (u32, u32),
+) -> Result<WaveletDenoise, String> {
+    let (wavelet, levels) = try_parse_wavelet(args, input_size)?;
+    let rule = match args.args.get("-rule").map(|x| x.as_str()) {
+        Some("visu") | None => ThresholdRule::VisuShrink,
+        Some("bayes") => ThresholdRule::BayesShrink,
+        Some(rule) => return Err(format!("Unknown threshold rule {}", rule)),
+    };
+    let thresholding = match args.args.get("-mode").map(|x| x.as_str()) {
+        Some("soft") | None => Thresholding::Soft,
+        Some("hard") => Thresholding::Hard,
+        Some(mode) => return Err(format!("Unknown thresholding mode {}", mode)),
+    };
+    Ok(WaveletDenoise::new(wavelet, levels)
+        .with_rule(rule)
+        .with_thresholding(thresholding)
+        .with_color_mode(try_parse_color_mode(args)?))
+}
+
+pub fn try_parse_interpolation(args: &Args) -> Result<Interpolation, String> {
+    match args.args.get("-interpolation") {
+        Some(interpolation) => match interpolation.as_str() {
+            "nearest" => Ok(Interpolation::Nearest),
+            "bilinear" => Ok(Interpolation::Bilinear),
+            "bicubic" => Ok(Interpolation::Bicubic),
+            "lanczos" => Ok(Interpolation::Lanczos3),
+            "area" => Ok(Interpolation::Area),
+            _ => Err(format!("Unknown interpolation {}", interpolation)),
+        },
+        None => Ok(Interpolation::default()),
+    }
+}
+
 pub fn try_new_enlarge(args: &Args) -> Result<Scale, String> {
     let factor = args.try_get_num_arg("amount")?;
-    Ok(Scale::new(factor, factor))
+    Ok(Scale::new(factor, factor).with_interpolation(try_parse_interpolation(args)?))
 }
 
 pub fn try_new_shrink(args: &Args) -> Result<Scale, String> {
     // invert the factor - shrink x2 = scale x0.5
     let factor = 1f64 / args.try_get_num_arg::<f64>("amount")?;
-    Ok(Scale::new(factor, factor))
+    Ok(Scale::new(factor, factor).with_interpolation(try_parse_interpolation(args)?))
+}
+
+/// Scales to `-width` and `-height`, or to one of them keeping the aspect ratio
+pub fn try_new_resize(args: &Args) -> Result<Scale, String> {
+    let get_size = |name: &str| -> Result<Option<u32>, String> {
+        match args.args.contains_key(&format!("-{}", name)) {
+            true => match args.try_get_num_arg::<u32>(name)? {
+                0 => Err("Target size must not be zero".to_string()),
+                size => Ok(Some(size)),
+            },
+            false => Ok(None),
+        }
+    };
+    let scale = match (get_size("width")?, get_size("height")?) {
+        (Some(width), Some(height)) => {
+            let aspect = match args.args.get("-aspect").map(|x| x.as_str()) {
+                None | Some("stretch") => AspectMode::Stretch,
+                Some("fit") => AspectMode::Fit,
+                Some("fill") => AspectMode::Fill,
+                Some(aspect) => return Err(format!("Unknown aspect mode {}", aspect)),
+            };
+            Scale::to_size(width, height, aspect)
+        }
+        (Some(width), None) => Scale::to_width(width),
+        (None, Some(height)) => Scale::to_height(height),
+        (None, None) => return Err("Missing width or height argument".to_string()),
+    };
+    Ok(scale.with_interpolation(try_parse_interpolation(args)?))
 }
 
 pub fn get_width_and_height(args: &Args) -> Result<(u32, u32), String> {
@@ -41,26 +386,139 @@ pub fn get_width_and_height(args: &Args) -> Result<(u32, u32), String> {
     Ok((width, height))
 }
 
-pub fn get_histogram_modifier(args: &Args) -> Result<HistogramConverter, String> {
-    match args.args.get("-c") {
-        Some(channel_arg) => {
-            let channel = match channel_arg.as_str() {
-                "r" => histogram::HistogramChannelOptions::R,
-                "g" => histogram::HistogramChannelOptions::G,
-                "b" => histogram::HistogramChannelOptions::B,
-                "all" => histogram::HistogramChannelOptions::All,
-                _ => panic!("Invalid option"),
-            };
-            Ok(HistogramConverter::new(channel))
-        }
-        _ => Err(String::from("Missing -c argument")),
+/// Parses `-c=<r|g|b|all|l>`, the channels shown in a histogram
+pub fn try_parse_histogram_channel(args: &Args) -> Result<HistogramChannelOptions, String> {
+    match args.try_get_arg("c")?.as_str() {
+        "r" => Ok(HistogramChannelOptions::R),
+        "g" => Ok(HistogramChannelOptions::G),
+        "b" => Ok(HistogramChannelOptions::B),
+        "all" => Ok(HistogramChannelOptions::All),
+        "l" => Ok(HistogramChannelOptions::Luminance),
+        other => Err(format!(
+            "Invalid channel {}, expected r, g, b, all or l",
+            other
+        )),
     }
 }
 
-pub fn try_new_raleigh(args: &Args) -> Result<RayleighModification, String> {
+/// Parses `-plot-width`, `-plot-height`, `-plot-log`, `-plot-cumulative` and `-plot-ticks`
+pub fn try_parse_histogram_plot(
+    args: &Args,
+    channel: HistogramChannelOptions,
+) -> Result<HistogramPlot, String> {
+    let width = match args.args.contains_key("-plot-width") {
+        true => args.try_get_num_arg("plot-width")?,
+        false => 256,
+    };
+    let height = match args.args.contains_key("-plot-height") {
+        true => args.try_get_num_arg("plot-height")?,
+        false => 100,
+    };
+    if width == 0 || height == 0 {
+        return Err("Plot size must be positive".to_string());
+    }
+    Ok(HistogramPlot::new(channel)
+        .with_size(width, height)
+        .with_log_scale(args.args.contains_key("-plot-log"))
+        .with_cumulative(args.args.contains_key("-plot-cumulative"))
+        .with_ticks(args.args.contains_key("-plot-ticks")))
+}
+
+pub fn get_histogram_modifier(args: &Args) -> Result<HistogramConverter, String> {
+    let channel = try_parse_histogram_channel(args)?;
+    Ok(HistogramConverter::new(channel).with_plot(try_parse_histogram_plot(args, channel)?))
+}
+
+/// Parses `-gmin` and `-gmax` of the final probability density functions
+fn try_parse_brightness_range(args: &Args) -> Result<(u8, u8), String> {
     let gmin: u8 = args.try_get_num_arg("-gmin")?;
     let gmax: u8 = args.try_get_num_arg("-gmax")?;
-    Ok(RayleighModification::new(gmin, gmax))
+    if gmax <= gmin {
+        return Err("gmax must be greater than gmin".to_string());
+    }
+    Ok((gmin, gmax))
+}
+
+pub fn try_new_raleigh(args: &Args) -> Result<RayleighModification, String> {
+    let (gmin, gmax) = try_parse_brightness_range(args)?;
+    Ok(RayleighModification::new(gmin, gmax).with_channels(parse_histogram_channels(args)))
+}
+
+pub fn try_new_uniform(args: &Args) -> Result<UniformModification, String> {
+    let (gmin, gmax) = try_parse_brightness_range(args)?;
+    Ok(UniformModification::new(gmin, gmax).with_channels(parse_histogram_channels(args)))
+}
+
+pub fn try_new_exponential(args: &Args) -> Result<ExponentialModification, String> {
+    let (gmin, gmax) = try_parse_brightness_range(args)?;
+    Ok(ExponentialModification::new(gmin, gmax).with_channels(parse_histogram_channels(args)))
+}
+
+pub fn try_new_hyperbolic(args: &Args) -> Result<HyperbolicModification, String> {
+    let (gmin, gmax) = try_parse_brightness_range(args)?;
+    if gmin == 0 {
+        return Err("gmin must be positive for the hyperbolic modification".to_string());
+    }
+    Ok(HyperbolicModification::new(gmin, gmax).with_channels(parse_histogram_channels(args)))
+}
+
+/// Matching to `-reference=<FILE>` image, or to `-histogram=<FILE>` with 256 counts separated by
+/// whitespace, commas or semicolons
+pub fn try_new_histogram_matching(args: &Args) -> Result<HistogramMatching, String> {
+    if let Some(path) = args.args.get("-reference") {
+        let reference = image::open(path).map_err(|e| e.to_string())?.to_rgb8();
+        return Ok(HistogramMatching::from_image(&reference));
+    }
+    let path = args
+        .try_get_arg("histogram")
+        .map_err(|_| "Missing -reference or -histogram argument".to_string())?;
+    let histogram: [f64; 256] = try_read_numbers(&path)?
+        .try_into()
+        .map_err(|values: Vec<f64>| format!("Expected 256 values, got {}", values.len()))?;
+    if histogram.iter().any(|&x| x < 0.0) || histogram.iter().sum::<f64>() <= 0.0 {
+        return Err("Histogram values must be non-negative and not all zero".to_string());
+    }
+    Ok(HistogramMatching::from_histogram(&histogram))
+}
+
+/// Reads numbers separated by whitespace, commas or semicolons from a text file
+fn try_read_numbers(path: &str) -> Result<Vec<f64>, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    text.split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|x| !x.is_empty())
+        .map(|x| x.parse())
+        .collect::<Result<_, ParseFloatError>>()
+        .map_err(|e| e.to_string())
+}
+
+fn parse_histogram_channels(args: &Args) -> HistogramChannels {
+    match args.args.contains_key("-luminance") {
+        true => HistogramChannels::Luminance,
+        false => HistogramChannels::PerChannel,
+    }
+}
+
+pub fn new_equalization(args: &Args) -> HistogramEqualization {
+    HistogramEqualization::new().with_channels(parse_histogram_channels(args))
+}
+
+pub fn try_new_clahe(args: &Args) -> Result<Clahe, String> {
+    let tiles: u32 = match args.args.get("-tiles") {
+        Some(_) => args.try_get_num_arg("tiles")?,
+        None => 8,
+    };
+    let clip_limit: f64 = match args.args.get("-clip") {
+        Some(_) => args.try_get_num_arg("clip")?,
+        None => 2.0,
+    };
+    if tiles == 0 {
+        return Err("Number of tiles must be positive".to_string());
+    }
+    if clip_limit < 1.0 {
+        return Err("Clip limit must be at least 1".to_string());
+    }
+    Ok(Clahe::new(tiles, tiles, clip_limit).with_channels(parse_histogram_channels(args)))
 }
 
 fn try_parse_mask(args: &Args) -> Result<[f64; 9], String> {
@@ -169,6 +627,58 @@ pub fn try_new_linear(args: &Args) -> Result<LinearFilter, String> {
     }
 }
 
+pub fn try_new_kernel_filter(args: &Args) -> Result<KernelFilter, String> {
+    let mask_string = args.try_get_arg("mask")?;
+    let mask: Result<Vec<f64>, ParseFloatError> =
+        mask_string.split(";").map(|s| s.parse()).collect();
+    let mask = mask.map_err(|e| e.to_string())?;
+    let size = (mask.len() as f64).sqrt().round() as usize;
+    if size * size != mask.len() || size.is_even() {
+        return Err(format!(
+            "Expected mask of odd square size, got {} values",
+            mask.len()
+        ));
+    }
+    let kernel = mask.chunks(size).map(|row| row.to_vec()).collect();
+
+    let mask_scale = match try_parse_mask_scale(args) {
+        Some(scale) => Some(scale?),
+        None => None,
+    };
+    let method = match args.args.get("-method").map(String::as_str) {
+        None | Some("auto") => ConvolutionMethod::Auto,
+        Some("spatial") => ConvolutionMethod::Spatial,
+        Some("fft") => ConvolutionMethod::Fourier,
+        Some(method) => return Err(format!("Unknown convolution method {}", method)),
+    };
+    Ok(KernelFilter::new(kernel, mask_scale).with_method(method))
+}
+
+pub fn try_new_template_matching(
+    args: &Args,
+    (width, height): (u32, u32),
+) -> Result<TemplateMatching, String> {
+    let path = args.try_get_arg("template")?;
+    let template = match image::open(&path) {
+        Ok(image) => image.to_rgb8(),
+        Err(e) => return Err(e.to_string()),
+    };
+    if template.width() > width || template.height() > height {
+        return Err(format!(
+            "Template of {}x{} does not fit into the image of {}x{}",
+            template.width(),
+            template.height(),
+            width,
+            height
+        ));
+    }
+    let output = match args.args.contains_key("-mark") {
+        true => MatchOutput::BestMatch,
+        false => MatchOutput::CorrelationMap,
+    };
+    Ok(TemplateMatching::new(template).with_output(output))
+}
+
 pub fn try_new_linear_gpu(args: &Args) -> Result<LinearFilterGPU, String> {
     let mask = try_parse_mask(args)?;
     match try_parse_mask_scale(args) {
@@ -193,3 +703,759 @@ pub fn try_new_region_grow(args: &Args) -> Result<RegionGrowing, String> {
     let tolerance: u8 = args.try_get_num_arg("-tolerance")?;
     Ok(RegionGrowing::new(seed_x, seed_y, tolerance))
 }
+
+/// Parses a threshold given as a level or as `otsu`, `kapur` or `triangle`
+pub fn try_parse_global_threshold(args: &Args, arg_name: &str) -> Result<GlobalThreshold, String> {
+    let value = args.try_get_arg(arg_name)?;
+    match value.as_str() {
+        "otsu" => Ok(GlobalThreshold::Otsu),
+        "kapur" => Ok(GlobalThreshold::Kapur),
+        "triangle" => Ok(GlobalThreshold::Triangle),
+        level => match level.parse::<u8>() {
+            Ok(level) => Ok(GlobalThreshold::Fixed(level)),
+            Err(_) => Err(format!(
+                "Invalid threshold {}, expected 0-255, otsu, kapur or triangle",
+                level
+            )),
+        },
+    }
+}
+
+/// Applies `-threshold` to the morphological operation, if given
+pub fn with_optional_threshold<T>(
+    args: &Args,
+    transform: T,
+) -> Result<Box<dyn Transformation>, String>
+where
+    T: MorphologicalTransform + 'static,
+{
+    match args.args.contains_key("-threshold") {
+        true => Ok(Box::new(
+            transform.with_threshold(try_parse_global_threshold(args, "threshold")?),
+        )),
+        false => Ok(Box::new(transform)),
+    }
+}
+
+pub fn try_new_multi_otsu(args: &Args) -> Result<MultiOtsu, String> {
+    let classes: usize = args.try_get_num_arg("classes")?;
+    if !(2..=256).contains(&classes) {
+        return Err("Number of classes must be between 2 and 256".to_string());
+    }
+    Ok(MultiOtsu::new(classes))
+}
+
+/// Parses a finite signed number, as `try_get_num_arg` only accepts non-negative ones
+fn try_get_required_signed_arg(args: &Args, arg_name: &str) -> Result<f64, String> {
+    let value = args.try_get_arg(arg_name)?;
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(format!("Value {} is not a number", value)),
+    }
+}
+
+/// Parses an optional signed number, `default` when it is missing
+fn try_get_signed_arg(args: &Args, arg_name: &str, default: f64) -> Result<f64, String> {
+    match args.try_get_arg(arg_name) {
+        Ok(_) => try_get_required_signed_arg(args, arg_name),
+        Err(_) => Ok(default),
+    }
+}
+
+pub fn try_new_adaptive_threshold(args: &Args) -> Result<AdaptiveThreshold, String> {
+    let window: u32 = match args.args.contains_key("-window") {
+        true => args.try_get_num_arg("window")?,
+        false => 15,
+    };
+    if window.is_even() {
+        return Err("Window size must be odd".to_string());
+    }
+    let method = match args.try_get_arg("method")?.as_str() {
+        "mean" => AdaptiveMethod::Mean {
+            offset: try_get_signed_arg(args, "offset", 5.0)?,
+        },
+        "gaussian" => AdaptiveMethod::Gaussian {
+            offset: try_get_signed_arg(args, "offset", 5.0)?,
+        },
+        "niblack" => AdaptiveMethod::Niblack {
+            k: try_get_signed_arg(args, "k", -0.2)?,
+        },
+        "sauvola" => AdaptiveMethod::Sauvola {
+            k: try_get_signed_arg(args, "k", 0.2)?,
+            r: try_get_signed_arg(args, "r", 128.0)?,
+        },
+        other => {
+            return Err(format!(
+                "Invalid method {}, expected mean, gaussian, niblack or sauvola",
+                other
+            ))
+        }
+    };
+    Ok(AdaptiveThreshold::new(method, window))
+}
+
+pub fn try_new_gamma(args: &Args) -> Result<Gamma, String> {
+    let gamma: f64 = args.try_get_num_arg("gamma")?;
+    if gamma <= 0.0 {
+        return Err("Gamma must be positive".to_string());
+    }
+    Ok(Gamma::new(gamma))
+}
+
+/// Strength of the log and exp transformations, 255 by default
+pub fn try_get_strength(args: &Args) -> Result<f64, String> {
+    let strength: f64 = match args.args.contains_key("-strength") {
+        true => args.try_get_num_arg("strength")?,
+        false => 255.0,
+    };
+    if strength <= 0.0 {
+        return Err("Strength must be positive".to_string());
+    }
+    Ok(strength)
+}
+
+/// Parses `-points` given as `in,out;in,out;...`
+pub fn try_new_tone_curve(args: &Args) -> Result<ToneCurve, String> {
+    let points = args
+        .try_get_arg("points")?
+        .split(';')
+        .filter(|point| !point.trim().is_empty())
+        .map(|point| {
+            let (input, output) = point
+                .split_once(',')
+                .ok_or(format!("Point {} is not in the in,out format", point))?;
+            let parse = |value: &str| {
+                value
+                    .trim()
+                    .parse::<u8>()
+                    .map_err(|_| format!("Value {} is not between 0 and 255", value))
+            };
+            Ok((parse(input)?, parse(output)?))
+        })
+        .collect::<Result<Vec<(u8, u8)>, String>>()?;
+    if points.len() < 2 {
+        return Err("Tone curve needs at least two points".to_string());
+    }
+    let mut inputs: Vec<u8> = points.iter().map(|&(input, _)| input).collect();
+    inputs.sort();
+    if inputs.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err("Control points must have different inputs".to_string());
+    }
+    let interpolation = match args.args.contains_key("-spline") {
+        true => CurveInterpolation::Spline,
+        false => CurveInterpolation::Linear,
+    };
+    Ok(ToneCurve::new(&points).with_interpolation(interpolation))
+}
+
+pub fn try_new_levels(args: &Args) -> Result<Levels, String> {
+    let black: u8 = match args.args.contains_key("-black") {
+        true => args.try_get_num_arg("black")?,
+        false => 0,
+    };
+    let white: u8 = match args.args.contains_key("-white") {
+        true => args.try_get_num_arg("white")?,
+        false => 255,
+    };
+    let gamma: f64 = match args.args.contains_key("-gamma") {
+        true => args.try_get_num_arg("gamma")?,
+        false => 1.0,
+    };
+    if white <= black {
+        return Err("White point must be above the black point".to_string());
+    }
+    if gamma <= 0.0 {
+        return Err("Gamma must be positive".to_string());
+    }
+    Ok(Levels::new(black, white).with_gamma(gamma))
+}
+
+pub fn try_new_auto_levels(args: &Args) -> Result<AutoLevels, String> {
+    let clip: f64 = match args.args.contains_key("-clip") {
+        true => args.try_get_num_arg("clip")?,
+        false => 0.5,
+    };
+    if clip >= 50.0 {
+        return Err("Clipped percentage must be below 50".to_string());
+    }
+    Ok(AutoLevels::new(clip).with_channels(parse_histogram_channels(args)))
+}
+
+/// Reads 256 values used for all channels, or 768 values with the red, green and blue tables in turn
+pub fn try_new_lookup_table(args: &Args) -> Result<LookupTable, String> {
+    let values = try_read_numbers(&args.try_get_arg("file")?)?;
+    let values: Vec<u8> = values
+        .iter()
+        .map(|&value| {
+            let valid = value.fract() == 0.0 && (0.0..=255.0).contains(&value);
+            match valid {
+                true => Ok(value as u8),
+                false => Err(format!("Value {} is not between 0 and 255", value)),
+            }
+        })
+        .collect::<Result<_, String>>()?;
+    let table = |channel: usize| -> [u8; 256] {
+        std::array::from_fn(|value| values[channel * 256 + value])
+    };
+    match values.len() {
+        256 => Ok(LookupTable::new(table(0))),
+        768 => Ok(LookupTable::per_channel([table(0), table(1), table(2)])),
+        count => Err(format!("Expected 256 or 768 values, got {}", count)),
+    }
+}
+
+pub fn try_new_white_balance(args: &Args) -> Result<WhiteBalance, String> {
+    let method = match args.args.get("-method").map(|x| x.as_str()) {
+        None | Some("gray-world") => WhiteBalanceMethod::GrayWorld,
+        Some("white-patch") => {
+            let percentile: f64 = match args.args.contains_key("-percentile") {
+                true => args.try_get_num_arg("percentile")?,
+                false => 99.0,
+            };
+            if percentile > 100.0 {
+                return Err("Percentile must be between 0 and 100".to_string());
+            }
+            WhiteBalanceMethod::WhitePatch { percentile }
+        }
+        Some(method) => return Err(format!("Unknown white balance method {}", method)),
+    };
+    Ok(WhiteBalance::new(method))
+}
+
+pub fn try_new_color_temperature(args: &Args) -> Result<ColorTemperature, String> {
+    let kelvin: f64 = args.try_get_num_arg("kelvin")?;
+    if !(1000.0..=40000.0).contains(&kelvin) {
+        return Err("Temperature must be between 1000 K and 40000 K".to_string());
+    }
+    Ok(ColorTemperature::new(kelvin))
+}
+
+pub fn try_new_vibrance(args: &Args) -> Result<Vibrance, String> {
+    let amount = try_get_signed_arg(args, "amount", 0.5)?;
+    if amount < -1.0 {
+        return Err("Vibrance must be at least -1".to_string());
+    }
+    Ok(Vibrance::new(amount))
+}
+
+pub fn try_new_hue_rotation(args: &Args) -> Result<HueRotation, String> {
+    let degrees = try_get_required_signed_arg(args, "degrees")?;
+    Ok(HueRotation::new(degrees))
+}
+
+/// Parses a matrix given as rows separated by semicolons of finite values separated by commas
+fn try_parse_matrix<const ROWS: usize, const COLUMNS: usize>(
+    text: &str,
+) -> Result<[[f64; COLUMNS]; ROWS], String> {
+    let rows = text
+        .split(';')
+        .map(|row| {
+            let values = row
+                .split(',')
+                .map(|x| x.trim().parse())
+                .collect::<Result<Vec<f64>, ParseFloatError>>()
+                .map_err(|e| e.to_string())?;
+            if values.iter().any(|value| !value.is_finite()) {
+                return Err(format!("Row {} has values that are not finite", row));
+            }
+            <[f64; COLUMNS]>::try_from(values)
+                .map_err(|_| format!("Row {} does not have {} values", row, COLUMNS))
+        })
+        .collect::<Result<Vec<[f64; COLUMNS]>, String>>()?;
+    rows.try_into()
+        .map_err(|rows: Vec<_>| format!("Expected {} rows, got {}", ROWS, rows.len()))
+}
+
+/// Parses `-matrix` given as three rows of three weights, `rr,rg,rb;gr,gg,gb;br,bg,bb`
+pub fn try_new_channel_mixer(args: &Args) -> Result<ChannelMixer, String> {
+    Ok(ChannelMixer::new(try_parse_matrix(
+        &args.try_get_arg("matrix")?,
+    )?))
+}
+
+/// Parses `-weights` as 601 (default), 709, average or custom `r,g,b` weights
+pub fn try_parse_luminance_weights(args: &Args) -> Result<LuminanceWeights, String> {
+    match args.args.get("-weights").map(|x| x.as_str()) {
+        None | Some("601") => Ok(LuminanceWeights::Rec601),
+        Some("709") => Ok(LuminanceWeights::Rec709),
+        Some("average") => Ok(LuminanceWeights::Average),
+        Some(weights) => {
+            let weights = weights
+                .split(',')
+                .map(|x| x.trim().parse())
+                .collect::<Result<Vec<f64>, ParseFloatError>>()
+                .map_err(|_| format!("Unknown luminance weights {}", weights))?;
+            let weights: [f64; 3] = weights
+                .try_into()
+                .map_err(|_| "Expected three weights".to_string())?;
+            Ok(LuminanceWeights::Custom(weights))
+        }
+    }
+}
+
+/// Parses `-background=r,g,b`
+fn try_parse_background(args: &Args) -> Result<Option<Rgb<u8>>, String> {
+    let Some(color) = args.args.get("-background") else {
+        return Ok(None);
+    };
+    let channels = color
+        .split(',')
+        .map(|x| x.trim().parse())
+        .collect::<Result<Vec<u8>, ParseIntError>>()
+        .map_err(|_| format!("Background {} is not r,g,b with values 0-255", color))?;
+    let channels: [u8; 3] = channels
+        .try_into()
+        .map_err(|_| format!("Background {} is not r,g,b with values 0-255", color))?;
+    Ok(Some(Rgb(channels)))
+}
+
+/// Applies `-interpolation`, `-background=r,g,b` and `-canvas=crop|expand` to a warp
+fn try_configure_warp(args: &Args, warp: AffineTransform) -> Result<AffineTransform, String> {
+    let warp = match args.args.contains_key("-interpolation") {
+        true => warp.with_interpolation(try_parse_interpolation(args)?),
+        false => warp,
+    };
+    let warp = match try_parse_background(args)? {
+        Some(background) => warp.with_background(background),
+        None => warp,
+    };
+    match args.args.get("-canvas").map(|x| x.as_str()) {
+        None | Some("crop") => Ok(warp.with_canvas(Canvas::Crop)),
+        Some("expand") => Ok(warp.with_canvas(Canvas::Expand)),
+        Some(canvas) => Err(format!("Unknown canvas {}", canvas)),
+    }
+}
+
+/// Rotations by multiples of 90 degrees are lossless, unless they would be cropped
+pub fn try_new_rotation(args: &Args) -> Result<Box<dyn Transformation>, String> {
+    let angle = try_get_required_signed_arg(args, "angle")?;
+    let expand = args
+        .args
+        .get("-canvas")
+        .is_some_and(|canvas| canvas == "expand");
+    match angle.rem_euclid(360.0) {
+        0.0 => Ok(Box::new(IdTransform)),
+        90.0 if expand => Ok(Box::new(Rotate90)),
+        180.0 => Ok(Box::new(Rotate180)),
+        270.0 if expand => Ok(Box::new(Rotate270)),
+        _ => Ok(Box::new(try_configure_warp(
+            args,
+            AffineTransform::rotation(angle),
+        )?)),
+    }
+}
+
+pub fn try_new_shear(args: &Args) -> Result<AffineTransform, String> {
+    let (x, y) = (
+        try_get_signed_arg(args, "x", 0.0)?,
+        try_get_signed_arg(args, "y", 0.0)?,
+    );
+    if (1.0 - x * y).abs() < 1e-12 {
+        return Err("Shear factors must not flatten the image".to_string());
+    }
+    try_configure_warp(args, AffineTransform::shear(x, y))
+}
+
+pub fn try_new_translation(args: &Args) -> Result<AffineTransform, String> {
+    let (x, y) = (
+        try_get_signed_arg(args, "x", 0.0)?,
+        try_get_signed_arg(args, "y", 0.0)?,
+    );
+    try_configure_warp(args, AffineTransform::translation(x, y))
+}
+
+/// Parses `-matrix` given as two rows, `a,b,c;d,e,f`
+pub fn try_new_affine(args: &Args) -> Result<AffineTransform, String> {
+    let matrix: [[f64; 3]; 2] = try_parse_matrix(&args.try_get_arg("matrix")?)?;
+    if (matrix[0][0] * matrix[1][1] - matrix[0][1] * matrix[1][0]).abs() < 1e-12 {
+        return Err("Affine matrix must be invertible".to_string());
+    }
+    try_configure_warp(args, AffineTransform::new(matrix))
+}
+
+/// Parses four points given as `x,y;x,y;x,y;x,y`
+fn try_parse_points(args: &Args, arg_name: &str) -> Result<[(f64, f64); 4], String> {
+    let points: [[f64; 2]; 4] = try_parse_matrix(&args.try_get_arg(arg_name)?)?;
+    Ok(points.map(|[x, y]| (x, y)))
+}
+
+/// No three of the points may lie on a line
+fn check_quadrilateral(points: &[(f64, f64); 4]) -> Result<(), String> {
+    for (a, b, c) in [(0, 1, 2), (0, 1, 3), (0, 2, 3), (1, 2, 3)] {
+        let ((x1, y1), (x2, y2), (x3, y3)) = (points[a], points[b], points[c]);
+        if ((x2 - x1) * (y3 - y1) - (y2 - y1) * (x3 - x1)).abs() < 1e-9 {
+            return Err("No three of the points may lie on a line".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Parses `-width` with `-height` if any of them is given
+fn try_get_target_size(args: &Args) -> Result<Option<(u32, u32)>, String> {
+    if !args.args.contains_key("-width") && !args.args.contains_key("-height") {
+        return Ok(None);
+    }
+    let width: u32 = args.try_get_num_arg("width")?;
+    let height: u32 = args.try_get_num_arg("height")?;
+    match width > 0 && height > 0 {
+        true => Ok(Some((width, height))),
+        false => Err("Target size must not be zero".to_string()),
+    }
+}
+
+/// Applies `-interpolation` and `-background=r,g,b` to a perspective warp
+fn try_configure_perspective(
+    args: &Args,
+    warp: PerspectiveTransform,
+) -> Result<PerspectiveTransform, String> {
+    let warp = match args.args.contains_key("-interpolation") {
+        true => warp.with_interpolation(try_parse_interpolation(args)?),
+        false => warp,
+    };
+    match try_parse_background(args)? {
+        Some(background) => Ok(warp.with_background(background)),
+        None => Ok(warp),
+    }
+}
+
+/// Takes a `-matrix` with three rows, or four `-from` points mapped to four `-to` points
+pub fn try_new_perspective(args: &Args) -> Result<PerspectiveTransform, String> {
+    let warp = match args.args.contains_key("-matrix") {
+        true => {
+            let matrix: [[f64; 3]; 3] = try_parse_matrix(&args.try_get_arg("matrix")?)?;
+            let [[a, b, c], [d, e, f], [g, h, i]] = matrix;
+            let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+            if det.abs() < 1e-12 {
+                return Err("Homography must be invertible".to_string());
+            }
+            PerspectiveTransform::new(matrix)
+        }
+        false => {
+            let from = try_parse_points(args, "from")?;
+            let to = try_parse_points(args, "to")?;
+            check_quadrilateral(&from)?;
+            check_quadrilateral(&to)?;
+            PerspectiveTransform::from_points(from, to)
+        }
+    };
+    let warp = match try_get_target_size(args)? {
+        Some((width, height)) => warp.with_size(width, height),
+        None => warp,
+    };
+    try_configure_perspective(args, warp)
+}
+
+/// Takes the `-corners` of a document, top-left, top-right, bottom-right and bottom-left
+pub fn try_new_rectification(args: &Args) -> Result<PerspectiveTransform, String> {
+    let corners = try_parse_points(args, "corners")?;
+    check_quadrilateral(&corners)?;
+    let warp = match try_get_target_size(args)? {
+        Some((width, height)) => PerspectiveTransform::rectify_to_size(corners, width, height),
+        None => PerspectiveTransform::rectify(corners),
+    };
+    try_configure_perspective(args, warp)
+}
+
+fn try_get_required_size(args: &Args) -> Result<(u32, u32), String> {
+    try_get_target_size(args)?.ok_or_else(|| "Missing width and height arguments".to_string())
+}
+
+pub fn try_new_crop(args: &Args, input_size: (u32, u32)) -> Result<Crop, String> {
+    let x: u32 = args.try_get_num_arg("x")?;
+    let y: u32 = args.try_get_num_arg("y")?;
+    let (width, height) = try_get_required_size(args)?;
+    let crop = Crop::new(x, y, width, height);
+    match crop.region(input_size.0, input_size.1) {
+        Some(_) => Ok(crop),
+        None => Err(format!(
+            "Crop rectangle does not intersect the {}x{} image",
+            input_size.0, input_size.1
+        )),
+    }
+}
+
+pub fn try_new_auto_crop(args: &Args) -> Result<AutoCrop, String> {
+    match args.args.contains_key("-tolerance") {
+        true => Ok(AutoCrop::new(args.try_get_num_arg("tolerance")?)),
+        false => Ok(AutoCrop::new(0)),
+    }
+}
+
+/// Largest padding of a side in pixels
+const MAX_PADDING: u32 = 4096;
+
+/// Padding of `-size` on all sides, or of `-left`, `-top`, `-right` and `-bottom`,
+/// or up to powers of two with `-pow2`
+pub fn try_new_pad(args: &Args) -> Result<Pad, String> {
+    let get_padding = |name: &str| -> Result<u32, String> {
+        let padding: u32 = args.try_get_num_arg(name)?;
+        match padding <= MAX_PADDING {
+            true => Ok(padding),
+            false => Err(format!("{} must be at most {}", name, MAX_PADDING)),
+        }
+    };
+    let pad = if args.args.contains_key("-pow2") {
+        Pad::to_power_of_two()
+    } else if args.args.contains_key("-size") {
+        let size = get_padding("size")?;
+        Pad::new(size, size, size, size)
+    } else {
+        let get_side = |name: &str| match args.args.contains_key(&format!("-{}", name)) {
+            true => get_padding(name),
+            false => Ok(0),
+        };
+        Pad::new(
+            get_side("left")?,
+            get_side("top")?,
+            get_side("right")?,
+            get_side("bottom")?,
+        )
+    };
+    let mode = match args.args.get("-mode").map(|x| x.as_str()) {
+        None | Some("constant") => {
+            PaddingMode::Constant(try_parse_background(args)?.unwrap_or(Rgb([0, 0, 0])))
+        }
+        Some("replicate") => PaddingMode::Replicate,
+        Some("reflect") => PaddingMode::Reflect,
+        Some(mode) => return Err(format!("Unknown padding mode {}", mode)),
+    };
+    Ok(pad.with_mode(mode))
+}
+
+pub fn try_new_tile_split(args: &Args) -> Result<TileSplit, String> {
+    let (width, height) = try_get_required_size(args)?;
+    let prefix = args.try_get_arg("prefix")?;
+    Ok(TileSplit::new(width, height, &prefix))
+}
+
+/// Replaces tiles of the image with the files saved by `--tiles` with the same size and prefix
+pub fn try_new_tile_assemble(args: &Args) -> Result<TileAssemble, String> {
+    let (width, height) = try_get_required_size(args)?;
+    let prefix = args.try_get_arg("prefix")?;
+    Ok(TileAssemble::new(width, height, &prefix))
+}
+
+/// Centre given as `-cx` with `-cy`, none when neither is given
+fn try_get_center(args: &Args) -> Result<Option<(f64, f64)>, String> {
+    if !args.args.contains_key("-cx") && !args.args.contains_key("-cy") {
+        return Ok(None);
+    }
+    Ok(Some((
+        try_get_required_signed_arg(args, "cx")?,
+        try_get_required_signed_arg(args, "cy")?,
+    )))
+}
+
+/// Brown–Conrady coefficients `-k1`, `-k2`, `-p1` and `-p2`, all zero by default
+pub fn try_new_lens_correction(args: &Args) -> Result<LensCorrection, String> {
+    let model = BrownConrady::new(
+        try_get_signed_arg(args, "k1", 0.0)?,
+        try_get_signed_arg(args, "k2", 0.0)?,
+        try_get_signed_arg(args, "p1", 0.0)?,
+        try_get_signed_arg(args, "p2", 0.0)?,
+    );
+    let mut correction = LensCorrection::new(model);
+    if args.args.contains_key("-interpolation") {
+        correction = correction.with_interpolation(try_parse_interpolation(args)?);
+    }
+    if args.args.contains_key("-inverse") {
+        correction = correction.inverse();
+    }
+    if let Some((x, y)) = try_get_center(args)? {
+        correction = correction.with_center(x, y);
+    }
+    if args.args.contains_key("-focal") {
+        match args.try_get_num_arg::<f64>("focal")? {
+            focal if focal > 0.0 => correction = correction.with_focal_length(focal),
+            _ => return Err("Focal length must be positive".to_string()),
+        }
+    }
+    match try_parse_background(args)? {
+        Some(background) => Ok(correction.with_background(background)),
+        None => Ok(correction),
+    }
+}
+
+pub fn try_new_polar(args: &Args) -> Result<PolarTransform, String> {
+    let scale = match args.args.contains_key("-log") {
+        true => RadialScale::Logarithmic,
+        false => RadialScale::Linear,
+    };
+    let mut polar = PolarTransform::new(scale);
+    if args.args.contains_key("-interpolation") {
+        polar = polar.with_interpolation(try_parse_interpolation(args)?);
+    }
+    if args.args.contains_key("-inverse") {
+        polar = polar.inverse();
+    }
+    if let Some((x, y)) = try_get_center(args)? {
+        polar = polar.with_center(x, y);
+    }
+    if args.args.contains_key("-radius") {
+        match args.try_get_num_arg::<f64>("radius")? {
+            radius if radius > 0.0 => polar = polar.with_max_radius(radius),
+            _ => return Err("Radius must be positive".to_string()),
+        }
+    }
+    if args.args.contains_key("-min-radius") {
+        match args.try_get_num_arg::<f64>("min-radius")? {
+            radius if radius > 0.0 => polar = polar.with_min_radius(radius),
+            _ => return Err("Minimum radius must be positive".to_string()),
+        }
+    }
+    if let Some((width, height)) = try_get_target_size(args)? {
+        polar = polar.with_size(width, height);
+    }
+    match try_parse_background(args)? {
+        Some(background) => Ok(polar.with_background(background)),
+        None => Ok(polar),
+    }
+}
+
+/// Largest radius of a grayscale structuring element, every pixel of which is visited
+/// for every pixel of the image
+const MAX_GRAY_ELEMENT_RADIUS: u32 = 32;
+
+/// Flat element of the binary structuring element, a `-ball=<RADIUS>`,
+/// or a flat square of side `-size` (default 3)
+fn try_parse_gray_element(args: &Args) -> Result<GrayStructuringElement, String> {
+    let max_size = 2 * MAX_GRAY_ELEMENT_RADIUS + 1;
+    if args.args.contains_key("-kernel") || args.args.contains_key("-element") {
+        let element = try_parse_structuring_element(args)?;
+        if element.width().max(element.height()) > max_size {
+            return Err(format!(
+                "Structuring element must be at most {}x{}",
+                max_size, max_size
+            ));
+        }
+        if element.is_empty() {
+            return Err("Structuring element must not be empty".to_string());
+        }
+        return Ok(GrayStructuringElement::flat(&element));
+    }
+    if args.args.contains_key("-ball") {
+        let radius: u32 = args.try_get_num_arg("ball")?;
+        if radius > MAX_GRAY_ELEMENT_RADIUS {
+            return Err(format!(
+                "Ball radius must be at most {}",
+                MAX_GRAY_ELEMENT_RADIUS
+            ));
+        }
+        return Ok(GrayStructuringElement::ball(radius));
+    }
+    let size: u32 = match args.args.contains_key("-size") {
+        true => args.try_get_num_arg("size")?,
+        false => 3,
+    };
+    match size {
+        0 => Err("Structuring element size must not be zero".to_string()),
+        size if size > max_size => Err(format!(
+            "Structuring element size must be at most {}",
+            max_size
+        )),
+        size => Ok(GrayStructuringElement::rectangle(size, size)),
+    }
+}
+
+pub fn try_new_gray_morphology(args: &Args) -> Result<GrayMorphology, String> {
+    let operation = match args.try_get_arg("op")?.as_str() {
+        "dilation" => GrayOperation::Dilation,
+        "erosion" => GrayOperation::Erosion,
+        "opening" => GrayOperation::Opening,
+        "closing" => GrayOperation::Closing,
+        "gradient" => GrayOperation::Gradient,
+        "tophat" => GrayOperation::TopHat,
+        "blackhat" => GrayOperation::BlackHat,
+        operation => return Err(format!("Unknown morphological operation {}", operation)),
+    };
+    let morphology = GrayMorphology::new(operation, try_parse_gray_element(args)?);
+    match args.args.contains_key("-rgb") {
+        true => Ok(morphology.per_channel()),
+        false => Ok(morphology),
+    }
+}
+
+/// Parses `-element=<SPEC>`, with SPEC one of `rect:WxH`, `disk:R`, `diamond:R`, `cross:WxH`,
+/// `line:LENGTH,ANGLE` or the path of a binary image, or the 3x3 `-kernel`,
+/// with the origin moved by `-origin=x,y`
+pub fn try_parse_structuring_element(args: &Args) -> Result<StructuringElement, String> {
+    let element = match args.args.get("-element") {
+        Some(spec) => try_parse_element_spec(spec)?,
+        None => {
+            let bits: Vec<u8> = try_parse_kernel(args)?
+                .into_iter()
+                .map(|x| u8::from(x > 0))
+                .collect();
+            StructuringElement::from(&Mask::from_raw_bits(&bits))
+        }
+    };
+    let Some(origin) = args.args.get("-origin") else {
+        return Ok(element);
+    };
+    let (x, y) = origin
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .ok_or_else(|| format!("Origin {} is not x,y", origin))?;
+    match x < element.width() && y < element.height() {
+        true => Ok(element.with_origin(x, y)),
+        false => Err("Origin must lie inside the structuring element".to_string()),
+    }
+}
+
+/// Largest side of a structuring element given by its shape
+const MAX_ELEMENT_SIZE: u32 = 1025;
+
+fn try_parse_element_spec(spec: &str) -> Result<StructuringElement, String> {
+    let parse = |value: &str, max: u32| -> Result<u32, String> {
+        match value.trim().parse() {
+            Ok(0) => Err("Structuring element size must not be zero".to_string()),
+            Ok(value) if value > max => Err(format!("Value {} must be at most {}", value, max)),
+            Ok(value) => Ok(value),
+            Err(_) => Err(format!("Value {} is not a positive number", value)),
+        }
+    };
+    let parse_size = |size: &str| -> Result<(u32, u32), String> {
+        let (width, height) = size
+            .split_once('x')
+            .ok_or_else(|| format!("Size {} is not WxH", size))?;
+        Ok((
+            parse(width, MAX_ELEMENT_SIZE)?,
+            parse(height, MAX_ELEMENT_SIZE)?,
+        ))
+    };
+    let parse_radius = |radius: &str| parse(radius, MAX_ELEMENT_SIZE / 2);
+    match spec.split_once(':') {
+        Some(("rect", size)) => {
+            let (width, height) = parse_size(size)?;
+            Ok(StructuringElement::rectangle(width, height))
+        }
+        Some(("cross", size)) => {
+            let (width, height) = parse_size(size)?;
+            Ok(StructuringElement::cross(width, height))
+        }
+        Some(("disk", radius)) => Ok(StructuringElement::disk(parse_radius(radius)?)),
+        Some(("diamond", radius)) => Ok(StructuringElement::diamond(parse_radius(radius)?)),
+        Some(("line", line)) => {
+            let (length, angle) = line
+                .split_once(',')
+                .ok_or_else(|| format!("Line {} is not LENGTH,ANGLE", line))?;
+            let angle: f64 = match angle.trim().parse() {
+                Ok(angle) if f64::is_finite(angle) => angle,
+                _ => return Err(format!("Angle {} is not a finite number", angle)),
+            };
+            Ok(StructuringElement::line(
+                parse(length, MAX_ELEMENT_SIZE)?,
+                angle,
+            ))
+        }
+        _ => {
+            let image = try_open_grayscale_image(spec)
+                .map_err(|error| format!("Unknown structuring element {}: {}", spec, error))?;
+            let element = StructuringElement::from_image(&image);
+            match element.is_empty() {
+                false => Ok(element),
+                true => Err(format!("Structuring element {} is empty", spec)),
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use image::{ImageBuffer, RgbImage};
-use image_proc::histogram::Histogram;
+use image::{ImageBuffer, Rgb, RgbImage};
+use image_proc::histogram::{ChannelHistogram, Histogram};
 use image_proc::modifications::Transformation;
+use std::fs::File;
+use std::io::{BufWriter, Write};
 
 #[derive(Copy, Clone)]
 pub enum HistogramChannelOptions {
@@ -8,62 +10,392 @@ pub enum HistogramChannelOptions {
     G = 1,
     B = 2,
     All,
+    Luminance,
 }
 
-pub struct HistogramConverter {
+impl HistogramChannelOptions {
+    /// Named histograms of the selected channels, with their colour in the plot
+    fn histograms(
+        &self,
+        image: &RgbImage,
+        histogram: &Histogram,
+    ) -> Vec<(&'static str, Rgb<u8>, ChannelHistogram)> {
+        const NAMES: [&str; 3] = ["red", "green", "blue"];
+        match self {
+            HistogramChannelOptions::All => (0..3)
+                .map(|channel| {
+                    let mut color = [0u8; 3];
+                    color[channel] = 255;
+                    (NAMES[channel], Rgb(color), histogram.channel(channel))
+                })
+                .collect(),
+            HistogramChannelOptions::Luminance => {
+                vec![("luminance", WHITE, Histogram::luminance(image))]
+            }
+            channel => {
+                let channel = *channel as usize;
+                vec![(NAMES[channel], WHITE, histogram.channel(channel))]
+            }
+        }
+    }
+}
+
+const WHITE: Rgb<u8> = Rgb([255, 255, 255]);
+const TICK_COLOR: Rgb<u8> = Rgb([160, 160, 160]);
+/// Cumulative curve over the white bars of a single channel
+const CURVE_COLOR: Rgb<u8> = Rgb([255, 160, 0]);
+/// Space left of and below the plot when axis ticks are drawn
+const TICK_MARGIN: u32 = 6;
+/// Space between the plots rendered side by side
+const COMPARISON_GAP: u32 = 8;
+
+/// Bar plot of the histogram of the image
+pub struct HistogramPlot {
     channel: HistogramChannelOptions,
+    width: u32,
+    height: u32,
+    log_scale: bool,
+    cumulative: bool,
+    ticks: bool,
 }
 
-impl HistogramConverter {
+impl HistogramPlot {
     pub fn new(channel: HistogramChannelOptions) -> Self {
-        Self { channel }
+        Self {
+            channel,
+            width: 256,
+            height: 100,
+            log_scale: false,
+            cumulative: false,
+            ticks: false,
+        }
+    }
+
+    pub fn with_size(self, width: u32, height: u32) -> Self {
+        assert!(width > 0 && height > 0, "Plot cannot be empty");
+        Self {
+            width,
+            height,
+            ..self
+        }
     }
-}
 
-const HISTOGRAM_HEIGHT: u32 = 100;
+    /// Heights of the bars proportional to the logarithm of the counts
+    pub fn with_log_scale(self, log_scale: bool) -> Self {
+        Self { log_scale, ..self }
+    }
 
-impl Transformation for HistogramConverter {
-    fn apply(&self, image: &mut RgbImage) {
-        let normalized_histogram = {
-            let mut histogram = Histogram::new(image);
-            let max_height = *histogram.iter().flat_map(|h| h.iter()).max().unwrap(); // cannot be empty
-            for channel in 0..3 {
-                let histogram = &mut histogram[channel as usize];
-                for x in histogram {
-                    *x = ((*x as f64) * HISTOGRAM_HEIGHT as f64 / (max_height as f64)) as u32
-                }
+    /// Overlays the cumulative distribution of every channel as a curve
+    pub fn with_cumulative(self, cumulative: bool) -> Self {
+        Self { cumulative, ..self }
+    }
+
+    /// Adds ticks every 32 values below the plot and every quarter of the height on its left
+    pub fn with_ticks(self, ticks: bool) -> Self {
+        Self { ticks, ..self }
+    }
+
+    pub fn render(&self, image: &RgbImage) -> RgbImage {
+        self.render_scaled(image, self.max_count(image))
+    }
+
+    /// Count of the highest bar, single channels share the scale of all three so that their plots can be compared
+    fn max_count(&self, image: &RgbImage) -> u32 {
+        let max_count = match self.channel {
+            HistogramChannelOptions::Luminance => {
+                Histogram::luminance(image).bins().iter().copied().max()
             }
-            histogram
+            _ => Histogram::new(image).iter().flatten().copied().max(),
         };
-        match &self.channel {
-            HistogramChannelOptions::All => {
-                let histogram_image = ImageBuffer::from_fn(256, HISTOGRAM_HEIGHT, |x, y| {
-                    let y = HISTOGRAM_HEIGHT - y;
-                    let mut pixel = [0u8; 3];
+        max_count.unwrap() // cannot be empty
+    }
+
+    fn render_scaled(&self, image: &RgbImage, max_count: u32) -> RgbImage {
+        let histograms = self.channel.histograms(image, &Histogram::new(image));
+        let scale = |count: u32| match self.log_scale {
+            true => (count as f64).ln_1p(),
+            false => count as f64,
+        };
+        let max_height = scale(max_count).max(f64::MIN_POSITIVE);
+
+        let column_bin = |x: u32| (x as usize * 256) / self.width as usize;
+        let mut plot = ImageBuffer::from_fn(self.width, self.height, |x, y| {
+            let y = self.height - y;
+            let mut pixel = [0u8; 3];
+            for (_, color, histogram) in &histograms {
+                let count = histogram.bins()[column_bin(x)];
+                let height = (scale(count) * self.height as f64 / max_height) as u32;
+                if y <= height {
                     for channel in 0..3 {
-                        let height = normalized_histogram[channel][x as usize];
-                        if y <= height {
-                            pixel[channel] = 255;
-                        }
+                        pixel[channel] |= color[channel];
                     }
-                    image::Rgb(pixel)
-                });
-                *image = histogram_image;
+                }
             }
-            _ => {
-                debug_assert!((self.channel as usize) < 3);
-                let normalized_histogram = normalized_histogram[self.channel as usize];
-                let histogram_image = ImageBuffer::from_fn(256, HISTOGRAM_HEIGHT, |x, y| {
-                    let height = normalized_histogram[x as usize];
-                    let y = HISTOGRAM_HEIGHT - y;
-                    if y <= height {
-                        image::Rgb([255, 255, 255])
-                    } else {
-                        image::Rgb([0, 0, 0])
+            Rgb(pixel)
+        });
+
+        if self.cumulative {
+            for (_, color, histogram) in &histograms {
+                let cdf = histogram.cdf();
+                // lighter than the bars of the same channel, so that the curve stays visible over them
+                let color = match *color == WHITE {
+                    true => CURVE_COLOR,
+                    false => Rgb(color.0.map(|value| value.max(128))),
+                };
+                let row = |x: u32| {
+                    let fraction = cdf[column_bin(x)];
+                    self.height - 1 - (fraction * (self.height - 1) as f64).round() as u32
+                };
+                for x in 0..self.width {
+                    let previous = row(x.saturating_sub(1));
+                    let current = row(x);
+                    // vertical segment joining the previous column, so that steep parts stay connected
+                    for y in current.min(previous)..=current.max(previous) {
+                        plot.put_pixel(x, y, color);
                     }
-                });
-                *image = histogram_image;
+                }
+            }
+        }
+
+        match self.ticks {
+            true => self.add_ticks(&plot),
+            false => plot,
+        }
+    }
+
+    fn add_ticks(&self, plot: &RgbImage) -> RgbImage {
+        let (width, height) = plot.dimensions();
+        let mut result = RgbImage::new(width + TICK_MARGIN, height + TICK_MARGIN);
+        image::imageops::replace(&mut result, plot, TICK_MARGIN as i64, 0);
+
+        for value in (0..=256).step_by(32) {
+            let x = TICK_MARGIN + (value * width / 256).min(width - 1);
+            let length = if value.is_multiple_of(128) {
+                TICK_MARGIN
+            } else {
+                TICK_MARGIN / 2
+            };
+            for y in height..height + length {
+                result.put_pixel(x, y, TICK_COLOR);
+            }
+        }
+        for quarter in 0..=4 {
+            let y = (height - 1) - quarter * (height - 1) / 4;
+            let length = if quarter.is_multiple_of(2) {
+                TICK_MARGIN
+            } else {
+                TICK_MARGIN / 2
+            };
+            for x in TICK_MARGIN - length..TICK_MARGIN {
+                result.put_pixel(x, y, TICK_COLOR);
             }
         }
+        result
+    }
+
+    /// Histograms of both images next to each other, on the same scale
+    pub fn render_comparison(&self, before: &RgbImage, after: &RgbImage) -> RgbImage {
+        let max_count = u32::max(self.max_count(before), self.max_count(after));
+        let (before, after) = (
+            self.render_scaled(before, max_count),
+            self.render_scaled(after, max_count),
+        );
+        let mut result = RgbImage::from_pixel(
+            before.width() + COMPARISON_GAP + after.width(),
+            before.height().max(after.height()),
+            TICK_COLOR,
+        );
+        image::imageops::replace(&mut result, &before, 0, 0);
+        image::imageops::replace(
+            &mut result,
+            &after,
+            (before.width() + COMPARISON_GAP) as i64,
+            0,
+        );
+        result
+    }
+}
+
+/// Named histograms of the selected channels, for exporting
+pub fn histogram_columns(
+    channel: HistogramChannelOptions,
+    image: &RgbImage,
+    prefix: &str,
+) -> Vec<(String, ChannelHistogram)> {
+    channel
+        .histograms(image, &Histogram::new(image))
+        .into_iter()
+        .map(|(name, _, histogram)| (format!("{}{}", prefix, name), histogram))
+        .collect()
+}
+
+/// Saves the counts as JSON if the file name ends with `.json`, or as CSV with a row per value otherwise
+pub fn export_histograms(path: &str, columns: &[(String, ChannelHistogram)]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Could not create {}: {}", path, e))?;
+    let mut writer = BufWriter::new(file);
+    let result = match path.to_lowercase().ends_with(".json") {
+        true => write_json(columns, &mut writer),
+        false => write_csv(columns, &mut writer),
+    };
+    result
+        .and_then(|_| writer.flush())
+        .map_err(|e| format!("Could not write {}: {}", path, e))
+}
+
+fn write_csv<W: Write>(
+    columns: &[(String, ChannelHistogram)],
+    writer: &mut W,
+) -> std::io::Result<()> {
+    let names: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+    writeln!(writer, "value,{}", names.join(","))?;
+    for value in 0..256 {
+        let counts: Vec<String> = columns
+            .iter()
+            .map(|(_, histogram)| histogram.bins()[value].to_string())
+            .collect();
+        writeln!(writer, "{},{}", value, counts.join(","))?;
+    }
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    columns: &[(String, ChannelHistogram)],
+    writer: &mut W,
+) -> std::io::Result<()> {
+    let optional = |value: Option<u32>| value.map_or("null".to_string(), |x| x.to_string());
+    writeln!(writer, "{{")?;
+    for (index, (name, histogram)) in columns.iter().enumerate() {
+        let counts: Vec<String> = histogram.bins().iter().map(|x| x.to_string()).collect();
+        let mean = histogram.mean();
+        writeln!(writer, "  \"{}\": {{", name)?;
+        writeln!(writer, "    \"count\": {},", histogram.count())?;
+        writeln!(writer, "    \"min\": {},", optional(histogram.min()))?;
+        writeln!(writer, "    \"max\": {},", optional(histogram.max()))?;
+        writeln!(writer, "    \"median\": {},", optional(histogram.median()))?;
+        writeln!(writer, "    \"mode\": {},", optional(histogram.mode()))?;
+        match mean.is_finite() {
+            true => writeln!(writer, "    \"mean\": {:.4},", mean)?,
+            false => writeln!(writer, "    \"mean\": null,")?,
+        }
+        writeln!(writer, "    \"counts\": [{}]", counts.join(", "))?;
+        let separator = if index + 1 < columns.len() { "," } else { "" };
+        writeln!(writer, "  }}{}", separator)?;
+    }
+    writeln!(writer, "}}")
+}
+
+/// Replaces the image with the plot of its histogram
+pub struct HistogramConverter {
+    plot: HistogramPlot,
+}
+
+impl HistogramConverter {
+    pub fn new(channel: HistogramChannelOptions) -> Self {
+        Self {
+            plot: HistogramPlot::new(channel),
+        }
+    }
+
+    pub fn with_plot(self, plot: HistogramPlot) -> Self {
+        Self { plot }
+    }
+}
+
+impl Transformation for HistogramConverter {
+    fn apply(&self, image: &mut RgbImage) {
+        *image = self.plot.render(image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column_height(plot: &RgbImage, x: u32) -> usize {
+        (0..plot.height())
+            .filter(|&y| *plot.get_pixel(x, y) != Rgb([0, 0, 0]))
+            .count()
+    }
+
+    #[test]
+    fn plot_has_full_bar_at_the_only_value() {
+        let image = RgbImage::from_pixel(4, 4, Rgb([10, 200, 30]));
+
+        let plot = HistogramPlot::new(HistogramChannelOptions::R).render(&image);
+
+        assert_eq!((256, 100), plot.dimensions());
+        assert_eq!(100, column_height(&plot, 10));
+        assert_eq!(0, column_height(&plot, 11));
+        assert_eq!(WHITE, *plot.get_pixel(10, 0));
+    }
+
+    #[test]
+    fn log_scale_raises_small_counts() {
+        let image = RgbImage::from_fn(10, 10, |x, y| match (x, y) {
+            (0, 0) => Rgb([0; 3]),
+            _ => Rgb([255; 3]),
+        });
+        let plot = HistogramPlot::new(HistogramChannelOptions::R);
+
+        let linear = plot.render(&image);
+        let log = HistogramPlot::new(HistogramChannelOptions::R)
+            .with_log_scale(true)
+            .render(&image);
+
+        assert_eq!(1, column_height(&linear, 0));
+        assert_eq!(15, column_height(&log, 0));
+        assert_eq!(100, column_height(&log, 255));
+    }
+
+    #[test]
+    fn ticks_and_comparison_extend_the_plot() {
+        let image = RgbImage::from_pixel(4, 4, Rgb([10, 200, 30]));
+        let plot = HistogramPlot::new(HistogramChannelOptions::All).with_size(128, 50);
+
+        let ticked = HistogramPlot::new(HistogramChannelOptions::All)
+            .with_size(128, 50)
+            .with_ticks(true)
+            .render(&image);
+        let comparison = plot.render_comparison(&image, &image);
+
+        assert_eq!((128 + TICK_MARGIN, 50 + TICK_MARGIN), ticked.dimensions());
+        assert_eq!((2 * 128 + COMPARISON_GAP, 50), comparison.dimensions());
+        assert_eq!(TICK_COLOR, *comparison.get_pixel(128, 0));
+    }
+
+    fn export(file_name: &str, image: &RgbImage) -> String {
+        let path = std::env::temp_dir().join(file_name);
+        let path = path.to_str().unwrap();
+        let columns = histogram_columns(HistogramChannelOptions::All, image, "before_");
+        export_histograms(path, &columns).unwrap();
+        let text = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        text
+    }
+
+    #[test]
+    fn csv_export_has_a_row_per_value() {
+        let image = RgbImage::from_pixel(4, 4, Rgb([10, 200, 30]));
+
+        let text = export("histogram_export_test.csv", &image);
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(257, lines.len());
+        assert_eq!("value,before_red,before_green,before_blue", lines[0]);
+        assert_eq!("10,16,0,0", lines[11]);
+        assert_eq!("200,0,16,0", lines[201]);
+    }
+
+    #[test]
+    fn json_export_has_statistics_and_counts() {
+        let image = RgbImage::from_pixel(4, 4, Rgb([10, 200, 30]));
+
+        let text = export("histogram_export_test.json", &image);
+
+        assert!(text.starts_with("{\n  \"before_red\": {\n    \"count\": 16,\n"));
+        assert!(text.contains("    \"median\": 200,\n    \"mode\": 200,\n    \"mean\": 200.0000,"));
+        let counts = text.lines().find(|line| line.contains("counts")).unwrap();
+        assert_eq!(256, counts.matches(',').count() + 1);
+        assert!(text.trim_end().ends_with("}\n}"));
     }
 }
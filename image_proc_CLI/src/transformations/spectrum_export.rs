@@ -0,0 +1,39 @@
+use image::RgbImage;
+use image_proc::modifications::frequency_domain::image_transformations::image_fourier_transforms::{
+    ImageFourierTransform, SpectrumOptions,
+};
+use image_proc::modifications::frequency_domain::image_transformations::spectrum_file::write_spectrum;
+use image_proc::modifications::Transformation;
+use std::fs::File;
+use std::io::BufWriter;
+use std::marker::PhantomData;
+
+/// Renders the spectrum like the transform itself and saves the raw complex values to a file
+pub struct SpectrumExport<T> {
+    options: SpectrumOptions,
+    path: String,
+    transform: PhantomData<T>,
+}
+
+impl<T> SpectrumExport<T> {
+    pub fn new(options: SpectrumOptions, path: &str) -> Self {
+        Self {
+            options,
+            path: path.to_string(),
+            transform: PhantomData,
+        }
+    }
+}
+
+impl<T: ImageFourierTransform> Transformation for SpectrumExport<T> {
+    fn apply(&self, image: &mut RgbImage) {
+        let spectrum = T::transform(image);
+        let result = File::create(&self.path)
+            .and_then(|file| write_spectrum(&spectrum, &mut BufWriter::new(file)));
+        match result {
+            Ok(_) => println!("Saved spectrum to {}", self.path),
+            Err(error) => eprintln!("Error while saving spectrum: {}", error),
+        }
+        *image = self.options.render(&spectrum);
+    }
+}
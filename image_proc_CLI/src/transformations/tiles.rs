@@ -0,0 +1,84 @@
+use image::RgbImage;
+use image_proc::modifications::geometric::{assemble_tiles, split_into_tiles};
+use image_proc::modifications::Transformation;
+
+/// Path of the file with a single tile, `<prefix>_<row>_<column>.png`
+pub fn tile_path(prefix: &str, row: u32, column: u32) -> String {
+    format!("{}_{}_{}.png", prefix, row, column)
+}
+
+/// Saves tiles of the image to files, leaving the image unchanged
+pub struct TileSplit {
+    width: u32,
+    height: u32,
+    prefix: String,
+}
+
+impl TileSplit {
+    pub fn new(width: u32, height: u32, prefix: &str) -> Self {
+        Self {
+            width,
+            height,
+            prefix: prefix.to_string(),
+        }
+    }
+}
+
+impl Transformation for TileSplit {
+    fn apply(&self, image: &mut RgbImage) {
+        let tiles = split_into_tiles(image, self.width, self.height);
+        for tile in &tiles {
+            let path = tile_path(&self.prefix, tile.y / self.height, tile.x / self.width);
+            if let Err(error) = tile.image.save(&path) {
+                eprintln!("Error while saving tile {}: {}", path, error);
+                return;
+            }
+        }
+        println!("Saved {} tiles to {}_*.png", tiles.len(), self.prefix);
+    }
+}
+
+/// Replaces tiles of the image with the files saved by [`TileSplit`] with the same tile size,
+/// tiles without a file are kept from the image
+pub struct TileAssemble {
+    width: u32,
+    height: u32,
+    prefix: String,
+}
+
+impl TileAssemble {
+    pub fn new(width: u32, height: u32, prefix: &str) -> Self {
+        Self {
+            width,
+            height,
+            prefix: prefix.to_string(),
+        }
+    }
+}
+
+impl Transformation for TileAssemble {
+    fn apply(&self, image: &mut RgbImage) {
+        let mut tiles = split_into_tiles(image, self.width, self.height);
+        let mut replaced = 0;
+        for tile in tiles.iter_mut() {
+            let path = tile_path(&self.prefix, tile.y / self.height, tile.x / self.width);
+            if !std::path::Path::new(&path).exists() {
+                continue;
+            }
+            match image::open(&path).map(|replacement| replacement.to_rgb8()) {
+                Ok(replacement) if replacement.dimensions() != tile.image.dimensions() => {
+                    eprintln!("Error: tile {} has a different size, keeping it", path)
+                }
+                Ok(replacement) => {
+                    tile.image = replacement;
+                    replaced += 1;
+                }
+                Err(error) => eprintln!("Error: {}", error),
+            }
+        }
+        match replaced {
+            0 => eprintln!("Error: no tile files found with prefix {}", self.prefix),
+            _ => *image = assemble_tiles(&tiles),
+        }
+    }
+}